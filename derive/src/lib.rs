@@ -0,0 +1,174 @@
+//! Derive macros for sparkplug-rs.
+//!
+//! Use through the `derive` feature of `sparkplug-rs` rather than depending
+//! on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitInt, LitStr};
+
+/// Derives `sparkplug_rs::SparkplugMetrics` for a struct with named fields.
+///
+/// Field attributes (all optional):
+///
+/// - `#[sparkplug(name = "DATA/Speed")]` — metric name (defaults to the field name)
+/// - `#[sparkplug(alias = 1)]` — metric alias used in births and data
+/// - `#[sparkplug(readonly)]` — ignore this field in `apply_command`
+/// - `#[sparkplug(skip)]` — not a metric
+#[proc_macro_derive(SparkplugMetrics, attributes(sparkplug))]
+pub fn derive_sparkplug_metrics(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct FieldSpec {
+    ident: Ident,
+    name: String,
+    alias: Option<u64>,
+    readonly: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "SparkplugMetrics requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "SparkplugMetrics can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut specs = Vec::new();
+    for field in fields {
+        let field_ident = field.ident.clone().expect("named field");
+        let mut spec = FieldSpec {
+            name: field_ident.to_string(),
+            ident: field_ident,
+            alias: None,
+            readonly: false,
+        };
+        let mut skip = false;
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("sparkplug"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    spec.name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("alias") {
+                    spec.alias = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("readonly") {
+                    spec.readonly = true;
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else {
+                    return Err(meta.error("unknown sparkplug attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if !skip {
+            specs.push(spec);
+        }
+    }
+
+    let birth = specs.iter().map(|s| {
+        let (f, name, alias) = (&s.ident, &s.name, option_tokens(s.alias));
+        quote! {
+            ::sparkplug_rs::MetricField::add_to_birth(&self.#f, &mut builder, #name, #alias)?;
+        }
+    });
+
+    let data = specs.iter().map(|s| {
+        let (f, name, alias) = (&s.ident, &s.name, option_tokens(s.alias));
+        quote! {
+            if self.#f != previous.#f {
+                ::sparkplug_rs::MetricField::add_to_data(&self.#f, &mut builder, #name, #alias)?;
+                changed = true;
+            }
+        }
+    });
+
+    let commands = specs.iter().filter(|s| !s.readonly).map(|s| {
+        let (f, name) = (&s.ident, &s.name);
+        let alias_match = match s.alias {
+            Some(alias) => quote! { || alias == ::core::option::Option::Some(#alias) },
+            None => quote! {},
+        };
+        quote! {
+            if name == ::core::option::Option::Some(#name) #alias_match {
+                if let ::core::option::Option::Some(value) =
+                    ::sparkplug_rs::MetricField::from_metric_value(&metric.value)
+                {
+                    self.#f = value;
+                    applied += 1;
+                }
+                continue;
+            }
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::sparkplug_rs::SparkplugMetrics for #ident #ty_generics #where_clause {
+            fn to_birth_payload(&self) -> ::sparkplug_rs::Result<::sparkplug_rs::PayloadBuilder> {
+                let mut builder = ::sparkplug_rs::PayloadBuilder::new()?;
+                #(#birth)*
+                ::core::result::Result::Ok(builder)
+            }
+
+            fn to_data_payload(
+                &self,
+                previous: &Self,
+            ) -> ::sparkplug_rs::Result<::core::option::Option<::sparkplug_rs::PayloadBuilder>> {
+                let mut builder = ::sparkplug_rs::PayloadBuilder::new()?;
+                let mut changed = false;
+                #(#data)*
+                ::core::result::Result::Ok(if changed {
+                    ::core::option::Option::Some(builder)
+                } else {
+                    ::core::option::Option::None
+                })
+            }
+
+            #[allow(unused_variables, unused_mut)]
+            fn apply_command(
+                &mut self,
+                payload: &::sparkplug_rs::Payload,
+            ) -> ::sparkplug_rs::Result<usize> {
+                let mut applied = 0;
+                for metric in payload.metrics() {
+                    let metric = metric?;
+                    let name = metric.name.as_deref();
+                    let alias = metric.alias.map(|a| a.value());
+                    #(#commands)*
+                }
+                ::core::result::Result::Ok(applied)
+            }
+        }
+    })
+}
+
+fn option_tokens(alias: Option<u64>) -> TokenStream2 {
+    match alias {
+        Some(alias) => quote! { ::core::option::Option::Some(#alias) },
+        None => quote! { ::core::option::Option::None },
+    }
+}
@@ -0,0 +1,56 @@
+//! Sparkplug B CSV Export Example
+//!
+//! Subscribes to a group and writes every decoded metric to stdout as CSV,
+//! for quick ad-hoc analysis of live metric streams.
+//!
+//! Usage: cargo run --example csv_export -- [group_id] [broker_url] > metrics.csv
+
+use sparkplug_rs::{CsvWriter, Message, Result, Subscriber, SubscriberConfig};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let group_id = args.get(1).cloned().unwrap_or_else(|| "Energy".to_string());
+    let broker_url = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| "tcp://localhost:1883".to_string());
+
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || r.store(false, Ordering::SeqCst))
+        .expect("Error setting Ctrl-C handler");
+
+    let csv = Arc::new(Mutex::new(CsvWriter::new(std::io::stdout())));
+    csv.lock().unwrap().write_header()?;
+
+    let csv_clone = csv.clone();
+    let config = SubscriberConfig::new(broker_url, "rust_csv_export", group_id.as_str());
+    let mut subscriber = Subscriber::new(
+        config,
+        Box::new(move |msg: Message| {
+            let mut csv = csv_clone.lock().unwrap();
+            if let Err(e) = csv.write_message(&msg) {
+                eprintln!("Skipping message on {}: {}", msg.topic, e);
+            }
+        }),
+    )?;
+
+    subscriber.connect()?;
+    subscriber.subscribe_all()?;
+    eprintln!(
+        "Exporting spBv1.0/{}/# as CSV (Ctrl+C to stop)...",
+        group_id
+    );
+
+    while running.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    subscriber.disconnect()?;
+    csv.lock().unwrap().flush()?;
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use sparkplug_rs::{
-    Message, PayloadBuilder, Publisher, PublisherConfig, Result, Subscriber, SubscriberConfig,
+    Message, MetricAlias, PayloadBuilder, Publisher, PublisherConfig, Result, Subscriber,
+    SubscriberConfig,
 };
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -42,10 +43,7 @@ impl BatteryState {
                 self.power = sp.clamp(-self.nominal_power_kw, self.nominal_power_kw);
             }
         } else {
-            let secs_since_epoch = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            let secs_since_epoch = sparkplug_rs::time::now_millis() / 1000;
             let hour = (secs_since_epoch / 3600) % 24;
             let minute = (secs_since_epoch / 60) % 60;
             let time_of_day = hour as f64 + minute as f64 / 60.0;
@@ -128,56 +126,116 @@ fn publish_device_births(
 ) -> Result<()> {
     let mut poc_birth = PayloadBuilder::new()?;
     poc_birth
-        .add_double_with_alias("DATA/POC_P_ACT", 100, state.poc_power())?
-        .add_double_with_alias("DATA/POC_Q_ACT", 101, 0.0)?
-        .add_double_with_alias("DATA/POC_V_ACT", 102, 230.0)?
-        .add_double_with_alias("DATA/POC_F_ACT", 103, 50.0)?
-        .add_bool_with_alias("DATA/POC_METER_AVAIL", 104, true)?;
+        .add_double_with_alias(
+            "DATA/POC_P_ACT",
+            publisher.alias("DATA/POC_P_ACT")?,
+            state.poc_power(),
+        )?
+        .add_double_with_alias("DATA/POC_Q_ACT", publisher.alias("DATA/POC_Q_ACT")?, 0.0)?
+        .add_double_with_alias("DATA/POC_V_ACT", publisher.alias("DATA/POC_V_ACT")?, 230.0)?
+        .add_double_with_alias("DATA/POC_F_ACT", publisher.alias("DATA/POC_F_ACT")?, 50.0)?
+        .add_bool_with_alias(
+            "DATA/POC_METER_AVAIL",
+            publisher.alias("DATA/POC_METER_AVAIL")?,
+            true,
+        )?;
     let poc_bytes = poc_birth.serialize()?;
     publisher.publish_device_birth("POC", &poc_bytes)?;
 
     let mut bess_birth = PayloadBuilder::new()?;
     bess_birth
-        .add_double_with_alias("DATA/BESS_SOC_ACT", 200, state.soc)?
-        .add_double_with_alias("DATA/BESS_SOH_ACT", 201, 95.0)?
-        .add_double_with_alias("DATA/BESS_E_ACT", 202, state.stored_energy_kwh())?
-        .add_double_with_alias("DATA/BESS_E_CAP_AVAIL_ACT", 203, state.nominal_capacity_kwh)?
+        .add_double_with_alias(
+            "DATA/BESS_SOC_ACT",
+            publisher.alias("DATA/BESS_SOC_ACT")?,
+            state.soc,
+        )?
+        .add_double_with_alias(
+            "DATA/BESS_SOH_ACT",
+            publisher.alias("DATA/BESS_SOH_ACT")?,
+            95.0,
+        )?
+        .add_double_with_alias(
+            "DATA/BESS_E_ACT",
+            publisher.alias("DATA/BESS_E_ACT")?,
+            state.stored_energy_kwh(),
+        )?
+        .add_double_with_alias(
+            "DATA/BESS_E_CAP_AVAIL_ACT",
+            publisher.alias("DATA/BESS_E_CAP_AVAIL_ACT")?,
+            state.nominal_capacity_kwh,
+        )?
         .add_double_with_alias(
             "DATA/BESS_E_DISCHARGE_AVAIL_ACT",
-            204,
+            publisher.alias("DATA/BESS_E_DISCHARGE_AVAIL_ACT")?,
             state.discharge_avail_kwh(),
         )?
         .add_double_with_alias(
             "DATA/BESS_E_CHARGE_AVAIL_ACT",
-            205,
+            publisher.alias("DATA/BESS_E_CHARGE_AVAIL_ACT")?,
             state.charge_avail_kwh(),
         )?
-        .add_bool_with_alias("DATA/BESS_AVAIL", 206, true)?
-        .add_double_with_alias("DATA/BESS_P_ACT", 207, state.power)?
-        .add_double_with_alias("DATA/BESS_Q_ACT", 208, 0.0)?
-        .add_double_with_alias("DATA/BESS_P_NOM_ACT", 209, state.nominal_power_kw)?
-        .add_double_with_alias("DATA/BESS_Q_NOM_ACT", 210, 0.0)?
-        .add_double_with_alias("DATA/BESS_P_LIM_MAX_ACT", 211, state.nominal_power_kw)?
-        .add_double_with_alias("DATA/BESS_P_LIM_MIN_ACT", 212, -state.nominal_power_kw)?;
+        .add_bool_with_alias("DATA/BESS_AVAIL", publisher.alias("DATA/BESS_AVAIL")?, true)?
+        .add_double_with_alias(
+            "DATA/BESS_P_ACT",
+            publisher.alias("DATA/BESS_P_ACT")?,
+            state.power,
+        )?
+        .add_double_with_alias("DATA/BESS_Q_ACT", publisher.alias("DATA/BESS_Q_ACT")?, 0.0)?
+        .add_double_with_alias(
+            "DATA/BESS_P_NOM_ACT",
+            publisher.alias("DATA/BESS_P_NOM_ACT")?,
+            state.nominal_power_kw,
+        )?
+        .add_double_with_alias(
+            "DATA/BESS_Q_NOM_ACT",
+            publisher.alias("DATA/BESS_Q_NOM_ACT")?,
+            0.0,
+        )?
+        .add_double_with_alias(
+            "DATA/BESS_P_LIM_MAX_ACT",
+            publisher.alias("DATA/BESS_P_LIM_MAX_ACT")?,
+            state.nominal_power_kw,
+        )?
+        .add_double_with_alias(
+            "DATA/BESS_P_LIM_MIN_ACT",
+            publisher.alias("DATA/BESS_P_LIM_MIN_ACT")?,
+            -state.nominal_power_kw,
+        )?;
     let bess_bytes = bess_birth.serialize()?;
     publisher.publish_device_birth("BESS", &bess_bytes)?;
 
     let mut pv_birth = PayloadBuilder::new()?;
     pv_birth
-        .add_double_with_alias("DATA/PV_P_ACT", 300, state.pv_power)?
-        .add_double_with_alias("DATA/PV_Q_ACT", 301, 0.0)?
-        .add_double_with_alias("DATA/PV_P_NOM_ACT", 302, state.pv_nominal_kw)?
-        .add_double_with_alias("DATA/PV_P_LIM_MAX_ACT", 303, state.pv_nominal_kw)?
-        .add_bool_with_alias("DATA/PV_AVAIL", 304, true)?;
+        .add_double_with_alias(
+            "DATA/PV_P_ACT",
+            publisher.alias("DATA/PV_P_ACT")?,
+            state.pv_power,
+        )?
+        .add_double_with_alias("DATA/PV_Q_ACT", publisher.alias("DATA/PV_Q_ACT")?, 0.0)?
+        .add_double_with_alias(
+            "DATA/PV_P_NOM_ACT",
+            publisher.alias("DATA/PV_P_NOM_ACT")?,
+            state.pv_nominal_kw,
+        )?
+        .add_double_with_alias(
+            "DATA/PV_P_LIM_MAX_ACT",
+            publisher.alias("DATA/PV_P_LIM_MAX_ACT")?,
+            state.pv_nominal_kw,
+        )?
+        .add_bool_with_alias("DATA/PV_AVAIL", publisher.alias("DATA/PV_AVAIL")?, true)?;
     let pv_bytes = pv_birth.serialize()?;
     publisher.publish_device_birth("PV", &pv_bytes)?;
 
     let mut ctrl_birth = PayloadBuilder::new()?;
     ctrl_birth
-        .add_bool_with_alias("DATA/BESS_P_CTRL_MODE_EN_ACT", 400, state.control_enabled)?
+        .add_bool_with_alias(
+            "DATA/BESS_P_CTRL_MODE_EN_ACT",
+            publisher.alias("DATA/BESS_P_CTRL_MODE_EN_ACT")?,
+            state.control_enabled,
+        )?
         .add_double_with_alias(
             "DATA/BESS_P_CTRL_SP_ACT",
-            401,
+            publisher.alias("DATA/BESS_P_CTRL_SP_ACT")?,
             state.power_setpoint.unwrap_or(0.0),
         )?;
     let ctrl_bytes = ctrl_birth.serialize()?;
@@ -193,29 +251,55 @@ fn publish_device_births(
     Ok(())
 }
 
+/// Aliases of the metrics published on every scan, resolved once after the births
+struct DataAliases {
+    poc_p: MetricAlias,
+    bess_soc: MetricAlias,
+    bess_e: MetricAlias,
+    bess_e_discharge: MetricAlias,
+    bess_e_charge: MetricAlias,
+    bess_p: MetricAlias,
+    pv_p: MetricAlias,
+}
+
+impl DataAliases {
+    fn resolve(publisher: &mut Publisher) -> Result<Self> {
+        Ok(Self {
+            poc_p: publisher.alias("DATA/POC_P_ACT")?,
+            bess_soc: publisher.alias("DATA/BESS_SOC_ACT")?,
+            bess_e: publisher.alias("DATA/BESS_E_ACT")?,
+            bess_e_discharge: publisher.alias("DATA/BESS_E_DISCHARGE_AVAIL_ACT")?,
+            bess_e_charge: publisher.alias("DATA/BESS_E_CHARGE_AVAIL_ACT")?,
+            bess_p: publisher.alias("DATA/BESS_P_ACT")?,
+            pv_p: publisher.alias("DATA/PV_P_ACT")?,
+        })
+    }
+}
+
 fn publish_data(
     publisher: &mut Publisher,
+    aliases: &DataAliases,
     state: &BatteryState,
     node: &str,
     verbose: bool,
 ) -> Result<()> {
     let mut poc_data = PayloadBuilder::new()?;
-    poc_data.add_double_by_alias(100, state.poc_power());
+    poc_data.add_double_by_alias(aliases.poc_p, state.poc_power());
     let poc_bytes = poc_data.serialize()?;
     publisher.publish_device_data("POC", &poc_bytes)?;
 
     let mut bess_data = PayloadBuilder::new()?;
     bess_data
-        .add_double_by_alias(200, state.soc)
-        .add_double_by_alias(202, state.stored_energy_kwh())
-        .add_double_by_alias(204, state.discharge_avail_kwh())
-        .add_double_by_alias(205, state.charge_avail_kwh())
-        .add_double_by_alias(207, state.power);
+        .add_double_by_alias(aliases.bess_soc, state.soc)
+        .add_double_by_alias(aliases.bess_e, state.stored_energy_kwh())
+        .add_double_by_alias(aliases.bess_e_discharge, state.discharge_avail_kwh())
+        .add_double_by_alias(aliases.bess_e_charge, state.charge_avail_kwh())
+        .add_double_by_alias(aliases.bess_p, state.power);
     let bess_bytes = bess_data.serialize()?;
     publisher.publish_device_data("BESS", &bess_bytes)?;
 
     let mut pv_data = PayloadBuilder::new()?;
-    pv_data.add_double_by_alias(300, state.pv_power);
+    pv_data.add_double_by_alias(aliases.pv_p, state.pv_power);
     let pv_bytes = pv_data.serialize()?;
     publisher.publish_device_data("PV", &pv_bytes)?;
 
@@ -321,6 +405,8 @@ fn main() -> Result<()> {
         "VPP4S_R2",
         "CBHS01",
     )?;
+    let bal01_aliases = DataAliases::resolve(&mut bal01_pub)?;
+    let cbhs01_aliases = DataAliases::resolve(&mut cbhs01_pub)?;
 
     println!("\nPublishing telemetry (Ctrl+C to stop)...\n");
 
@@ -348,13 +434,25 @@ fn main() -> Result<()> {
         {
             let mut state = bal01_state.lock().unwrap();
             state.update(5.0);
-            publish_data(&mut bal01_pub, &state, "VPP_R2/BAL01", counter % 6 == 0)?;
+            publish_data(
+                &mut bal01_pub,
+                &bal01_aliases,
+                &state,
+                "VPP_R2/BAL01",
+                counter % 6 == 0,
+            )?;
         }
 
         {
             let mut state = cbhs01_state.lock().unwrap();
             state.update(5.0);
-            publish_data(&mut cbhs01_pub, &state, "VPP4S_R2/CBHS01", counter % 6 == 0)?;
+            publish_data(
+                &mut cbhs01_pub,
+                &cbhs01_aliases,
+                &state,
+                "VPP4S_R2/CBHS01",
+                counter % 6 == 0,
+            )?;
         }
 
         if counter % 6 == 0 {
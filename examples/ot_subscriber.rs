@@ -1,12 +1,12 @@
 use sparkplug_rs::{
-    Message, MetricAlias, PayloadBuilder, Publisher, PublisherConfig, Result, Subscriber,
-    SubscriberConfig,
+    HostApplication, HostApplicationConfig, Message, Result, SequenceStatus, SequenceTracker,
+    Subscriber, SubscriberConfig,
 };
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime};
 
 fn timestamp() -> String {
     let now = chrono::Local::now();
@@ -16,9 +16,7 @@ fn timestamp() -> String {
 #[derive(Debug, Clone)]
 struct NodeState {
     last_seen: SystemTime,
-    last_seq: Option<u64>,
-    metrics: HashMap<String, f64>,
-    aliases: HashMap<MetricAlias, String>,
+    sequence: SequenceTracker,
     online: bool,
 }
 
@@ -26,9 +24,7 @@ impl NodeState {
     fn new() -> Self {
         Self {
             last_seen: SystemTime::now(),
-            last_seq: None,
-            metrics: HashMap::new(),
-            aliases: HashMap::new(),
+            sequence: SequenceTracker::new(),
             online: false,
         }
     }
@@ -40,14 +36,8 @@ fn main() -> Result<()> {
     println!("OT Subscriber - Monitoring Tool");
     println!("================================\n");
 
-    // Get timestamp for STATE messages (must be consistent for birth and death)
-    let state_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("System time before UNIX epoch")
-        .as_millis() as u64;
-
     // Generate unique instance ID for MQTT client IDs (prevents collision when running multiple instances)
-    let instance_id = state_timestamp % 100000; // Use last 5 digits of timestamp
+    let instance_id = sparkplug_rs::time::now_millis() % 100000; // Use last 5 digits of timestamp
 
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
@@ -56,12 +46,14 @@ fn main() -> Result<()> {
 
     let nodes: NodeMap = Arc::new(Mutex::new(HashMap::new()));
 
+    // The subscribers keep the latest metric values for the status report
     let nodes_clone = nodes.clone();
-    let vpp_r2_config = SubscriberConfig::new(
+    let mut vpp_r2_config = SubscriberConfig::new(
         "tcp://localhost:1883",
         format!("ot_monitor_r2_{}", instance_id),
         "VPP_R2",
     );
+    vpp_r2_config.store_values = true;
     let mut vpp_r2_sub = Subscriber::new(
         vpp_r2_config,
         Box::new(move |msg: Message| {
@@ -73,11 +65,12 @@ fn main() -> Result<()> {
     println!("[{}] [OK] Subscribed to VPP_R2/#", timestamp());
 
     let nodes_clone2 = nodes.clone();
-    let vpp4s_r2_config = SubscriberConfig::new(
+    let mut vpp4s_r2_config = SubscriberConfig::new(
         "tcp://localhost:1883",
         format!("ot_monitor_4s_{}", instance_id),
         "VPP4S_R2",
     );
+    vpp4s_r2_config.store_values = true;
     let mut vpp4s_r2_sub = Subscriber::new(
         vpp4s_r2_config,
         Box::new(move |msg: Message| {
@@ -88,41 +81,25 @@ fn main() -> Result<()> {
     vpp4s_r2_sub.subscribe_all()?;
     println!("[{}] [OK] Subscribed to VPP4S_R2/#", timestamp());
 
-    let cmd_pub_r2_config = PublisherConfig::new(
-        "tcp://localhost:1883",
-        format!("ot_monitor_cmd_r2_{}", instance_id),
-        "VPP_R2",
-        "MONITOR",
-    );
-    let mut cmd_pub_r2 = Publisher::new(cmd_pub_r2_config)?;
-    cmd_pub_r2.connect()?;
-
-    // Publish STATE birth for Host Application (Sparkplug B 2.2 spec)
-    cmd_pub_r2.publish_state_birth("MONITOR", state_timestamp)?;
-    println!(
-        "[{}] [VPP_R2] Published STATE birth for MONITOR",
-        timestamp()
-    );
-
-    let cmd_pub_4s_config = PublisherConfig::new(
+    let host_config = HostApplicationConfig::new(
         "tcp://localhost:1883",
-        format!("ot_monitor_cmd_4s_{}", instance_id),
-        "VPP4S_R2",
+        format!("ot_monitor_cmd_{}", instance_id),
         "MONITOR",
+        ["VPP_R2", "VPP4S_R2"],
     );
-    let mut cmd_pub_4s = Publisher::new(cmd_pub_4s_config)?;
-    cmd_pub_4s.connect()?;
+    let mut host = HostApplication::new(host_config)?;
 
-    // Publish STATE birth for Host Application (Sparkplug B 2.2 spec)
-    cmd_pub_4s.publish_state_birth("MONITOR", state_timestamp)?;
+    // Connecting publishes the STATE birth for the Host Application (Sparkplug B 2.2 spec)
+    host.connect()?;
     println!(
-        "[{}] [VPP4S_R2] Published STATE birth for MONITOR",
-        timestamp()
+        "[{}] Published STATE birth for {}",
+        timestamp(),
+        host.host_id()
     );
 
     println!("\nSending rebirth requests to known nodes...");
-    send_rebirth_request(&mut cmd_pub_r2, "BAL01")?;
-    send_rebirth_request(&mut cmd_pub_4s, "CBHS01")?;
+    send_rebirth_request(&mut host, "VPP_R2", "BAL01")?;
+    send_rebirth_request(&mut host, "VPP4S_R2", "CBHS01")?;
     println!("Rebirth requests sent\n");
 
     println!("Monitoring messages (Ctrl+C to stop)\n");
@@ -134,7 +111,7 @@ fn main() -> Result<()> {
         counter += 1;
 
         if counter % 30 == 0 {
-            print_status(&nodes);
+            print_status(&nodes, &[&vpp_r2_sub, &vpp4s_r2_sub]);
         }
 
         check_stale_data(&nodes);
@@ -147,26 +124,17 @@ fn main() -> Result<()> {
     vpp_r2_sub.disconnect()?;
     vpp4s_r2_sub.disconnect()?;
 
-    // Publish STATE death for Host Applications (Sparkplug B 2.2 spec requirement)
-    println!("[{}] Publishing STATE death messages...", timestamp());
-    cmd_pub_r2.publish_state_death("MONITOR", state_timestamp)?;
-    cmd_pub_4s.publish_state_death("MONITOR", state_timestamp)?;
-
-    // Disconnect publishers
-    cmd_pub_r2.disconnect()?;
-    cmd_pub_4s.disconnect()?;
+    // Disconnecting publishes the STATE death (Sparkplug B 2.2 spec requirement)
+    println!("[{}] Publishing STATE death message...", timestamp());
+    host.disconnect()?;
 
     println!("[{}] Disconnected gracefully", timestamp());
 
     Ok(())
 }
 
-fn send_rebirth_request(publisher: &mut Publisher, node: &str) -> Result<()> {
-    let mut payload = PayloadBuilder::new()?;
-    payload.add_bool("Node Control/Rebirth", true)?;
-    let payload_bytes = payload.serialize()?;
-
-    publisher.publish_node_command(node, &payload_bytes)?;
+fn send_rebirth_request(host: &mut HostApplication, group: &str, node: &str) -> Result<()> {
+    host.request_rebirth(group, node)?;
     println!("[{}]   → Sent rebirth request to {}", timestamp(), node);
     Ok(())
 }
@@ -187,52 +155,22 @@ fn handle_message(msg: &Message, nodes: &NodeMap, group: &str) {
 
                     if let Ok(payload) = msg.parse_payload() {
                         if let Some(seq) = payload.seq() {
-                            node.last_seq = Some(seq);
-                        }
-
-                        for metric in payload.metrics().flatten() {
-                            if let Some(name) = &metric.name {
-                                if let Some(alias) = metric.alias {
-                                    node.aliases.insert(alias, name.clone());
-                                }
-                                if let Some(val) = extract_double(&metric.value) {
-                                    node.metrics.insert(name.clone(), val);
-                                }
-                            }
+                            node.sequence.check(msg_type, seq);
                         }
                     }
                 } else if msg_type.is_data() {
                     if let Ok(payload) = msg.parse_payload() {
                         if let Some(seq) = payload.seq() {
-                            if let Some(last_seq) = node.last_seq {
-                                // Sparkplug B sequence numbers are 0-255 (wraps at 256)
-                                let expected_seq = (last_seq + 1) % 256;
-                                if seq != last_seq && seq != expected_seq {
-                                    println!(
-                                        "[{}] [{}] SEQUENCE GAP: expected {}, got {}",
-                                        timestamp(),
-                                        key,
-                                        expected_seq,
-                                        seq
-                                    );
-                                }
-                            }
-                            node.last_seq = Some(seq);
-                        }
-
-                        for metric in payload.metrics().flatten() {
-                            let metric_name = if let Some(name) = &metric.name {
-                                Some(name.clone())
-                            } else if let Some(alias) = metric.alias {
-                                node.aliases.get(&alias).cloned()
-                            } else {
-                                None
-                            };
-
-                            if let Some(name) = metric_name {
-                                if let Some(val) = extract_double(&metric.value) {
-                                    node.metrics.insert(name, val);
-                                }
+                            if let SequenceStatus::Gap { expected, received } =
+                                node.sequence.check(msg_type, seq)
+                            {
+                                println!(
+                                    "[{}] [{}] SEQUENCE GAP: expected {}, got {}",
+                                    timestamp(),
+                                    key,
+                                    expected,
+                                    received
+                                );
                             }
                         }
                     }
@@ -245,17 +183,16 @@ fn handle_message(msg: &Message, nodes: &NodeMap, group: &str) {
     }
 }
 
-fn extract_double(value: &sparkplug_rs::MetricValue) -> Option<f64> {
-    match value {
-        sparkplug_rs::MetricValue::Double(v) => Some(*v),
-        sparkplug_rs::MetricValue::Float(v) => Some(*v as f64),
-        sparkplug_rs::MetricValue::Int64(v) => Some(*v as f64),
-        sparkplug_rs::MetricValue::Int32(v) => Some(*v as f64),
-        _ => None,
-    }
+/// Looks up the latest value of a device metric in the subscribers' stores.
+fn latest(subscribers: &[&Subscriber], key: &str, device: &str, name: &str) -> Option<f64> {
+    let (group, node) = key.split_once('/')?;
+    subscribers
+        .iter()
+        .find_map(|sub| sub.value(group, node, Some(device), name))
+        .and_then(|sample| sample.value.as_f64())
 }
 
-fn print_status(nodes: &NodeMap) {
+fn print_status(nodes: &NodeMap, subscribers: &[&Subscriber]) {
     let nodes_map = nodes.lock().unwrap();
     if nodes_map.is_empty() {
         println!("\n[{}] [STATUS] No nodes detected", timestamp());
@@ -282,16 +219,16 @@ fn print_status(nodes: &NodeMap) {
             print!("ACTIVE ");
         }
 
-        if let Some(soc) = state.metrics.get("DATA/BESS_SOC_ACT") {
+        if let Some(soc) = latest(subscribers, key, "BESS", "DATA/BESS_SOC_ACT") {
             print!("SOC={:.1}% ", soc);
         }
-        if let Some(power) = state.metrics.get("DATA/BESS_P_ACT") {
+        if let Some(power) = latest(subscribers, key, "BESS", "DATA/BESS_P_ACT") {
             print!("P={:.1}kW ", power);
         }
-        if let Some(pv) = state.metrics.get("DATA/PV_P_ACT") {
+        if let Some(pv) = latest(subscribers, key, "PV", "DATA/PV_P_ACT") {
             print!("PV={:.1}kW ", pv);
         }
-        if let Some(seq) = state.last_seq {
+        if let Some(seq) = state.sequence.last() {
             print!("seq={}", seq);
         }
         println!();
@@ -106,8 +106,8 @@ fn main() -> Result<()> {
         .add_bool_with_alias("Sensor/Online", 11, true)?;
 
     let device_birth_bytes = device_birth.serialize()?;
-    publisher.publish_device_birth("Sensor01", &device_birth_bytes)?;
-    println!("[OK] Published DBIRTH for Sensor01");
+    publisher.attach_device("Sensor01", &device_birth_bytes)?;
+    println!("[OK] Attached Sensor01 (DBIRTH published)");
 
     let mut device_data = PayloadBuilder::new()?;
     device_data.add_double_by_alias(10, 23.0);
@@ -118,8 +118,8 @@ fn main() -> Result<()> {
 
     thread::sleep(Duration::from_secs(1));
 
-    publisher.publish_device_death("Sensor01")?;
-    println!("[OK] Published DDEATH for Sensor01");
+    publisher.detach_device("Sensor01")?;
+    println!("[OK] Detached Sensor01 (DDEATH published)");
 
     // Clean disconnect
     println!("\nDisconnecting...");
@@ -114,6 +114,7 @@ fn main() -> Result<()> {
                                     MetricValue::Double(v) => println!("{} (double)", v),
                                     MetricValue::Boolean(v) => println!("{} (bool)", v),
                                     MetricValue::String(ref s) => println!("\"{}\" (string)", s),
+                                    MetricValue::DateTime(v) => println!("{} (datetime ms)", v),
                                 }
                             }
                             Err(e) => {
@@ -0,0 +1,24 @@
+//! Sparkplug B Tag List Codegen Example
+//!
+//! Reads a CSV tag list export (name,type,alias,units,writable) and writes
+//! the generated Rust constants and typed struct to stdout.
+//!
+//! Usage: cargo run --example tag_codegen -- tags.csv [StructName] > src/tags.rs
+
+use sparkplug_rs::codegen::{generate_rust, parse_tag_csv};
+use sparkplug_rs::Result;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("Usage: tag_codegen <tags.csv> [StructName]");
+        std::process::exit(2);
+    };
+    let struct_name = args.get(2).map(String::as_str).unwrap_or("Tags");
+
+    let tags = parse_tag_csv(&std::fs::read_to_string(path)?)?;
+    print!("{}", generate_rust(&tags, struct_name)?);
+    eprintln!("Generated {} tags from {}", tags.len(), path);
+
+    Ok(())
+}
@@ -18,6 +18,7 @@
 //!   --cycle <sec>     Cycle connection every N seconds (0=never)
 //!   --help            Show help message
 
+use sparkplug_rs::sequence::SequenceTracker;
 use sparkplug_rs::{
     Message, MessageType, PayloadBuilder, Publisher, PublisherConfig, Subscriber, SubscriberConfig,
 };
@@ -63,7 +64,7 @@ struct NodeStats {
     death_count: i64,
     data_count: i64,
     current_bd_seq: u64,
-    last_seq: u8,
+    sequence: SequenceTracker,
     state: NodeSleepState,
     last_death_time: Option<Instant>,
     last_wake_attempt: Option<Instant>,
@@ -77,7 +78,7 @@ impl NodeStats {
             death_count: 0,
             data_count: 0,
             current_bd_seq: 0,
-            last_seq: 0,
+            sequence: SequenceTracker::new(),
             state: NodeSleepState::Unknown,
             last_death_time: None,
             last_wake_attempt: None,
@@ -354,7 +355,7 @@ impl TortureTestSubscriber {
                         let mut bd_seq = 0u64;
                         if let Ok(payload) = msg.parse_payload() {
                             if let Some(seq) = payload.seq() {
-                                stats.last_seq = (seq & 0xFF) as u8;
+                                stats.sequence.check(MessageType::NBirth, seq);
                             }
 
                             for metric in payload.metrics().flatten() {
@@ -419,7 +420,6 @@ impl TortureTestSubscriber {
                     MessageType::NData => {
                         if let Ok(payload) = msg.parse_payload() {
                             let seq = payload.seq().unwrap_or(0);
-                            let seq_u8 = (seq & 0xFF) as u8;
 
                             if stats.sleeping() || stats.state == NodeSleepState::Unknown {
                                 println!(
@@ -458,20 +458,17 @@ impl TortureTestSubscriber {
 
                             stats.data_count += 1;
 
-                            let expected_seq = ((stats.last_seq as u16 + 1) % 256) as u8;
-                            if seq_u8 != expected_seq && stats.last_seq != 255 {
+                            let status = stats.sequence.check(MessageType::NData, seq);
+                            if !status.is_ok() {
                                 eprintln!(
-                                    "{} SEQUENCE ERROR on {}: expected {}, got {}",
+                                    "{} SEQUENCE ERROR on {}: {:?}",
                                     log_prefix_fn(Some(stats.state)),
                                     edge_node_id,
-                                    expected_seq,
-                                    seq_u8
+                                    status
                                 );
                                 sequence_errors.fetch_add(1, Ordering::SeqCst);
                             }
 
-                            stats.last_seq = seq_u8;
-
                             println!(
                                 "{} NDATA from {} (seq={}, metrics={}, count={})",
                                 log_prefix_fn(Some(stats.state)),
@@ -784,7 +781,9 @@ impl TortureTestSubscriber {
             println!("      NDEATH: {}", node_stats.death_count);
             println!("      NDATA: {}", node_stats.data_count);
             println!("      Current bdSeq: {}", node_stats.current_bd_seq);
-            println!("      Last seq: {}", node_stats.last_seq);
+            if let Some(seq) = node_stats.sequence.last() {
+                println!("      Last seq: {}", seq);
+            }
             println!("      Wake attempts: {}", node_stats.wake_attempt_count);
         }
     }
@@ -0,0 +1,172 @@
+//! Alarm and event helpers.
+//!
+//! Alarms are represented as boolean tags under the `Alarms/` folder. Their
+//! metadata is published as sibling metrics in the birth certificate:
+//!
+//! - `Alarms/<name>` — Boolean, true while the alarm is active
+//! - `Alarms/<name>/Severity` — Int32 severity code (see [`AlarmSeverity`])
+//! - `Alarms/<name>/Setpoint` — Double trip setpoint (optional)
+//!
+//! Sparkplug would carry this metadata as properties of the alarm metric, but
+//! the C library's metric API has no way to attach a property set, so it is
+//! published as separate metrics instead.
+//!
+//! Host applications detect transitions with
+//! [`MetricStore::on_alarm`](crate::store::MetricStore::on_alarm).
+
+use crate::error::Result;
+use crate::payload::PayloadBuilder;
+use crate::store::MetricKey;
+
+/// Metric name prefix identifying alarm tags.
+pub const ALARM_PREFIX: &str = "Alarms/";
+
+/// Name suffix of the severity metadata metric.
+pub const SEVERITY_SUFFIX: &str = "Severity";
+
+/// Name suffix of the setpoint metadata metric.
+pub const SETPOINT_SUFFIX: &str = "Setpoint";
+
+/// Returns true if the metric name is an alarm tag (not one of its metadata metrics).
+pub fn is_alarm_tag(name: &str) -> bool {
+    name.strip_prefix(ALARM_PREFIX).is_some_and(|rest| {
+        !rest.is_empty()
+            && !has_metadata_suffix(name, SEVERITY_SUFFIX)
+            && !has_metadata_suffix(name, SETPOINT_SUFFIX)
+    })
+}
+
+/// Returns true if `name` ends with `/` followed by `suffix`.
+fn has_metadata_suffix(name: &str, suffix: &str) -> bool {
+    name.strip_suffix(suffix)
+        .is_some_and(|rest| rest.ends_with('/'))
+}
+
+/// Alarm severity levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AlarmSeverity {
+    /// Informational, no action required
+    Low,
+    /// Requires attention
+    Medium,
+    /// Requires prompt action
+    High,
+    /// Requires immediate action
+    Critical,
+}
+
+impl AlarmSeverity {
+    /// Returns the numeric code published in the severity metric.
+    pub fn code(self) -> i32 {
+        match self {
+            AlarmSeverity::Low => 0,
+            AlarmSeverity::Medium => 1,
+            AlarmSeverity::High => 2,
+            AlarmSeverity::Critical => 3,
+        }
+    }
+
+    /// Converts a published severity code back into a severity.
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(AlarmSeverity::Low),
+            1 => Some(AlarmSeverity::Medium),
+            2 => Some(AlarmSeverity::High),
+            3 => Some(AlarmSeverity::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// An alarm definition on an edge node or device.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::alarm::{Alarm, AlarmSeverity};
+/// use sparkplug_rs::PayloadBuilder;
+///
+/// let alarm = Alarm::new("HighTemp", AlarmSeverity::High).with_setpoint(85.0);
+///
+/// let mut birth = PayloadBuilder::new()?;
+/// alarm.add_to_birth(&mut birth, false)?;
+///
+/// let mut data = PayloadBuilder::new()?;
+/// alarm.add_to_data(&mut data, true)?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alarm {
+    /// Alarm name (without the `Alarms/` prefix)
+    pub name: String,
+    /// Alarm severity
+    pub severity: AlarmSeverity,
+    /// Trip setpoint, if the alarm has one
+    pub setpoint: Option<f64>,
+}
+
+impl Alarm {
+    /// Creates a new alarm definition.
+    pub fn new(name: impl Into<String>, severity: AlarmSeverity) -> Self {
+        Self {
+            name: name.into(),
+            severity,
+            setpoint: None,
+        }
+    }
+
+    /// Sets the trip setpoint.
+    pub fn with_setpoint(mut self, setpoint: f64) -> Self {
+        self.setpoint = Some(setpoint);
+        self
+    }
+
+    /// Returns the full metric name of the alarm tag.
+    pub fn tag_name(&self) -> String {
+        format!("{}{}", ALARM_PREFIX, self.name)
+    }
+
+    /// Adds the alarm tag and its metadata metrics (for NBIRTH/DBIRTH).
+    pub fn add_to_birth(&self, builder: &mut PayloadBuilder, active: bool) -> Result<()> {
+        let tag = self.tag_name();
+        builder.add_bool(&tag, active)?;
+        builder.add_int32(
+            &format!("{}/{}", tag, SEVERITY_SUFFIX),
+            self.severity.code(),
+        )?;
+        if let Some(setpoint) = self.setpoint {
+            builder.add_double(&format!("{}/{}", tag, SETPOINT_SUFFIX), setpoint)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the alarm state only (for NDATA/DDATA).
+    pub fn add_to_data(&self, builder: &mut PayloadBuilder, active: bool) -> Result<()> {
+        builder.add_bool(&self.tag_name(), active)?;
+        Ok(())
+    }
+}
+
+/// Direction of an alarm state change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmTransition {
+    /// The alarm became active
+    Raised,
+    /// The alarm returned to normal
+    Cleared,
+}
+
+/// An alarm state change detected by a [`MetricStore`](crate::store::MetricStore).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlarmEvent {
+    /// Key of the alarm tag
+    pub key: MetricKey,
+    /// Whether the alarm was raised or cleared
+    pub transition: AlarmTransition,
+    /// Severity from the alarm metadata, if known
+    pub severity: Option<AlarmSeverity>,
+    /// Setpoint from the alarm metadata, if known
+    pub setpoint: Option<f64>,
+    /// Timestamp of the state change (ms since Unix epoch)
+    pub timestamp: Option<u64>,
+}
@@ -0,0 +1,162 @@
+//! Metric alias assignment.
+//!
+//! Sparkplug aliases must be unique across an edge node and its devices, and
+//! should stay the same across rebirths so that hosts can keep their alias
+//! maps. An [`AliasAllocator`] hands out the next free alias for each new
+//! metric name and returns the same alias for a name it has already seen.
+//!
+//! The assignments can be saved to a file, one `alias<TAB>name` line per
+//! metric, so they also survive restarts (see
+//! [`PublisherConfig::alias_file`](crate::PublisherConfig::alias_file)).
+
+use crate::error::{Error, Result};
+use crate::types::MetricAlias;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+
+/// Assigns unique, stable aliases to metric names.
+///
+/// # Example
+///
+/// ```
+/// use sparkplug_rs::AliasAllocator;
+///
+/// let mut aliases = AliasAllocator::new();
+/// let temperature = aliases.alias("Temperature");
+/// let pressure = aliases.alias("Pressure");
+///
+/// assert_ne!(temperature, pressure);
+/// assert_eq!(aliases.alias("Temperature"), temperature);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AliasAllocator {
+    by_name: HashMap<String, MetricAlias>,
+    by_alias: BTreeMap<MetricAlias, String>,
+    next: u64,
+}
+
+impl Default for AliasAllocator {
+    fn default() -> Self {
+        Self::starting_at(1)
+    }
+}
+
+impl AliasAllocator {
+    /// Creates an allocator whose first alias is 1.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an allocator whose first alias is `first`.
+    pub fn starting_at(first: u64) -> Self {
+        Self {
+            by_name: HashMap::new(),
+            by_alias: BTreeMap::new(),
+            next: first,
+        }
+    }
+
+    /// Returns the alias of `name`, assigning the next free one if the name is new.
+    pub fn alias(&mut self, name: &str) -> MetricAlias {
+        if let Some(alias) = self.by_name.get(name) {
+            return *alias;
+        }
+        while self.by_alias.contains_key(&MetricAlias::new(self.next)) {
+            self.next += 1;
+        }
+        let alias = MetricAlias::new(self.next);
+        self.next += 1;
+        self.by_name.insert(name.to_string(), alias);
+        self.by_alias.insert(alias, name.to_string());
+        alias
+    }
+
+    /// Records a fixed alias for `name`.
+    ///
+    /// Returns [`Error::InvalidConfig`] if the name or the alias is already
+    /// assigned to something else.
+    pub fn insert(&mut self, name: &str, alias: impl Into<MetricAlias>) -> Result<()> {
+        let alias = alias.into();
+        match (self.by_name.get(name), self.by_alias.get(&alias)) {
+            (Some(existing), _) if *existing == alias => return Ok(()),
+            (Some(existing), _) => {
+                return Err(Error::InvalidConfig(format!(
+                    "metric '{}' already has alias {}",
+                    name, existing
+                )))
+            }
+            (None, Some(owner)) => {
+                return Err(Error::InvalidConfig(format!(
+                    "alias {} is already assigned to '{}'",
+                    alias, owner
+                )))
+            }
+            (None, None) => {}
+        }
+        self.by_name.insert(name.to_string(), alias);
+        self.by_alias.insert(alias, name.to_string());
+        Ok(())
+    }
+
+    /// Returns the alias assigned to `name`, if any.
+    pub fn get(&self, name: &str) -> Option<MetricAlias> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Returns the name an alias is assigned to, if any.
+    pub fn name(&self, alias: MetricAlias) -> Option<&str> {
+        self.by_alias.get(&alias).map(String::as_str)
+    }
+
+    /// Returns the number of assigned aliases.
+    pub fn len(&self) -> usize {
+        self.by_alias.len()
+    }
+
+    /// Returns true if no alias is assigned.
+    pub fn is_empty(&self) -> bool {
+        self.by_alias.is_empty()
+    }
+
+    /// Iterates over the assignments in alias order.
+    pub fn iter(&self) -> impl Iterator<Item = (MetricAlias, &str)> {
+        self.by_alias
+            .iter()
+            .map(|(alias, name)| (*alias, name.as_str()))
+    }
+
+    /// Loads assignments saved by [`save`](Self::save).
+    ///
+    /// New names get aliases after the highest loaded one.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut allocator = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || {
+                Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("alias file line {}: expected 'alias<TAB>name'", number + 1),
+                ))
+            };
+            let (alias, name) = line.split_once('\t').ok_or_else(invalid)?;
+            let alias: u64 = alias.trim().parse().map_err(|_| invalid())?;
+            allocator.insert(name, alias)?;
+            allocator.next = allocator.next.max(alias + 1);
+        }
+        Ok(allocator)
+    }
+
+    /// Saves the assignments, one `alias<TAB>name` line per metric.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut text = String::new();
+        for (alias, name) in self.iter() {
+            text.push_str(&format!("{}\t{}\n", alias, name));
+        }
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+//! Store-and-forward queue, kept in memory or mirrored to a file.
+//!
+//! The file starts with a header holding the `u64` offsets of the first
+//! queued record and of the end of the last one, followed by the records in
+//! queue order. Bytes past the end offset are left over from an interrupted
+//! write and are discarded when the file is opened. Each record holds the
+//! enqueue time in milliseconds since the Unix epoch (`u64`), a kind byte
+//! (0 for NDATA, 1 for DDATA), for DDATA the device ID as a `u32` length and
+//! UTF-8 bytes, and the payload as a `u32` length and bytes. All integers
+//! are little-endian.
+//!
+//! Enqueuing appends a record and then moves the end offset; sending one
+//! advances the head offset, so each change writes only a few bytes. The
+//! sent records at the start of the file are reclaimed once they outgrow the
+//! ones still queued: the queued records are copied over the sent ones and
+//! the header is rewritten to point at the copy before the file is
+//! truncated, so the file is valid at every step. The file is not synced
+//! after each write: it survives restarts of the process and of the
+//! operating system, but a power loss can lose the latest changes.
+
+use crate::error::{Error, Result};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const HEADER_LEN: u64 = 16;
+
+/// Sent bytes at the start of the file before they are reclaimed
+const COMPACT_THRESHOLD: u64 = 1 << 20;
+
+/// A queued NDATA (no device ID) or DDATA payload.
+pub(crate) struct Entry {
+    pub(crate) device_id: Option<String>,
+    pub(crate) payload: Vec<u8>,
+    /// Enqueue time in milliseconds since the Unix epoch
+    pub(crate) queued_at: u64,
+}
+
+impl Entry {
+    fn encoded_len(&self) -> u64 {
+        let device = self.device_id.as_ref().map_or(0, |id| 4 + id.len());
+        (8 + 1 + device + 4 + self.payload.len()) as u64
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.queued_at.to_le_bytes());
+        match &self.device_id {
+            None => out.push(0),
+            Some(device_id) => {
+                out.push(1);
+                out.extend_from_slice(&(device_id.len() as u32).to_le_bytes());
+                out.extend_from_slice(device_id.as_bytes());
+            }
+        }
+        out.extend_from_slice(&(self.payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.payload);
+    }
+
+    /// Decodes the record at the start of `bytes` and returns it with its
+    /// length, or `None` if the record is incomplete.
+    fn decode(bytes: &[u8]) -> Result<Option<(Self, usize)>> {
+        let mut rest = bytes;
+        let Some(queued_at) = take(&mut rest, 8) else {
+            return Ok(None);
+        };
+        let queued_at = u64::from_le_bytes(queued_at.try_into().unwrap());
+        let device_id = match take(&mut rest, 1) {
+            None => return Ok(None),
+            Some([0]) => None,
+            Some([1]) => {
+                let Some(device_id) = take_sized(&mut rest) else {
+                    return Ok(None);
+                };
+                let device_id = String::from_utf8(device_id.to_vec())
+                    .map_err(|_| invalid_data("device ID is not UTF-8".to_string()))?;
+                Some(device_id)
+            }
+            Some(kind) => {
+                return Err(invalid_data(format!("unknown record kind {}", kind[0])));
+            }
+        };
+        let Some(payload) = take_sized(&mut rest) else {
+            return Ok(None);
+        };
+        let entry = Entry {
+            device_id,
+            payload: payload.to_vec(),
+            queued_at,
+        };
+        Ok(Some((entry, bytes.len() - rest.len())))
+    }
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (head, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Some(head)
+}
+
+fn take_sized<'a>(bytes: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = u32::from_le_bytes(take(bytes, 4)?.try_into().unwrap());
+    take(bytes, len as usize)
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// The store-and-forward queue of a publisher.
+#[derive(Default)]
+pub(crate) struct Backlog {
+    entries: VecDeque<Entry>,
+    file: Option<BacklogFile>,
+}
+
+struct BacklogFile {
+    file: File,
+    /// Offset of the first queued record
+    head: u64,
+    /// Offset after the last queued record
+    end: u64,
+}
+
+impl Backlog {
+    /// Opens the queue stored in `path`, creating the file if needed.
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut entries = VecDeque::new();
+        let (mut head, mut end) = (HEADER_LEN, HEADER_LEN);
+        if bytes.len() >= HEADER_LEN as usize {
+            head = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+            end = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+            if head < HEADER_LEN || head > end {
+                return Err(invalid_data(format!(
+                    "{}: offsets {}..{} are out of range",
+                    path.display(),
+                    head,
+                    end
+                )));
+            }
+            // A power loss can lose appended records whose end offset was
+            // already written.
+            end = end.min(bytes.len() as u64).max(head);
+        }
+        let mut offset = head;
+        while let Some((entry, len)) = Entry::decode(&bytes[offset as usize..end as usize])? {
+            entries.push_back(entry);
+            offset += len as u64;
+        }
+        end = offset;
+        if entries.is_empty() {
+            head = HEADER_LEN;
+            end = HEADER_LEN;
+        }
+
+        let mut file = BacklogFile { file, head, end };
+        file.write_header()?;
+        if end != bytes.len() as u64 {
+            file.file.set_len(end)?;
+        }
+        Ok(Self {
+            entries,
+            file: Some(file),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn front(&self) -> Option<&Entry> {
+        self.entries.front()
+    }
+
+    /// Queues `entry`, writing it to the file first.
+    pub(crate) fn push_back(&mut self, entry: Entry) -> Result<()> {
+        if let Some(file) = &mut self.file {
+            file.append(&entry)?;
+        }
+        self.entries.push_back(entry);
+        Ok(())
+    }
+
+    /// Removes the oldest entry.
+    ///
+    /// The entry is removed from memory even if updating the file fails; it
+    /// is then queued again the next time the file is opened.
+    pub(crate) fn pop_front(&mut self) -> Result<Option<Entry>> {
+        let Some(entry) = self.entries.pop_front() else {
+            return Ok(None);
+        };
+        if let Some(file) = &mut self.file {
+            file.advance(entry.encoded_len(), &self.entries)?;
+        }
+        Ok(Some(entry))
+    }
+}
+
+impl BacklogFile {
+    /// Writes both offsets in a single write.
+    fn write_header(&mut self) -> io::Result<()> {
+        let mut header = [0; HEADER_LEN as usize];
+        header[..8].copy_from_slice(&self.head.to_le_bytes());
+        header[8..].copy_from_slice(&self.end.to_le_bytes());
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&header)
+    }
+
+    fn append(&mut self, entry: &Entry) -> io::Result<()> {
+        let mut record = Vec::with_capacity(entry.encoded_len() as usize);
+        entry.encode(&mut record);
+        self.file.seek(SeekFrom::Start(self.end))?;
+        self.file.write_all(&record)?;
+        self.end += record.len() as u64;
+        self.write_header()
+    }
+
+    fn advance(&mut self, len: u64, remaining: &VecDeque<Entry>) -> io::Result<()> {
+        self.head += len;
+        if self.head >= self.end {
+            self.head = HEADER_LEN;
+            self.end = HEADER_LEN;
+            self.write_header()?;
+            return self.file.set_len(HEADER_LEN);
+        }
+
+        let sent = self.head - HEADER_LEN;
+        if sent >= COMPACT_THRESHOLD && sent > self.end - self.head {
+            // The queued records are copied into the space of the sent ones
+            // without overlapping them, so the old offsets stay valid until
+            // the header is rewritten. The bytes left past the new end are
+            // ignored if the truncation does not happen.
+            let mut records = Vec::with_capacity((self.end - self.head) as usize);
+            for entry in remaining {
+                entry.encode(&mut records);
+            }
+            self.file.seek(SeekFrom::Start(HEADER_LEN))?;
+            self.file.write_all(&records)?;
+            self.head = HEADER_LEN;
+            self.end = HEADER_LEN + records.len() as u64;
+            self.write_header()?;
+            return self.file.set_len(self.end);
+        }
+        self.write_header()
+    }
+}
@@ -0,0 +1,347 @@
+//! Fan-out of decoded messages to multiple consumers.
+//!
+//! [`EventBus`] decodes each received message once into a [`SparkplugEvent`]
+//! and broadcasts it to every [`EventReceiver`], so independent consumers (a
+//! UI, a historian sink, an alarm engine) no longer share a single callback.
+//! Each receiver has its own bounded queue: a slow consumer loses its oldest
+//! events instead of blocking the others, and the loss is counted per
+//! consumer.
+
+use crate::error::Result;
+use crate::subscriber::{Message, MessageCallback};
+use crate::topic::{MessageType, ParsedTopic};
+use crate::types::Metric;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// A decoded Sparkplug message.
+#[derive(Debug, Clone)]
+pub struct SparkplugEvent {
+    /// Parsed topic
+    pub topic: ParsedTopic,
+    /// Payload timestamp (ms since Unix epoch)
+    pub timestamp: Option<u64>,
+    /// Payload sequence number
+    pub seq: Option<u64>,
+    /// Decoded metrics (empty for STATE messages)
+    pub metrics: Vec<Metric>,
+}
+
+impl SparkplugEvent {
+    /// Decodes a received message.
+    pub fn decode(message: &Message) -> Result<Self> {
+        let topic = message.parse_topic()?;
+        if topic.message_type() == Some(MessageType::State) {
+            return Ok(Self {
+                topic,
+                timestamp: None,
+                seq: None,
+                metrics: Vec::new(),
+            });
+        }
+        let payload = message.parse_payload()?;
+        Ok(Self {
+            timestamp: payload.timestamp(),
+            seq: payload.seq(),
+            metrics: payload.to_metrics()?,
+            topic,
+        })
+    }
+}
+
+/// Queue statistics of one consumer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerStats {
+    /// Consumer name given to [`EventBus::subscribe`]
+    pub name: String,
+    /// Events waiting to be received
+    pub queued: usize,
+    /// Events dropped because the queue was full
+    pub lagged: u64,
+}
+
+struct Queue {
+    events: VecDeque<Arc<SparkplugEvent>>,
+    lagged: u64,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+struct Consumer {
+    name: String,
+    capacity: usize,
+    queue: Mutex<Queue>,
+    available: Condvar,
+}
+
+impl Consumer {
+    fn push(&self, event: Arc<SparkplugEvent>) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.events.len() == self.capacity {
+            queue.events.pop_front();
+            queue.lagged += 1;
+        }
+        queue.events.push_back(event);
+        self.wake(&mut queue);
+    }
+
+    fn close(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.closed = true;
+        self.wake(&mut queue);
+    }
+
+    fn wake(&self, queue: &mut Queue) {
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+        self.available.notify_all();
+    }
+}
+
+struct Shared {
+    capacity: usize,
+    consumers: Mutex<Vec<Weak<Consumer>>>,
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        if let Ok(consumers) = self.consumers.lock() {
+            for consumer in consumers.iter().filter_map(Weak::upgrade) {
+                consumer.close();
+            }
+        }
+    }
+}
+
+/// Broadcasts decoded messages to independent consumers.
+///
+/// Clones share the same consumers. When the last clone is dropped, the
+/// receivers drain their queues and then report the bus as closed.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{EventBus, Subscriber, SubscriberConfig};
+///
+/// let bus = EventBus::new(1024);
+/// let historian = bus.subscribe("historian");
+/// let alarms = bus.subscribe("alarms");
+///
+/// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+/// let mut subscriber = Subscriber::new(config, bus.callback())?;
+/// subscriber.connect()?;
+/// subscriber.subscribe_all()?;
+///
+/// std::thread::spawn(move || {
+///     while let Some(event) = historian.recv() {
+///         println!("{}: {} metrics", event.topic, event.metrics.len());
+///     }
+/// });
+/// for stats in bus.stats() {
+///     println!("{}: {} queued, {} lagged", stats.name, stats.queued, stats.lagged);
+/// }
+/// # drop(alarms);
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct EventBus {
+    shared: Arc<Shared>,
+}
+
+impl EventBus {
+    /// Creates a bus whose consumers queue up to `capacity` events each.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                capacity: capacity.max(1),
+                consumers: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Registers a new consumer. It receives events published from now on.
+    pub fn subscribe(&self, name: impl Into<String>) -> EventReceiver {
+        let consumer = Arc::new(Consumer {
+            name: name.into(),
+            capacity: self.shared.capacity,
+            queue: Mutex::new(Queue {
+                events: VecDeque::new(),
+                lagged: 0,
+                closed: false,
+                waker: None,
+            }),
+            available: Condvar::new(),
+        });
+        self.shared
+            .consumers
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&consumer));
+        EventReceiver { consumer }
+    }
+
+    /// Decodes a message and broadcasts it.
+    ///
+    /// Returns the number of consumers the event was delivered to.
+    pub fn publish(&self, message: &Message) -> Result<usize> {
+        Ok(self.send(SparkplugEvent::decode(message)?))
+    }
+
+    /// Broadcasts an already decoded event.
+    ///
+    /// Returns the number of consumers the event was delivered to.
+    pub fn send(&self, event: SparkplugEvent) -> usize {
+        let event = Arc::new(event);
+        let mut consumers = self.shared.consumers.lock().unwrap();
+        consumers.retain(|weak| match weak.upgrade() {
+            Some(consumer) => {
+                consumer.push(event.clone());
+                true
+            }
+            None => false,
+        });
+        consumers.len()
+    }
+
+    /// Returns a subscriber callback that publishes every message to this bus.
+    ///
+    /// Messages that fail to decode are dropped.
+    pub fn callback(&self) -> MessageCallback {
+        let bus = self.clone();
+        Box::new(move |msg: Message| {
+            let _ = bus.publish(&msg);
+        })
+    }
+
+    /// Returns the number of live consumers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared
+            .consumers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|weak| weak.strong_count() > 0)
+            .count()
+    }
+
+    /// Returns the queue statistics of every live consumer.
+    pub fn stats(&self) -> Vec<ConsumerStats> {
+        self.shared
+            .consumers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|consumer| {
+                let queue = consumer.queue.lock().unwrap();
+                ConsumerStats {
+                    name: consumer.name.clone(),
+                    queued: queue.events.len(),
+                    lagged: queue.lagged,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One consumer of an [`EventBus`].
+///
+/// Dropping the receiver unregisters the consumer.
+pub struct EventReceiver {
+    consumer: Arc<Consumer>,
+}
+
+impl EventReceiver {
+    /// Blocks until an event is available.
+    ///
+    /// Returns `None` once the bus is dropped and the queue is drained.
+    pub fn recv(&self) -> Option<Arc<SparkplugEvent>> {
+        let mut queue = self.consumer.queue.lock().unwrap();
+        loop {
+            if let Some(event) = queue.events.pop_front() {
+                return Some(event);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self.consumer.available.wait(queue).unwrap();
+        }
+    }
+
+    /// Waits up to `timeout` for an event.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Arc<SparkplugEvent>> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.consumer.queue.lock().unwrap();
+        loop {
+            if let Some(event) = queue.events.pop_front() {
+                return Some(event);
+            }
+            let now = Instant::now();
+            if queue.closed || now >= deadline {
+                return None;
+            }
+            queue = self
+                .consumer
+                .available
+                .wait_timeout(queue, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Returns the next queued event without blocking.
+    pub fn try_recv(&self) -> Option<Arc<SparkplugEvent>> {
+        self.consumer.queue.lock().unwrap().events.pop_front()
+    }
+
+    /// Returns a future resolving to the next event (`None` once the bus is closed).
+    pub fn recv_async(&self) -> Recv<'_> {
+        Recv { receiver: self }
+    }
+
+    /// Returns the consumer name.
+    pub fn name(&self) -> &str {
+        &self.consumer.name
+    }
+
+    /// Returns the number of queued events.
+    pub fn len(&self) -> usize {
+        self.consumer.queue.lock().unwrap().events.len()
+    }
+
+    /// Returns true if no events are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of events dropped because this consumer fell behind.
+    pub fn lagged(&self) -> u64 {
+        self.consumer.queue.lock().unwrap().lagged
+    }
+}
+
+/// Future returned by [`EventReceiver::recv_async`].
+pub struct Recv<'a> {
+    receiver: &'a EventReceiver,
+}
+
+impl Future for Recv<'_> {
+    type Output = Option<Arc<SparkplugEvent>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut queue = self.receiver.consumer.queue.lock().unwrap();
+        if let Some(event) = queue.events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if queue.closed {
+            return Poll::Ready(None);
+        }
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,277 @@
+//! Code generation from tag list exports.
+//!
+//! Large OT tag databases are usually maintained outside the code base and
+//! exported as CSV. [`parse_tag_csv`] reads such an export and
+//! [`generate_rust`] turns it into Rust source with name and alias constants
+//! plus a struct deriving [`SparkplugMetrics`](crate::SparkplugMetrics).
+//!
+//! The expected CSV header is `name,type,alias,units,writable`. Columns may
+//! appear in any order; only `name` and `type` are required.
+//!
+//! # Example (build script)
+//!
+//! ```no_run
+//! use sparkplug_rs::codegen::{generate_rust, parse_tag_csv};
+//!
+//! let csv = std::fs::read_to_string("tags.csv")?;
+//! let tags = parse_tag_csv(&csv)?;
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("tags.rs");
+//! std::fs::write(out, generate_rust(&tags, "Tags")?)?;
+//! # Ok::<(), sparkplug_rs::Error>(())
+//! ```
+//!
+//! Then `include!(concat!(env!("OUT_DIR"), "/tags.rs"));` in the crate. The
+//! generated struct requires the `derive` feature.
+
+use crate::error::{Error, Result};
+use crate::types::DataType;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// A tag (metric) definition from a tag list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagDefinition {
+    /// Metric name
+    pub name: String,
+    /// Metric data type
+    pub datatype: DataType,
+    /// Metric alias, if assigned
+    pub alias: Option<u64>,
+    /// Engineering units, if any
+    pub units: Option<String>,
+    /// Whether the tag accepts writes via NCMD/DCMD
+    pub writable: bool,
+}
+
+/// Parses a CSV tag list export.
+pub fn parse_tag_csv(input: &str) -> Result<Vec<TagDefinition>> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l))
+        .filter(|(_, l)| !l.trim().is_empty());
+
+    let (header_line, header) = lines
+        .next()
+        .ok_or_else(|| Error::InvalidTagList("missing header".to_string()))?;
+    let header: Vec<String> = split_csv_line(header, header_line)?
+        .into_iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let required = |name: &str| {
+        column(name).ok_or_else(|| {
+            Error::InvalidTagList(format!("line {}: missing '{}' column", header_line, name))
+        })
+    };
+    let name_col = required("name")?;
+    let type_col = required("type")?;
+    let alias_col = column("alias");
+    let units_col = column("units");
+    let writable_col = column("writable");
+
+    let mut tags = Vec::new();
+    for (line, text) in lines {
+        let fields = split_csv_line(text, line)?;
+        let field = |col: Option<usize>| {
+            col.and_then(|c| fields.get(c))
+                .map(|f| f.trim())
+                .filter(|f| !f.is_empty())
+        };
+        let invalid =
+            |details: String| Error::InvalidTagList(format!("line {}: {}", line, details));
+
+        let name = field(Some(name_col)).ok_or_else(|| invalid("empty name".to_string()))?;
+        let type_name = field(Some(type_col)).unwrap_or_default();
+        let datatype = DataType::from_name(type_name)
+            .ok_or_else(|| invalid(format!("unknown type '{}'", type_name)))?;
+        let alias = field(alias_col)
+            .map(|a| {
+                a.parse::<u64>()
+                    .map_err(|_| invalid(format!("invalid alias '{}'", a)))
+            })
+            .transpose()?;
+        let writable = match field(writable_col).map(|w| w.to_ascii_lowercase()) {
+            None => false,
+            Some(w) => match w.as_str() {
+                "true" | "yes" | "y" | "1" => true,
+                "false" | "no" | "n" | "0" => false,
+                _ => return Err(invalid(format!("invalid writable flag '{}'", w))),
+            },
+        };
+
+        tags.push(TagDefinition {
+            name: name.to_string(),
+            datatype,
+            alias,
+            units: field(units_col).map(|u| u.to_string()),
+            writable,
+        });
+    }
+    Ok(tags)
+}
+
+/// Generates Rust source for a tag list.
+///
+/// The output contains a `names` module of metric name constants, an
+/// `aliases` module of alias constants and a struct `struct_name` with one
+/// field per tag. Tags whose type has no [`MetricField`](crate::MetricField)
+/// implementation (DateTime, Text) only get constants. Non-writable tags are
+/// marked `readonly`.
+pub fn generate_rust(tags: &[TagDefinition], struct_name: &str) -> Result<String> {
+    let mut consts = HashSet::new();
+    let mut fields = HashSet::new();
+    for tag in tags {
+        if !consts.insert(const_ident(&tag.name)) || !fields.insert(field_ident(&tag.name)) {
+            return Err(Error::InvalidTagList(format!(
+                "'{}' maps to a duplicate identifier",
+                tag.name
+            )));
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("// Generated by sparkplug-rs from a tag list. Do not edit.\n\n");
+
+    out.push_str("/// Metric names.\npub mod names {\n");
+    for tag in tags {
+        let _ = writeln!(out, "    /// {}", describe(tag));
+        let _ = writeln!(
+            out,
+            "    pub const {}: &str = {:?};",
+            const_ident(&tag.name),
+            tag.name
+        );
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("/// Metric aliases.\npub mod aliases {\n");
+    for tag in tags {
+        if let Some(alias) = tag.alias {
+            let _ = writeln!(out, "    /// Alias of `{}`", tag.name);
+            let _ = writeln!(
+                out,
+                "    pub const {}: u64 = {};",
+                const_ident(&tag.name),
+                alias
+            );
+        }
+    }
+    out.push_str("}\n\n");
+
+    let _ = writeln!(out, "/// Typed view of the tag list.");
+    let _ = writeln!(
+        out,
+        "#[derive(Debug, Clone, PartialEq, Default, sparkplug_rs::SparkplugMetrics)]"
+    );
+    let _ = writeln!(out, "pub struct {} {{", struct_name);
+    for tag in tags {
+        let Some((ty, alias_capable)) = rust_type(tag.datatype) else {
+            let _ = writeln!(
+                out,
+                "    // {} ({}) has no typed field",
+                tag.name, tag.datatype
+            );
+            continue;
+        };
+        let _ = writeln!(out, "    /// {}", describe(tag));
+        let mut attrs = vec![format!("name = {:?}", tag.name)];
+        if let (Some(alias), true) = (tag.alias, alias_capable) {
+            attrs.push(format!("alias = {}", alias));
+        }
+        if !tag.writable {
+            attrs.push("readonly".to_string());
+        }
+        let _ = writeln!(out, "    #[sparkplug({})]", attrs.join(", "));
+        let _ = writeln!(out, "    pub {}: {},", field_ident(&tag.name), ty);
+    }
+    out.push_str("}\n");
+
+    Ok(out)
+}
+
+/// Maps a data type to its Rust field type and whether it can carry an alias.
+fn rust_type(datatype: DataType) -> Option<(&'static str, bool)> {
+    match datatype {
+        DataType::Int8 => Some(("i8", false)),
+        DataType::Int16 => Some(("i16", false)),
+        DataType::Int32 => Some(("i32", true)),
+        DataType::Int64 => Some(("i64", true)),
+        DataType::UInt8 => Some(("u8", false)),
+        DataType::UInt16 => Some(("u16", false)),
+        DataType::UInt32 => Some(("u32", true)),
+        DataType::UInt64 => Some(("u64", true)),
+        DataType::Float => Some(("f32", true)),
+        DataType::Double => Some(("f64", true)),
+        DataType::Boolean => Some(("bool", true)),
+        DataType::String => Some(("String", false)),
+        DataType::DateTime | DataType::Text | DataType::Unknown => None,
+    }
+}
+
+fn describe(tag: &TagDefinition) -> String {
+    match &tag.units {
+        Some(units) => format!("`{}` ({}, {})", tag.name, tag.datatype, units),
+        None => format!("`{}` ({})", tag.name, tag.datatype),
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+fn const_ident(name: &str) -> String {
+    sanitize(name).to_ascii_uppercase()
+}
+
+fn field_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+        "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+        "where", "while", "async", "await", "dyn",
+    ];
+    let ident = sanitize(name).to_ascii_lowercase();
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("{}_", ident)
+    } else {
+        ident
+    }
+}
+
+/// Splits one CSV line, honouring double-quoted fields.
+fn split_csv_line(line: &str, line_number: usize) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if current.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(Error::InvalidTagList(format!(
+            "line {}: unterminated quoted field",
+            line_number
+        )));
+    }
+    fields.push(current);
+    Ok(fields)
+}
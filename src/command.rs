@@ -0,0 +1,289 @@
+//! Waiting for command confirmations.
+//!
+//! Sparkplug has no command acknowledgement: a write is confirmed when the
+//! edge node reports the new value in a later NDATA/DDATA, and a rebirth
+//! request when the NBIRTH arrives. [`CommandWaiter`] watches received
+//! messages and resolves [`PendingCommand`]s when the expected message shows
+//! up, either blocking ([`PendingCommand::wait`]) or as a `Future`.
+
+use crate::error::{Error, Result};
+use crate::store::{MetricKey, MetricSample, MetricStore, Quality};
+use crate::subscriber::Message;
+use crate::topic::MessageType;
+use crate::types::MetricValue;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// The message that confirmed a command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirmation {
+    /// The expected metric was reported with this sample.
+    Metric(MetricSample),
+    /// The edge node published its NBIRTH.
+    Birth,
+}
+
+enum Expectation {
+    Metric {
+        key: MetricKey,
+        value: Option<MetricValue>,
+    },
+    Birth {
+        group_id: String,
+        edge_node_id: String,
+    },
+}
+
+struct Waiting {
+    expectation: Expectation,
+    deadline: Instant,
+    result: Option<Confirmation>,
+    waker: Option<Waker>,
+    timer_started: bool,
+}
+
+#[derive(Default)]
+struct State {
+    store: MetricStore,
+    next_id: u64,
+    pending: HashMap<u64, Waiting>,
+}
+
+type Shared = Arc<(Mutex<State>, Condvar)>;
+
+/// Resolves pending commands from received messages.
+///
+/// Feed every received message to [`observe`](Self::observe) (typically from
+/// the subscriber callback). Register the expectation *before* publishing the
+/// command so a fast reply cannot be missed.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{CommandWaiter, Message, MetricKey, MetricValue};
+/// use sparkplug_rs::{HostApplication, HostApplicationConfig, PayloadBuilder};
+/// use std::time::Duration;
+///
+/// let waiter = CommandWaiter::new();
+/// let observer = waiter.clone();
+/// let callback = Box::new(move |msg: Message| observer.observe(&msg));
+/// // pass `callback` to Subscriber::new and subscribe, then:
+///
+/// let mut host = HostApplication::new(HostApplicationConfig::new(
+///     "tcp://localhost:1883",
+///     "scada",
+///     "SCADA01",
+///     ["Energy"],
+/// ))?;
+/// host.connect()?;
+///
+/// let pending = waiter.expect_metric(
+///     MetricKey::new("Energy", "Gateway01", None, "Setpoint"),
+///     Some(MetricValue::Double(42.0)),
+///     Duration::from_secs(5),
+/// );
+/// let mut cmd = PayloadBuilder::new()?;
+/// cmd.add_double("Setpoint", 42.0)?;
+/// host.send_node_command("Energy", "Gateway01", &cmd.serialize()?)?;
+/// pending.wait()?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct CommandWaiter {
+    shared: Shared,
+}
+
+impl CommandWaiter {
+    /// Creates a waiter with no pending commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects `key` to be reported, optionally with a specific value.
+    pub fn expect_metric(
+        &self,
+        key: MetricKey,
+        value: Option<MetricValue>,
+        timeout: Duration,
+    ) -> PendingCommand {
+        self.register(Expectation::Metric { key, value }, timeout)
+    }
+
+    /// Expects an NBIRTH from an edge node (e.g. after a rebirth request).
+    pub fn expect_birth(
+        &self,
+        group_id: impl Into<String>,
+        edge_node_id: impl Into<String>,
+        timeout: Duration,
+    ) -> PendingCommand {
+        self.register(
+            Expectation::Birth {
+                group_id: group_id.into(),
+                edge_node_id: edge_node_id.into(),
+            },
+            timeout,
+        )
+    }
+
+    /// Returns the number of unresolved pending commands.
+    pub fn pending_count(&self) -> usize {
+        let state = self.shared.0.lock().unwrap();
+        state
+            .pending
+            .values()
+            .filter(|w| w.result.is_none())
+            .count()
+    }
+
+    /// Processes a received message, resolving any pending command it confirms.
+    pub fn observe(&self, message: &Message) {
+        let (lock, condvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        let Ok(updated) = state.store.ingest(message) else {
+            return;
+        };
+        let Ok(topic) = message.parse_topic() else {
+            return;
+        };
+
+        let State { store, pending, .. } = &mut *state;
+        let mut resolved = false;
+        for waiting in pending.values_mut().filter(|w| w.result.is_none()) {
+            waiting.result = match &waiting.expectation {
+                Expectation::Birth {
+                    group_id,
+                    edge_node_id,
+                } => (topic.message_type() == Some(MessageType::NBirth)
+                    && topic.group_id() == Some(group_id.as_str())
+                    && topic.edge_node_id() == Some(edge_node_id.as_str()))
+                .then_some(Confirmation::Birth),
+                Expectation::Metric { key, value } => updated
+                    .contains(key)
+                    .then(|| {
+                        store.get(
+                            &key.group_id,
+                            &key.edge_node_id,
+                            key.device_id.as_deref(),
+                            &key.name,
+                        )
+                    })
+                    .flatten()
+                    .filter(|sample| sample.quality == Quality::Good)
+                    .filter(|sample| value.as_ref().is_none_or(|v| *v == sample.value))
+                    .map(|sample| Confirmation::Metric(sample.clone())),
+            };
+            if waiting.result.is_some() {
+                resolved = true;
+                if let Some(waker) = waiting.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+        if resolved {
+            condvar.notify_all();
+        }
+    }
+
+    fn register(&self, expectation: Expectation, timeout: Duration) -> PendingCommand {
+        let mut state = self.shared.0.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.pending.insert(
+            id,
+            Waiting {
+                expectation,
+                deadline: Instant::now() + timeout,
+                result: None,
+                waker: None,
+                timer_started: false,
+            },
+        );
+        PendingCommand {
+            id,
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// A command awaiting confirmation.
+///
+/// Resolve it with [`wait`](Self::wait) or `.await` it. Dropping it cancels
+/// the expectation.
+pub struct PendingCommand {
+    id: u64,
+    shared: Shared,
+}
+
+impl PendingCommand {
+    /// Blocks until the command is confirmed or its timeout expires.
+    pub fn wait(self) -> Result<Confirmation> {
+        let (lock, condvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        loop {
+            let Some(waiting) = state.pending.get_mut(&self.id) else {
+                return Err(Error::Timeout("command confirmation".to_string()));
+            };
+            if let Some(result) = waiting.result.take() {
+                return Ok(result);
+            }
+            let now = Instant::now();
+            if now >= waiting.deadline {
+                return Err(Error::Timeout("command confirmation".to_string()));
+            }
+            let remaining = waiting.deadline - now;
+            state = condvar.wait_timeout(state, remaining).unwrap().0;
+        }
+    }
+}
+
+impl Future for PendingCommand {
+    type Output = Result<Confirmation>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.0.lock().unwrap();
+        let Some(waiting) = state.pending.get_mut(&self.id) else {
+            return Poll::Ready(Err(Error::Timeout("command confirmation".to_string())));
+        };
+        if let Some(result) = waiting.result.take() {
+            return Poll::Ready(Ok(result));
+        }
+        if Instant::now() >= waiting.deadline {
+            return Poll::Ready(Err(Error::Timeout("command confirmation".to_string())));
+        }
+
+        waiting.waker = Some(cx.waker().clone());
+        if !waiting.timer_started {
+            // No runtime is assumed, so a helper thread wakes the task at the deadline.
+            waiting.timer_started = true;
+            let deadline = waiting.deadline;
+            let shared = self.shared.clone();
+            let id = self.id;
+            std::thread::spawn(move || {
+                std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                let waker = shared
+                    .0
+                    .lock()
+                    .unwrap()
+                    .pending
+                    .get_mut(&id)
+                    .and_then(|w| w.waker.take());
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            });
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for PendingCommand {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.0.lock() {
+            state.pending.remove(&self.id);
+        }
+    }
+}
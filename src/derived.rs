@@ -0,0 +1,270 @@
+//! Derived (computed) metrics.
+//!
+//! [`DerivedMetrics`] computes additional tags from incoming samples — rate of
+//! change, rolling average, windowed min/max, or an arbitrary function of other
+//! metrics — so they can be republished alongside the source data.
+
+use crate::error::Result;
+use crate::payload::PayloadBuilder;
+use crate::types::Metric;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Function computing a derived value from its input values (in declaration order).
+pub type DeriveFn = Arc<dyn Fn(&[f64]) -> f64 + Send + Sync>;
+
+/// How a derived metric is computed from its source metric(s).
+#[derive(Clone)]
+pub enum Derivation {
+    /// Change per second between the two most recent samples of `source`.
+    RateOfChange {
+        /// Source metric name
+        source: String,
+    },
+    /// Mean of `source` samples within the trailing window.
+    RollingAverage {
+        /// Source metric name
+        source: String,
+        /// Window length
+        window: Duration,
+    },
+    /// Minimum of `source` samples within the trailing window.
+    Min {
+        /// Source metric name
+        source: String,
+        /// Window length
+        window: Duration,
+    },
+    /// Maximum of `source` samples within the trailing window.
+    Max {
+        /// Source metric name
+        source: String,
+        /// Window length
+        window: Duration,
+    },
+    /// Arbitrary function of the latest values of several metrics.
+    ///
+    /// Evaluated once every input has been seen at least once.
+    Expression {
+        /// Input metric names, passed to `function` in this order
+        inputs: Vec<String>,
+        /// Function computing the derived value
+        function: DeriveFn,
+    },
+}
+
+impl Derivation {
+    /// Returns true if this derivation reads the given source metric.
+    fn depends_on(&self, name: &str) -> bool {
+        match self {
+            Derivation::RateOfChange { source }
+            | Derivation::RollingAverage { source, .. }
+            | Derivation::Min { source, .. }
+            | Derivation::Max { source, .. } => source == name,
+            Derivation::Expression { inputs, .. } => inputs.iter().any(|i| i == name),
+        }
+    }
+
+    /// Returns how much history this derivation needs for its sources.
+    fn window(&self) -> Duration {
+        match self {
+            Derivation::RollingAverage { window, .. }
+            | Derivation::Min { window, .. }
+            | Derivation::Max { window, .. } => *window,
+            Derivation::RateOfChange { .. } | Derivation::Expression { .. } => Duration::ZERO,
+        }
+    }
+}
+
+/// A computed value produced by [`DerivedMetrics`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedValue {
+    /// Name of the derived metric
+    pub name: String,
+    /// Timestamp of the sample that triggered the computation (ms since Unix epoch)
+    pub timestamp: u64,
+    /// Computed value
+    pub value: f64,
+}
+
+/// Computes derived metrics from incoming samples.
+///
+/// Definitions can be added and removed at runtime. Each call to
+/// [`update`](Self::update) recomputes only the derived metrics that depend
+/// on the updated source. Samples must arrive in timestamp order per source;
+/// a sample older than the latest one recorded for its source is ignored.
+///
+/// # Example
+///
+/// ```
+/// use sparkplug_rs::derived::{Derivation, DerivedMetrics};
+/// use std::time::Duration;
+///
+/// let mut derived = DerivedMetrics::new();
+/// derived.add(
+///     "Power/Avg5m",
+///     Derivation::RollingAverage {
+///         source: "Power".to_string(),
+///         window: Duration::from_secs(300),
+///     },
+/// );
+///
+/// derived.update("Power", 1_000, 10.0);
+/// let values = derived.update("Power", 2_000, 20.0);
+/// assert_eq!(values[0].value, 15.0);
+/// ```
+#[derive(Default)]
+pub struct DerivedMetrics {
+    definitions: Vec<(String, Derivation)>,
+    history: HashMap<String, VecDeque<(u64, f64)>>,
+}
+
+impl DerivedMetrics {
+    /// Creates an empty set of derived metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds (or replaces) a derived metric definition.
+    pub fn add(&mut self, name: impl Into<String>, derivation: Derivation) -> &mut Self {
+        let name = name.into();
+        self.definitions.retain(|(n, _)| *n != name);
+        self.definitions.push((name, derivation));
+        self.prune_history();
+        self
+    }
+
+    /// Removes a derived metric definition. Returns true if it existed.
+    ///
+    /// The history of sources that no remaining definition reads is dropped.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.definitions.len();
+        self.definitions.retain(|(n, _)| n != name);
+        self.prune_history();
+        before != self.definitions.len()
+    }
+
+    fn prune_history(&mut self) {
+        let definitions = &self.definitions;
+        self.history
+            .retain(|source, _| definitions.iter().any(|(_, d)| d.depends_on(source)));
+    }
+
+    /// Returns the names of all defined derived metrics (e.g. for NBIRTH).
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.definitions.iter().map(|(n, _)| n.as_str())
+    }
+
+    /// Records a source sample and returns the derived values it affects.
+    ///
+    /// A sample older than the latest one recorded for `source` is ignored
+    /// and affects nothing, so a late sample cannot skew rates or windows.
+    pub fn update(&mut self, source: &str, timestamp: u64, value: f64) -> Vec<DerivedValue> {
+        let retention = self
+            .definitions
+            .iter()
+            .filter(|(_, d)| d.depends_on(source))
+            .map(|(_, d)| d.window())
+            .max();
+
+        let Some(retention) = retention else {
+            return Vec::new();
+        };
+
+        let samples = self.history.entry(source.to_string()).or_default();
+        if samples.back().is_some_and(|(last, _)| timestamp < *last) {
+            return Vec::new();
+        }
+        samples.push_back((timestamp, value));
+        let cutoff = timestamp.saturating_sub(retention.as_millis() as u64);
+        // Keep at least two samples so rate of change can always be computed.
+        while samples.len() > 2 && samples.front().is_some_and(|(ts, _)| *ts < cutoff) {
+            samples.pop_front();
+        }
+
+        self.definitions
+            .iter()
+            .filter(|(_, d)| d.depends_on(source))
+            .filter_map(|(name, d)| {
+                self.compute(d, timestamp).map(|value| DerivedValue {
+                    name: name.clone(),
+                    timestamp,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// Records a parsed metric as a source sample.
+    ///
+    /// The metric must have a name and a numeric value; its own timestamp is
+    /// used when present, otherwise `fallback_timestamp`.
+    pub fn update_metric(&mut self, metric: &Metric, fallback_timestamp: u64) -> Vec<DerivedValue> {
+        match (&metric.name, metric.value.as_f64()) {
+            (Some(name), Some(value)) => {
+                let timestamp = metric.timestamp.unwrap_or(fallback_timestamp);
+                self.update(name, timestamp, value)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Adds computed values to a payload as double metrics, by name.
+    pub fn add_to_payload(values: &[DerivedValue], builder: &mut PayloadBuilder) -> Result<()> {
+        for v in values {
+            builder.add_double(&v.name, v.value)?;
+        }
+        Ok(())
+    }
+
+    fn compute(&self, derivation: &Derivation, now: u64) -> Option<f64> {
+        match derivation {
+            Derivation::RateOfChange { source } => {
+                let samples = self.history.get(source)?;
+                let mut recent = samples.iter().rev();
+                let (t1, v1) = recent.next()?;
+                let (t0, v0) = recent.next()?;
+                if t1 <= t0 {
+                    return None;
+                }
+                Some((v1 - v0) / ((t1 - t0) as f64 / 1000.0))
+            }
+            Derivation::RollingAverage { source, window } => {
+                let values = self.window_values(source, *window, now)?;
+                Some(values.iter().sum::<f64>() / values.len() as f64)
+            }
+            Derivation::Min { source, window } => self
+                .window_values(source, *window, now)?
+                .into_iter()
+                .reduce(f64::min),
+            Derivation::Max { source, window } => self
+                .window_values(source, *window, now)?
+                .into_iter()
+                .reduce(f64::max),
+            Derivation::Expression { inputs, function } => {
+                let values = inputs
+                    .iter()
+                    .map(|i| self.history.get(i)?.back().map(|(_, v)| *v))
+                    .collect::<Option<Vec<f64>>>()?;
+                Some(function(&values))
+            }
+        }
+    }
+
+    fn window_values(&self, source: &str, window: Duration, now: u64) -> Option<Vec<f64>> {
+        let cutoff = now.saturating_sub(window.as_millis() as u64);
+        let values: Vec<f64> = self
+            .history
+            .get(source)?
+            .iter()
+            .filter(|(ts, _)| *ts >= cutoff)
+            .map(|(_, v)| *v)
+            .collect();
+        if values.is_empty() {
+            None
+        } else {
+            Some(values)
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Offline session for [`Publisher::dry_run`](crate::Publisher::dry_run).
+//!
+//! Replaces the C publisher's MQTT client: payloads are validated, stamped
+//! with the sequence numbers the C library would use and handed to a sink
+//! as [`Message`]s.
+
+use crate::error::{Error, Result};
+use crate::payload::{Payload, PayloadBuilder};
+use crate::subscriber::{Message, MessageCallback};
+use crate::topic::{MessageType, ParsedTopic};
+use crate::validate::{self, MAX_SEQ};
+use crate::wire;
+
+pub(crate) struct DryRun {
+    sink: MessageCallback,
+    group_id: String,
+    edge_node_id: String,
+    seq: u64,
+    bd_seq: u64,
+    connected_once: bool,
+}
+
+impl DryRun {
+    pub(crate) fn new(sink: MessageCallback, group_id: &str, edge_node_id: &str) -> Self {
+        Self {
+            sink,
+            group_id: group_id.to_string(),
+            edge_node_id: edge_node_id.to_string(),
+            seq: 0,
+            bd_seq: 0,
+            connected_once: false,
+        }
+    }
+
+    pub(crate) fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub(crate) fn bd_seq(&self) -> u64 {
+        self.bd_seq
+    }
+
+    /// Starts a session: every connect after the first uses the next bdSeq.
+    pub(crate) fn connect(&mut self) {
+        if self.connected_once {
+            self.bd_seq = (self.bd_seq + 1) % (MAX_SEQ + 1);
+        }
+        self.connected_once = true;
+    }
+
+    pub(crate) fn node_birth(&mut self, payload: &[u8]) -> Result<()> {
+        let bytes = stamp(payload, Some(0), Some(self.bd_seq))?;
+        validate::validate_node_birth(&Payload::parse(&bytes)?)?;
+        self.seq = 0;
+        self.emit(MessageType::NBirth, &self.edge_node_id, None, bytes);
+        Ok(())
+    }
+
+    pub(crate) fn rebirth(&mut self, birth: Option<&[u8]>) -> Result<()> {
+        let birth = birth.ok_or_else(|| Error::InvalidState {
+            operation: "rebirth",
+            details: "no NBIRTH to republish".to_string(),
+        })?;
+        self.bd_seq = (self.bd_seq + 1) % (MAX_SEQ + 1);
+        self.node_birth(birth)
+    }
+
+    pub(crate) fn node_death(&mut self) -> Result<()> {
+        let mut death = PayloadBuilder::new()?;
+        death
+            .set_timestamp(crate::time::now_millis())
+            .add_bd_seq(self.bd_seq)?;
+        let bytes = death.serialize()?;
+        self.emit(MessageType::NDeath, &self.edge_node_id, None, bytes);
+        Ok(())
+    }
+
+    pub(crate) fn data(&mut self, device_id: Option<&str>, payload: &[u8]) -> Result<()> {
+        validate::validate_data(&Payload::parse(payload)?)?;
+        let bytes = stamp(payload, Some(self.next_seq()), None)?;
+        let message_type = match device_id {
+            None => MessageType::NData,
+            Some(_) => MessageType::DData,
+        };
+        self.emit(message_type, &self.edge_node_id, device_id, bytes);
+        Ok(())
+    }
+
+    pub(crate) fn device_birth(&mut self, device_id: &str, payload: &[u8]) -> Result<()> {
+        validate::validate_birth(&Payload::parse(payload)?)?;
+        let bytes = stamp(payload, Some(self.next_seq()), None)?;
+        self.emit(
+            MessageType::DBirth,
+            &self.edge_node_id,
+            Some(device_id),
+            bytes,
+        );
+        Ok(())
+    }
+
+    pub(crate) fn device_death(&mut self, device_id: &str) -> Result<()> {
+        let mut death = PayloadBuilder::new()?;
+        death
+            .set_timestamp(crate::time::now_millis())
+            .set_seq(self.next_seq());
+        let bytes = death.serialize()?;
+        self.emit(
+            MessageType::DDeath,
+            &self.edge_node_id,
+            Some(device_id),
+            bytes,
+        );
+        Ok(())
+    }
+
+    pub(crate) fn command(&self, edge_node_id: &str, device_id: Option<&str>, payload: &[u8]) {
+        let message_type = match device_id {
+            None => MessageType::NCmd,
+            Some(_) => MessageType::DCmd,
+        };
+        self.emit(message_type, edge_node_id, device_id, payload.to_vec());
+    }
+
+    pub(crate) fn state(&self, host_id: &str, online: bool, timestamp: u64) {
+        let topic = ParsedTopic::State {
+            host_id: host_id.to_string(),
+        };
+        (self.sink)(Message {
+            topic: topic.to_topic_string(),
+            payload_data: format!(r#"{{"online":{},"timestamp":{}}}"#, online, timestamp)
+                .into_bytes(),
+        });
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        self.seq = (self.seq + 1) % (MAX_SEQ + 1);
+        self.seq
+    }
+
+    fn emit(
+        &self,
+        message_type: MessageType,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        payload: Vec<u8>,
+    ) {
+        let topic = ParsedTopic::Sparkplug {
+            message_type,
+            group_id: self.group_id.clone(),
+            edge_node_id: edge_node_id.to_string(),
+            device_id: device_id.map(str::to_string),
+        };
+        (self.sink)(Message {
+            topic: topic.to_topic_string(),
+            payload_data: payload,
+        });
+    }
+}
+
+/// Stamps a payload with a sequence number, a timestamp if it has none
+/// and, for NBIRTH, the session's bdSeq, as the C publisher would.
+fn stamp(payload: &[u8], seq: Option<u64>, bd_seq: Option<u64>) -> Result<Vec<u8>> {
+    wire::stamp(payload, crate::time::now_millis(), seq, bd_seq)
+}
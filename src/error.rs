@@ -75,4 +75,58 @@ pub enum Error {
     /// Invalid Sparkplug topic.
     #[error("Invalid topic: {0}")]
     InvalidTopic(String),
+
+    /// Invalid tag list input for code generation.
+    #[error("Invalid tag list: {0}")]
+    InvalidTagList(String),
+
+    /// Device data was published for a device without a current DBIRTH.
+    #[error("Device '{device_id}' has no current DBIRTH")]
+    DeviceNotBirthed {
+        /// The device ID
+        device_id: String,
+    },
+
+    /// An operation is not allowed in the current session state (strict mode).
+    #[error("Invalid state for {operation}: {details}")]
+    InvalidState {
+        /// The rejected operation
+        operation: &'static str,
+        /// Why the operation is not allowed
+        details: String,
+    },
+
+    /// A configuration is incomplete or inconsistent.
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    /// An operation did not complete in time.
+    #[error("Timed out waiting for {0}")]
+    Timeout(String),
+
+    /// The requested combination is not supported by the underlying C API.
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// A UUID string failed validation.
+    #[cfg(feature = "uuid")]
+    #[error("Invalid UUID: {0}")]
+    InvalidUuid(#[from] uuid::Error),
+
+    /// A payload breaks Sparkplug payload rules.
+    #[error("Invalid payload: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InvalidPayload(Vec<crate::validate::Violation>),
+
+    /// Invalid Sparkplug JSON input.
+    #[cfg(feature = "json")]
+    #[error("Invalid JSON payload: {0}")]
+    InvalidJson(String),
+
+    /// A STATE payload is neither `ONLINE`/`OFFLINE` nor a JSON state object.
+    #[error("Invalid STATE payload: {0}")]
+    InvalidStatePayload(String),
+
+    /// I/O error while writing exported data.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
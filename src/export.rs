@@ -0,0 +1,130 @@
+//! CSV export of decoded metric streams.
+//!
+//! Each metric becomes one row with the columns
+//! `timestamp,group,node,device,metric,type,value`, which is convenient for
+//! quick ad-hoc analysis in a spreadsheet or pandas.
+
+use crate::error::Result;
+use crate::subscriber::Message;
+use crate::topic::ParsedTopic;
+use crate::types::Metric;
+use std::io::Write;
+
+/// Column names written by [`CsvWriter::write_header`].
+pub const CSV_HEADER: [&str; 7] = [
+    "timestamp",
+    "group",
+    "node",
+    "device",
+    "metric",
+    "type",
+    "value",
+];
+
+/// Writes decoded Sparkplug metrics as CSV rows.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{CsvWriter, Message};
+///
+/// # fn example(msg: Message) -> Result<(), sparkplug_rs::Error> {
+/// let mut csv = CsvWriter::new(std::io::stdout());
+/// csv.write_header()?;
+/// csv.write_message(&msg)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct CsvWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// Creates a new CSV writer wrapping the given output.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes the header row.
+    pub fn write_header(&mut self) -> Result<()> {
+        writeln!(self.writer, "{}", CSV_HEADER.join(","))?;
+        Ok(())
+    }
+
+    /// Writes a single metric row.
+    ///
+    /// The metric timestamp is used when present, otherwise `fallback_timestamp`
+    /// (typically the payload timestamp). Alias-only metrics are written with
+    /// their alias number in the `metric` column.
+    pub fn write_metric(
+        &mut self,
+        topic: &ParsedTopic,
+        metric: &Metric,
+        fallback_timestamp: Option<u64>,
+    ) -> Result<()> {
+        let timestamp = metric
+            .timestamp
+            .or(fallback_timestamp)
+            .map(|ts| ts.to_string())
+            .unwrap_or_default();
+        let name = match (&metric.name, metric.alias) {
+            (Some(name), _) => name.clone(),
+            (None, Some(alias)) => alias.to_string(),
+            (None, None) => String::new(),
+        };
+
+        let fields = [
+            timestamp,
+            topic.group_id().unwrap_or_default().to_string(),
+            topic.edge_node_id().unwrap_or_default().to_string(),
+            topic.device_id().unwrap_or_default().to_string(),
+            name,
+            metric.datatype.to_string(),
+            metric.value.to_string(),
+        ];
+
+        let row: Vec<String> = fields.iter().map(|f| escape_field(f)).collect();
+        writeln!(self.writer, "{}", row.join(","))?;
+        Ok(())
+    }
+
+    /// Parses a received message and writes one row per metric.
+    ///
+    /// STATE messages carry no Sparkplug payload and are skipped. Returns the
+    /// number of rows written.
+    pub fn write_message(&mut self, message: &Message) -> Result<usize> {
+        let topic = message.parse_topic()?;
+        if topic.message_type().is_none() {
+            return Ok(0);
+        }
+
+        let payload = message.parse_payload()?;
+        let fallback = payload.timestamp();
+        let mut rows = 0;
+        for metric in payload.metrics() {
+            self.write_metric(&topic, &metric?, fallback)?;
+            rows += 1;
+        }
+        Ok(rows)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Consumes the CSV writer and returns the underlying output.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Quotes a field if it contains a delimiter, quote or line break.
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,149 @@
+//! Content-based message filtering.
+//!
+//! Filters registered with [`Subscriber::add_filter`](crate::Subscriber::add_filter)
+//! are evaluated before the message callback runs; a message is delivered only
+//! if every filter accepts it. Topic filters are checked first, and the payload
+//! is decoded at most once, only when a payload filter needs it, so consumers
+//! that discard most traffic skip the decoding work.
+
+use crate::payload::Payload;
+use crate::subscriber::Message;
+use crate::topic::{MessageType, ParsedTopic};
+
+/// Custom predicate on a decoded payload.
+pub type PayloadPredicate = Box<dyn Fn(&ParsedTopic, &Payload) -> bool + Send + 'static>;
+
+/// A predicate deciding whether a received message is delivered.
+///
+/// Metric filters match by name. Data messages that only carry aliases have
+/// no names, so use [`MessageFilter::Payload`] with your own alias map for them.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::filter::MessageFilter;
+/// use sparkplug_rs::{Message, MessageType, Subscriber, SubscriberConfig};
+///
+/// let config = SubscriberConfig::new("tcp://localhost:1883", "thin_consumer", "Energy");
+/// let mut subscriber = Subscriber::new(config, Box::new(|msg: Message| {
+///     println!("Over temperature: {}", msg.topic);
+/// }))?;
+///
+/// subscriber.add_filter(MessageFilter::MessageTypes(vec![MessageType::NData]));
+/// subscriber.add_filter(MessageFilter::MetricAbove {
+///     name: "Temperature".to_string(),
+///     threshold: 80.0,
+/// });
+///
+/// subscriber.connect()?;
+/// subscriber.subscribe_all()?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+pub enum MessageFilter {
+    /// Only deliver these message types.
+    MessageTypes(Vec<MessageType>),
+    /// Only deliver messages from these edge nodes.
+    EdgeNodes(Vec<String>),
+    /// Only deliver payloads containing a metric with this name.
+    HasMetric(String),
+    /// Only deliver payloads where the named metric is numeric and above `threshold`.
+    MetricAbove {
+        /// Metric name
+        name: String,
+        /// Exclusive lower bound
+        threshold: f64,
+    },
+    /// Only deliver payloads where the named metric is numeric and below `threshold`.
+    MetricBelow {
+        /// Metric name
+        name: String,
+        /// Exclusive upper bound
+        threshold: f64,
+    },
+    /// Only deliver payloads accepted by a custom predicate.
+    Payload(PayloadPredicate),
+}
+
+impl MessageFilter {
+    fn needs_payload(&self) -> bool {
+        !matches!(
+            self,
+            MessageFilter::MessageTypes(_) | MessageFilter::EdgeNodes(_)
+        )
+    }
+
+    fn accepts_topic(&self, topic: &ParsedTopic) -> bool {
+        match self {
+            MessageFilter::MessageTypes(types) => topic
+                .message_type()
+                .is_some_and(|msg_type| types.contains(&msg_type)),
+            MessageFilter::EdgeNodes(nodes) => topic
+                .edge_node_id()
+                .is_some_and(|node| nodes.iter().any(|n| n == node)),
+            _ => true,
+        }
+    }
+
+    fn accepts_payload(&self, topic: &ParsedTopic, payload: &Payload) -> bool {
+        let metric_value = |wanted: &str| {
+            payload
+                .metrics()
+                .flatten()
+                .find(|m| m.name.as_deref() == Some(wanted))
+                .and_then(|m| m.value.as_f64())
+        };
+        match self {
+            MessageFilter::HasMetric(name) => payload
+                .metrics()
+                .flatten()
+                .any(|m| m.name.as_deref() == Some(name.as_str())),
+            MessageFilter::MetricAbove { name, threshold } => {
+                metric_value(name).is_some_and(|v| v > *threshold)
+            }
+            MessageFilter::MetricBelow { name, threshold } => {
+                metric_value(name).is_some_and(|v| v < *threshold)
+            }
+            MessageFilter::Payload(predicate) => predicate(topic, payload),
+            _ => true,
+        }
+    }
+}
+
+/// Returns true if every filter accepts the message.
+///
+/// Messages whose topic or payload cannot be parsed are rejected unless the
+/// filter list is empty.
+pub fn accepts(filters: &[MessageFilter], message: &Message) -> bool {
+    if filters.is_empty() {
+        return true;
+    }
+    let Ok(topic) = message.parse_topic() else {
+        return false;
+    };
+    let payload = std::cell::OnceCell::new();
+    accepts_parsed(filters, &topic, || {
+        payload
+            .get_or_init(|| message.parse_payload())
+            .as_ref()
+            .ok()
+    })
+}
+
+/// Like [`accepts`], for a message whose topic is already parsed. `payload`
+/// is only called if a filter needs the payload.
+pub(crate) fn accepts_parsed<'a>(
+    filters: &[MessageFilter],
+    topic: &ParsedTopic,
+    payload: impl FnOnce() -> Option<&'a Payload>,
+) -> bool {
+    if !filters.iter().all(|f| f.accepts_topic(topic)) {
+        return false;
+    }
+    if !filters.iter().any(MessageFilter::needs_payload) {
+        return true;
+    }
+    let Some(payload) = payload() else {
+        return false;
+    };
+    filters.iter().all(|f| f.accepts_payload(topic, payload))
+}
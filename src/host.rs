@@ -0,0 +1,362 @@
+//! Sparkplug Host Application support.
+//!
+//! A Host Application publishes its online state on the STATE topic and sends
+//! commands (NCMD/DCMD) to edge nodes. [`HostApplication`] declares the host
+//! ID once and manages the per-group publishers the C API needs to send
+//! commands, so applications no longer wire up a [`Publisher`] with a made-up
+//! edge node ID themselves.
+
+use crate::error::{Error, Result};
+use crate::payload::PayloadBuilder;
+use crate::publisher::{Publisher, PublisherConfig};
+use crate::rebirth::{AutoRebirth, RebirthRequest};
+use crate::reconnect::ReconnectPolicy;
+use crate::subscriber::Message;
+
+/// Metric name of the rebirth request in an NCMD.
+pub const REBIRTH_METRIC: &str = "Node Control/Rebirth";
+
+/// A decoded STATE message payload.
+///
+/// Sparkplug 2.2 hosts publish the plain strings `ONLINE` and `OFFLINE`;
+/// Sparkplug 3.0 hosts publish `{"online": <bool>, "timestamp": <u64>}`.
+/// Both forms parse to the same type; `timestamp` is `None` for 2.2 payloads.
+///
+/// # Example
+///
+/// ```
+/// use sparkplug_rs::StatePayload;
+///
+/// let state = StatePayload::parse(br#"{"online": true, "timestamp": 1700000000000}"#)?;
+/// assert!(state.online);
+/// assert_eq!(state.timestamp, Some(1700000000000));
+///
+/// assert!(!StatePayload::parse(b"OFFLINE")?.online);
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatePayload {
+    /// Whether the host application is online.
+    pub online: bool,
+    /// When the host changed state (ms since epoch), for Sparkplug 3.0 payloads.
+    pub timestamp: Option<u64>,
+}
+
+impl StatePayload {
+    /// Parses a STATE payload in either the 2.2 or the 3.0 format.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let text = std::str::from_utf8(data)?.trim();
+        match text {
+            "ONLINE" => {
+                return Ok(Self {
+                    online: true,
+                    timestamp: None,
+                })
+            }
+            "OFFLINE" => {
+                return Ok(Self {
+                    online: false,
+                    timestamp: None,
+                })
+            }
+            _ => {}
+        }
+
+        let invalid = || Error::InvalidStatePayload(text.to_string());
+        let body = text
+            .strip_prefix('{')
+            .and_then(|t| t.strip_suffix('}'))
+            .ok_or_else(invalid)?;
+
+        let mut online = None;
+        let mut timestamp = None;
+        for field in body.split(',').filter(|f| !f.trim().is_empty()) {
+            let (key, value) = field.split_once(':').ok_or_else(invalid)?;
+            match (key.trim().trim_matches('"'), value.trim()) {
+                ("online", "true") => online = Some(true),
+                ("online", "false") => online = Some(false),
+                ("online", _) => return Err(invalid()),
+                ("timestamp", value) => timestamp = Some(value.parse().map_err(|_| invalid())?),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            online: online.ok_or_else(invalid)?,
+            timestamp,
+        })
+    }
+}
+
+/// Configuration for a Sparkplug Host Application.
+#[derive(Debug, Clone)]
+pub struct HostApplicationConfig {
+    /// MQTT broker URL (e.g., "tcp://localhost:1883").
+    pub broker_url: String,
+    /// MQTT client identifier prefix (suffixed with the group ID per connection).
+    pub client_id: String,
+    /// Primary host ID, used for the `STATE/<host_id>` topic.
+    pub host_id: String,
+    /// Sparkplug groups this host sends commands to.
+    pub groups: Vec<String>,
+    /// Retry policy for [`connect`](HostApplication::connect) and
+    /// [`reconnect`](HostApplication::reconnect); `None` makes a single
+    /// connect attempt and lets reconnect retry forever.
+    pub reconnect: Option<ReconnectPolicy>,
+}
+
+impl HostApplicationConfig {
+    /// Creates a new host application configuration.
+    pub fn new(
+        broker_url: impl Into<String>,
+        client_id: impl Into<String>,
+        host_id: impl Into<String>,
+        groups: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            broker_url: broker_url.into(),
+            client_id: client_id.into(),
+            host_id: host_id.into(),
+            groups: groups.into_iter().map(Into::into).collect(),
+            reconnect: None,
+        }
+    }
+}
+
+/// A Sparkplug Host Application.
+///
+/// On [`connect`](Self::connect) the host publishes its STATE birth; on
+/// [`disconnect`](Self::disconnect) it publishes the matching STATE death.
+/// Commands are scoped to the configured groups.
+///
+/// The C API does not allow a custom MQTT will, so an unclean disconnect
+/// is not announced on the STATE topic.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::host::{HostApplication, HostApplicationConfig};
+///
+/// let config = HostApplicationConfig::new(
+///     "tcp://localhost:1883",
+///     "scada",
+///     "SCADA01",
+///     ["Energy"],
+/// );
+///
+/// let mut host = HostApplication::new(config)?;
+/// host.connect()?;
+/// host.request_rebirth("Energy", "Gateway01")?;
+/// host.disconnect()?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+pub struct HostApplication {
+    host_id: String,
+    publishers: Vec<(String, Publisher)>,
+    state_timestamp: Option<u64>,
+    auto_rebirth: Option<AutoRebirth>,
+}
+
+impl HostApplication {
+    /// Creates a new host application with one command publisher per group.
+    pub fn new(config: HostApplicationConfig) -> Result<Self> {
+        if config.groups.is_empty() {
+            return Err(Error::CreateFailed {
+                component: "HostApplication",
+                details: "at least one group is required".to_string(),
+            });
+        }
+
+        let publishers = config
+            .groups
+            .iter()
+            .map(|group| {
+                let mut publisher_config = PublisherConfig::new(
+                    config.broker_url.as_str(),
+                    format!("{}_{}", config.client_id, group),
+                    group.as_str(),
+                    config.host_id.as_str(),
+                );
+                publisher_config.reconnect = config.reconnect.clone();
+                Ok((group.clone(), Publisher::new(publisher_config)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            host_id: config.host_id,
+            publishers,
+            state_timestamp: None,
+            auto_rebirth: None,
+        })
+    }
+
+    /// Returns the primary host ID.
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Returns the groups this host sends commands to.
+    pub fn groups(&self) -> impl Iterator<Item = &str> {
+        self.publishers.iter().map(|(g, _)| g.as_str())
+    }
+
+    /// Returns true between the STATE birth and the STATE death.
+    pub fn is_online(&self) -> bool {
+        self.state_timestamp.is_some()
+    }
+
+    /// Returns the timestamp of the current STATE birth, if online.
+    pub fn state_timestamp(&self) -> Option<u64> {
+        self.state_timestamp
+    }
+
+    /// Connects to the broker and publishes the STATE birth.
+    pub fn connect(&mut self) -> Result<()> {
+        for (_, publisher) in &mut self.publishers {
+            publisher.connect()?;
+        }
+
+        self.publish_state_birth()
+    }
+
+    /// Re-establishes the connections after a connection loss and
+    /// republishes the STATE birth with a new timestamp.
+    ///
+    /// Edge nodes that saw the host go offline use the new birth to resume
+    /// publishing; see [`Publisher::reconnect`] for the retry behavior.
+    pub fn reconnect(&mut self) -> Result<()> {
+        self.state_timestamp = None;
+        for (_, publisher) in &mut self.publishers {
+            publisher.reconnect()?;
+        }
+        self.publish_state_birth()
+    }
+
+    fn publish_state_birth(&mut self) -> Result<()> {
+        let timestamp = crate::time::now_millis();
+        self.publishers[0]
+            .1
+            .publish_state_birth(&self.host_id, timestamp)?;
+        self.state_timestamp = Some(timestamp);
+        Ok(())
+    }
+
+    /// Publishes the STATE death and disconnects from the broker.
+    pub fn disconnect(&mut self) -> Result<()> {
+        if let Some(timestamp) = self.state_timestamp.take() {
+            self.publishers[0]
+                .1
+                .publish_state_death(&self.host_id, timestamp)?;
+        }
+        for (_, publisher) in &mut self.publishers {
+            publisher.disconnect()?;
+        }
+        Ok(())
+    }
+
+    /// Publishes an NCMD to an edge node in one of the configured groups.
+    pub fn send_node_command(
+        &mut self,
+        group_id: &str,
+        edge_node_id: &str,
+        payload: &[u8],
+    ) -> Result<()> {
+        self.publisher(group_id)?
+            .publish_node_command(edge_node_id, payload)
+    }
+
+    /// Publishes a DCMD to a device in one of the configured groups.
+    pub fn send_device_command(
+        &mut self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: &str,
+        payload: &[u8],
+    ) -> Result<()> {
+        self.publisher(group_id)?
+            .publish_device_command(edge_node_id, device_id, payload)
+    }
+
+    /// Asks an edge node to republish its births (`Node Control/Rebirth`).
+    pub fn request_rebirth(&mut self, group_id: &str, edge_node_id: &str) -> Result<()> {
+        let mut payload = PayloadBuilder::new()?;
+        payload.add_bool(REBIRTH_METRIC, true)?;
+        let bytes = payload.serialize()?;
+        self.send_node_command(group_id, edge_node_id, &bytes)
+    }
+
+    /// Enables automatic rebirth requests in [`handle_message`](Self::handle_message).
+    ///
+    /// Repeated requests to the same node are spaced by `backoff`; see
+    /// [`AutoRebirth`].
+    pub fn set_auto_rebirth(&mut self, backoff: ReconnectPolicy) {
+        self.auto_rebirth = Some(AutoRebirth::new(backoff));
+    }
+
+    /// Disables automatic rebirth requests.
+    pub fn clear_auto_rebirth(&mut self) {
+        self.auto_rebirth = None;
+    }
+
+    /// Feeds a received message to the automatic rebirth policy.
+    ///
+    /// When the message comes from a node without a current NBIRTH, or
+    /// breaks its sequence, and a request is due, the rebirth NCMD is
+    /// published and the request is returned. Messages from groups this host
+    /// is not configured for are tracked but never answered. Does nothing
+    /// unless [`set_auto_rebirth`](Self::set_auto_rebirth) was called.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{
+    ///     HostApplication, HostApplicationConfig, Message, ReconnectPolicy, Subscriber,
+    ///     SubscriberConfig,
+    /// };
+    ///
+    /// let config = HostApplicationConfig::new("tcp://localhost:1883", "scada", "SCADA01", ["Energy"]);
+    /// let mut host = HostApplication::new(config)?;
+    /// host.set_auto_rebirth(ReconnectPolicy::default());
+    /// host.connect()?;
+    ///
+    /// let sub_config = SubscriberConfig::new("tcp://localhost:1883", "scada_sub", "Energy");
+    /// let (mut subscriber, messages) = Subscriber::with_channel(sub_config)?;
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    ///
+    /// for msg in messages {
+    ///     if let Some(request) = host.handle_message(&msg)? {
+    ///         println!("asked {} to rebirth ({:?})", request.edge_node_id, request.reason);
+    ///     }
+    /// }
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn handle_message(&mut self, message: &Message) -> Result<Option<RebirthRequest>> {
+        let Some(request) = self
+            .auto_rebirth
+            .as_mut()
+            .and_then(|auto| auto.observe(message))
+        else {
+            return Ok(None);
+        };
+        if !self.publishers.iter().any(|(g, _)| *g == request.group_id) {
+            return Ok(None);
+        }
+        self.request_rebirth(&request.group_id, &request.edge_node_id)?;
+        Ok(Some(request))
+    }
+
+    fn publisher(&mut self, group_id: &str) -> Result<&mut Publisher> {
+        let host_id = &self.host_id;
+        self.publishers
+            .iter_mut()
+            .find(|(g, _)| g == group_id)
+            .map(|(_, p)| p)
+            .ok_or_else(|| {
+                Error::Unsupported(format!(
+                    "group '{}' is not configured for host '{}'",
+                    group_id, host_id
+                ))
+            })
+    }
+}
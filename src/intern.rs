@@ -0,0 +1,71 @@
+//! Metric name interning.
+//!
+//! Host applications parse the same metric names over and over. A
+//! [`NameCache`] keeps one shared `Arc<str>` per distinct name so parsed
+//! metrics (see [`Payload::metrics_interned`](crate::Payload::metrics_interned))
+//! don't each own a copy.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A cache of interned metric names.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::intern::NameCache;
+/// use sparkplug_rs::Payload;
+///
+/// # fn example(payloads: Vec<Payload>) -> Result<(), sparkplug_rs::Error> {
+/// let mut names = NameCache::new();
+/// for payload in &payloads {
+///     for metric in payload.metrics_interned(&mut names) {
+///         let metric = metric?;
+///         println!("{:?} = {}", metric.name, metric.value);
+///     }
+/// }
+/// println!("{} distinct names", names.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct NameCache {
+    names: HashSet<Arc<str>>,
+}
+
+impl NameCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared copy of `name`, adding it on first use.
+    pub fn intern(&mut self, name: &str) -> Arc<str> {
+        if let Some(existing) = self.names.get(name) {
+            return existing.clone();
+        }
+        let name: Arc<str> = Arc::from(name);
+        self.names.insert(name.clone());
+        name
+    }
+
+    /// Returns the number of distinct names.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if no names are cached.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Drops names no longer referenced outside the cache.
+    pub fn shrink(&mut self) {
+        self.names.retain(|name| Arc::strong_count(name) > 1);
+    }
+
+    /// Removes all names.
+    pub fn clear(&mut self) {
+        self.names.clear();
+    }
+}
@@ -0,0 +1,241 @@
+//! Sparkplug JSON representation (`json` feature).
+//!
+//! Converts payloads to and from the JSON layout commonly used by Sparkplug tooling
+//! (e.g. Eclipse Tahu): a `timestamp`, `seq` and `uuid` at the top level and a
+//! `metrics` array whose entries carry `name`, `alias`, `timestamp`,
+//! `dataType` and `value`. Absent fields are omitted.
+//!
+//! ```json
+//! {
+//!   "timestamp": 1700000000000,
+//!   "seq": 3,
+//!   "metrics": [
+//!     { "name": "Temperature", "alias": 1, "dataType": "Double", "value": 20.5 }
+//!   ]
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::payload::{Payload, PayloadBuilder};
+use crate::types::{DataType, Metric, MetricAlias, MetricValue};
+use serde_json::{Map, Value};
+
+impl MetricValue {
+    /// Converts the value to JSON (`null` for [`MetricValue::Null`]).
+    pub fn to_json_value(&self) -> Value {
+        match self {
+            MetricValue::Int8(v) => Value::from(*v),
+            MetricValue::Int16(v) => Value::from(*v),
+            MetricValue::Int32(v) => Value::from(*v),
+            MetricValue::Int64(v) => Value::from(*v),
+            MetricValue::UInt8(v) => Value::from(*v),
+            MetricValue::UInt16(v) => Value::from(*v),
+            MetricValue::UInt32(v) => Value::from(*v),
+            MetricValue::UInt64(v) | MetricValue::DateTime(v) => Value::from(*v),
+            MetricValue::Float(v) => Value::from(*v),
+            MetricValue::Double(v) => Value::from(*v),
+            MetricValue::Boolean(v) => Value::from(*v),
+            MetricValue::String(v) => Value::from(v.as_str()),
+            MetricValue::Null => Value::Null,
+        }
+    }
+}
+
+impl<N: AsRef<str>> Metric<N> {
+    /// Converts the metric to a Sparkplug JSON object.
+    pub fn to_json_value(&self) -> Value {
+        let mut object = Map::new();
+        if let Some(name) = &self.name {
+            object.insert("name".to_string(), Value::from(name.as_ref()));
+        }
+        if let Some(alias) = self.alias {
+            object.insert("alias".to_string(), Value::from(u64::from(alias)));
+        }
+        if let Some(timestamp) = self.timestamp {
+            object.insert("timestamp".to_string(), Value::from(timestamp));
+        }
+        object.insert("dataType".to_string(), Value::from(self.datatype.as_str()));
+        if self.is_null() {
+            object.insert("isNull".to_string(), Value::Bool(true));
+        }
+        object.insert("value".to_string(), self.value.to_json_value());
+        Value::Object(object)
+    }
+
+    /// Converts the metric to a Sparkplug JSON string.
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_string()
+    }
+}
+
+impl Payload {
+    /// Converts the payload to a Sparkplug JSON object.
+    pub fn to_json_value(&self) -> Result<Value> {
+        let mut object = Map::new();
+        if let Some(timestamp) = self.timestamp() {
+            object.insert("timestamp".to_string(), Value::from(timestamp));
+        }
+        if let Some(seq) = self.seq() {
+            object.insert("seq".to_string(), Value::from(seq));
+        }
+        if let Some(uuid) = self.uuid() {
+            object.insert("uuid".to_string(), Value::from(uuid));
+        }
+        let metrics = self
+            .metrics()
+            .map(|metric| Ok(metric?.to_json_value()))
+            .collect::<Result<Vec<_>>>()?;
+        object.insert("metrics".to_string(), Value::Array(metrics));
+        Ok(Value::Object(object))
+    }
+
+    /// Converts the payload to a Sparkplug JSON string.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::Message;
+    ///
+    /// # fn example(msg: Message) -> Result<(), sparkplug_rs::Error> {
+    /// let json = msg.parse_payload()?.to_json()?;
+    /// println!("{} {}", msg.topic, json);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> Result<String> {
+        Ok(self.to_json_value()?.to_string())
+    }
+}
+
+impl PayloadBuilder {
+    /// Builds a payload from Sparkplug JSON.
+    ///
+    /// Accepts the layout produced by [`Payload::to_json`]. `dataType` is
+    /// optional: without it, integers become Int64, other numbers Double,
+    /// booleans Boolean and strings String. The payload `uuid` and per-metric
+    /// timestamps are ignored because the C API cannot set them; metrics are
+    /// added with [`add_metric`](Self::add_metric), with the same limits.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::PayloadBuilder;
+    ///
+    /// let builder = PayloadBuilder::from_json(
+    ///     r#"{"timestamp": 1700000000000,
+    ///         "metrics": [{"name": "Temperature", "alias": 1, "dataType": "Double", "value": 20.5}]}"#,
+    /// )?;
+    /// let bytes = builder.serialize()?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self> {
+        let root: Value =
+            serde_json::from_str(json).map_err(|e| Error::InvalidJson(e.to_string()))?;
+        let object = root
+            .as_object()
+            .ok_or_else(|| invalid("payload must be a JSON object"))?;
+
+        let mut builder = PayloadBuilder::new()?;
+        if let Some(timestamp) = object.get("timestamp") {
+            builder.set_timestamp(as_u64(timestamp, "timestamp")?);
+        }
+        if let Some(seq) = object.get("seq") {
+            builder.set_seq(as_u64(seq, "seq")?);
+        }
+        let metrics = match object.get("metrics") {
+            Some(Value::Array(metrics)) => metrics.as_slice(),
+            Some(_) => return Err(invalid("'metrics' must be an array")),
+            None => &[],
+        };
+        for (index, metric) in metrics.iter().enumerate() {
+            let metric = metric_from_json(metric)
+                .map_err(|e| invalid(&format!("metrics[{}]: {}", index, e)))?;
+            builder.add_metric(&metric)?;
+        }
+        Ok(builder)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidJson(message.to_string())
+}
+
+fn as_u64(value: &Value, field: &str) -> Result<u64> {
+    value
+        .as_u64()
+        .ok_or_else(|| invalid(&format!("'{}' must be an unsigned integer", field)))
+}
+
+fn metric_from_json(value: &Value) -> std::result::Result<Metric, String> {
+    let object = value.as_object().ok_or("metric must be a JSON object")?;
+    let name = match object.get("name") {
+        Some(Value::String(name)) => Some(name.clone()),
+        Some(_) => return Err("'name' must be a string".to_string()),
+        None => None,
+    };
+    let alias = match object.get("alias") {
+        Some(alias) => Some(MetricAlias::new(
+            alias
+                .as_u64()
+                .ok_or("'alias' must be an unsigned integer")?,
+        )),
+        None => None,
+    };
+    let raw = object.get("value").unwrap_or(&Value::Null);
+    let datatype = match object.get("dataType") {
+        Some(Value::String(name)) => {
+            DataType::from_name(name).ok_or(format!("unknown dataType '{}'", name))?
+        }
+        Some(_) => return Err("'dataType' must be a string".to_string()),
+        None => infer_datatype(raw).ok_or("'dataType' is required for null values")?,
+    };
+    let is_null = raw.is_null() || object.get("isNull") == Some(&Value::Bool(true));
+    let value = if is_null {
+        MetricValue::Null
+    } else {
+        value_from_json(datatype, raw).ok_or(format!(
+            "value {} is not a valid {}",
+            raw,
+            datatype.as_str()
+        ))?
+    };
+
+    Ok(Metric {
+        name,
+        alias,
+        timestamp: None,
+        datatype,
+        value,
+    })
+}
+
+fn infer_datatype(value: &Value) -> Option<DataType> {
+    match value {
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some(DataType::Int64),
+        Value::Number(_) => Some(DataType::Double),
+        Value::Bool(_) => Some(DataType::Boolean),
+        Value::String(_) => Some(DataType::String),
+        _ => None,
+    }
+}
+
+fn value_from_json(datatype: DataType, value: &Value) -> Option<MetricValue> {
+    let int = || value.as_i64();
+    let uint = || value.as_u64();
+    Some(match datatype {
+        DataType::Int8 => MetricValue::Int8(int()?.try_into().ok()?),
+        DataType::Int16 => MetricValue::Int16(int()?.try_into().ok()?),
+        DataType::Int32 => MetricValue::Int32(int()?.try_into().ok()?),
+        DataType::Int64 => MetricValue::Int64(int()?),
+        DataType::UInt8 => MetricValue::UInt8(uint()?.try_into().ok()?),
+        DataType::UInt16 => MetricValue::UInt16(uint()?.try_into().ok()?),
+        DataType::UInt32 => MetricValue::UInt32(uint()?.try_into().ok()?),
+        DataType::UInt64 => MetricValue::UInt64(uint()?),
+        DataType::DateTime => MetricValue::DateTime(uint()?),
+        DataType::Float => MetricValue::Float(value.as_f64()? as f32),
+        DataType::Double => MetricValue::Double(value.as_f64()?),
+        DataType::Boolean => MetricValue::Boolean(value.as_bool()?),
+        DataType::String | DataType::Text => MetricValue::String(value.as_str()?.to_string()),
+        DataType::Unknown => return None,
+    })
+}
@@ -0,0 +1,181 @@
+//! Latency and clock-skew measurement.
+//!
+//! [`LatencyTracker`] compares the timestamps in received payloads with the
+//! local receive time, per edge node. A consistently large or negative delta
+//! usually means the node's clock is drifting rather than the network being
+//! slow.
+
+use crate::error::Result;
+use crate::payload::Payload;
+use crate::subscriber::Message;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// Receive-time delta statistics for one edge node.
+///
+/// Deltas are `receive time - payload timestamp` in milliseconds; a negative
+/// delta means the node's clock is ahead of the local clock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLatency {
+    /// Number of timestamped messages measured
+    pub count: u64,
+    /// Most recent delta (ms)
+    pub last_ms: i64,
+    /// Smallest delta seen (ms)
+    pub min_ms: i64,
+    /// Largest delta seen (ms)
+    pub max_ms: i64,
+    /// Mean delta (ms)
+    pub mean_ms: f64,
+}
+
+impl NodeLatency {
+    fn new(delta: i64) -> Self {
+        Self {
+            count: 1,
+            last_ms: delta,
+            min_ms: delta,
+            max_ms: delta,
+            mean_ms: delta as f64,
+        }
+    }
+
+    fn record(&mut self, delta: i64) {
+        self.count += 1;
+        self.last_ms = delta;
+        self.min_ms = self.min_ms.min(delta);
+        self.max_ms = self.max_ms.max(delta);
+        self.mean_ms += (delta as f64 - self.mean_ms) / self.count as f64;
+    }
+}
+
+/// A delta that exceeded the configured skew threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkewWarning {
+    /// Sparkplug group ID
+    pub group_id: String,
+    /// Edge node ID
+    pub edge_node_id: String,
+    /// Measured delta (ms)
+    pub delta_ms: i64,
+}
+
+/// Callback invoked when a delta exceeds the skew threshold.
+pub type SkewCallback = Box<dyn Fn(&SkewWarning) + Send + 'static>;
+
+/// Tracks receive-time deltas per edge node.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::latency::LatencyTracker;
+/// use sparkplug_rs::Message;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// let mut tracker = LatencyTracker::new();
+/// tracker.on_skew(
+///     Duration::from_secs(2),
+///     Box::new(|w| eprintln!("{}/{} clock skew {} ms", w.group_id, w.edge_node_id, w.delta_ms)),
+/// );
+/// let tracker = Arc::new(Mutex::new(tracker));
+/// let tracker_clone = tracker.clone();
+/// let callback = Box::new(move |msg: Message| {
+///     let _ = tracker_clone.lock().unwrap().record(&msg);
+/// });
+/// // pass `callback` to Subscriber::new, then later:
+/// let stats = tracker.lock().unwrap().get("Energy", "Gateway01").cloned();
+/// if let Some(stats) = stats {
+///     println!("mean delta: {:.1} ms", stats.mean_ms);
+/// }
+/// ```
+#[derive(Default)]
+pub struct LatencyTracker {
+    nodes: HashMap<(String, String), NodeLatency>,
+    threshold: Option<Duration>,
+    skew_callback: Option<SkewCallback>,
+}
+
+impl LatencyTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a callback invoked when the absolute delta exceeds `threshold`.
+    pub fn on_skew(&mut self, threshold: Duration, callback: SkewCallback) {
+        self.threshold = Some(threshold);
+        self.skew_callback = Some(callback);
+    }
+
+    /// Records a message received now.
+    ///
+    /// Returns the measured delta, or `None` if the message carries no
+    /// timestamp (e.g. STATE or untimestamped payloads).
+    pub fn record(&mut self, message: &Message) -> Result<Option<i64>> {
+        self.record_at(message, SystemTime::now())
+    }
+
+    /// Records a message received at `received`.
+    pub fn record_at(&mut self, message: &Message, received: SystemTime) -> Result<Option<i64>> {
+        let topic = message.parse_topic()?;
+        let (Some(msg_type), Some(group_id), Some(edge_node_id)) =
+            (topic.message_type(), topic.group_id(), topic.edge_node_id())
+        else {
+            return Ok(None);
+        };
+        if msg_type.is_command() {
+            return Ok(None);
+        }
+        let payload = message.parse_payload()?;
+        Ok(self.record_payload(group_id, edge_node_id, &payload, received))
+    }
+
+    /// Records an already parsed payload from an edge node.
+    ///
+    /// The payload timestamp is used, falling back to the newest metric timestamp.
+    pub fn record_payload(
+        &mut self,
+        group_id: &str,
+        edge_node_id: &str,
+        payload: &Payload,
+        received: SystemTime,
+    ) -> Option<i64> {
+        let sent = payload
+            .timestamp()
+            .or_else(|| payload.metrics().filter_map(|m| m.ok()?.timestamp).max())?;
+        let delta = crate::time::to_millis(received) as i64 - sent as i64;
+
+        self.nodes
+            .entry((group_id.to_string(), edge_node_id.to_string()))
+            .and_modify(|n| n.record(delta))
+            .or_insert_with(|| NodeLatency::new(delta));
+
+        if let (Some(threshold), Some(callback)) = (self.threshold, &self.skew_callback) {
+            if delta.unsigned_abs() > threshold.as_millis() as u64 {
+                callback(&SkewWarning {
+                    group_id: group_id.to_string(),
+                    edge_node_id: edge_node_id.to_string(),
+                    delta_ms: delta,
+                });
+            }
+        }
+        Some(delta)
+    }
+
+    /// Gets the statistics of an edge node.
+    pub fn get(&self, group_id: &str, edge_node_id: &str) -> Option<&NodeLatency> {
+        self.nodes
+            .get(&(group_id.to_string(), edge_node_id.to_string()))
+    }
+
+    /// Returns an iterator over `((group, edge node), statistics)`.
+    pub fn iter(&self) -> impl Iterator<Item = (&(String, String), &NodeLatency)> {
+        self.nodes.iter()
+    }
+
+    /// Clears all statistics.
+    pub fn reset(&mut self) {
+        self.nodes.clear();
+    }
+}
@@ -17,9 +17,30 @@
 //! The library is organized into several modules:
 //!
 //! - [`Publisher`]: Publish node and device data (NBIRTH, NDATA, DBIRTH, DDATA)
+//! - [`PublisherHandle`]: Share one publisher across threads with `&self` methods
+//! - [`AliasAllocator`]: Assign unique, stable metric aliases
+//! - [`MetricRegistry`]: Declare node metrics once and build the NBIRTH from them
 //! - [`Subscriber`]: Subscribe to messages with callback handlers
+//! - [`stream`]: Receive messages as an async `Stream` (`async` feature)
+//! - [`filter`]: Filter received messages by topic or payload content
+//! - [`EventBus`]: Fan decoded messages out to multiple consumers
+//! - [`SequenceTracker`]: Validate sequence numbers (wrap and rebirth reset)
+//! - [`HostApplication`]: Publish host STATE and send scoped commands
+//! - [`StatePayload`]: Parse 2.2 and 3.0 STATE payloads
+//! - [`AutoRebirth`]: Decide when a primary host requests a rebirth, with backoff
+//! - [`CommandWaiter`]: Wait for command confirmations (blocking or async)
 //! - [`PayloadBuilder`]: Build payloads with type-safe metric additions
+//! - [`BirthPayloadBuilder`]: Build NBIRTH/DBIRTH payloads that follow the birth rules
+//! - [`DataPayloadBuilder`]: Build NDATA/DDATA payloads from aliases only
 //! - [`Payload`]: Parse and read received payloads
+//! - [`validate`]: Check births and data messages against Sparkplug payload rules
+//! - [`json`]: Sparkplug JSON conversion (`json` feature)
+//! - [`CsvWriter`]: Export decoded metrics as CSV rows
+//! - [`MetricStore`]: Track the latest metric values on the host side
+//! - [`LatencyTracker`]: Measure per-node latency and clock skew
+//! - [`SparkplugMetrics`]: Map struct fields to metrics (`derive` feature)
+//! - [`codegen`]: Generate metric constants and structs from tag list exports
+//! - [`DerivedMetrics`]: Compute derived tags (rates, rolling statistics, expressions)
 //!
 //! # Example: Publisher
 //!
@@ -97,18 +118,68 @@
 #![warn(missing_docs)]
 #![allow(unsafe_op_in_unsafe_fn)]
 
+mod backlog;
+mod dry_run;
 mod sys;
+mod wire;
 
+pub mod alarm;
+pub mod alias;
+pub mod bus;
+pub mod codegen;
+pub mod command;
+pub mod derived;
 pub mod error;
+pub mod export;
+pub mod filter;
+pub mod host;
+pub mod intern;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod latency;
 pub mod payload;
 pub mod publisher;
+pub mod rebirth;
+pub mod reconnect;
+pub mod registry;
+pub mod sequence;
+pub mod store;
+#[cfg(feature = "async")]
+pub mod stream;
 pub mod subscriber;
+pub mod time;
 pub mod topic;
+pub mod typed;
 pub mod types;
+pub mod validate;
 
+pub use alias::AliasAllocator;
+pub use bus::{EventBus, EventReceiver, SparkplugEvent};
+pub use command::{CommandWaiter, Confirmation, PendingCommand};
+pub use derived::{Derivation, DerivedMetrics};
 pub use error::{Error, Result};
-pub use payload::{Payload, PayloadBuilder};
-pub use publisher::{Publisher, PublisherConfig};
-pub use subscriber::{Message, Subscriber, SubscriberConfig};
+pub use export::CsvWriter;
+pub use host::{HostApplication, HostApplicationConfig, StatePayload};
+pub use latency::LatencyTracker;
+pub use payload::{BirthPayloadBuilder, DataPayloadBuilder, Payload, PayloadBuilder};
+pub use publisher::{
+    DeviceBirthPolicy, DeviceHandle, Publisher, PublisherConfig, PublisherConfigBuilder,
+    PublisherHandle,
+};
+pub use rebirth::{AutoRebirth, RebirthReason, RebirthRequest};
+pub use reconnect::ReconnectPolicy;
+pub use registry::MetricRegistry;
+pub use sequence::{SequenceStatus, SequenceTracker};
+pub use store::{MetricKey, MetricSample, MetricStore, Quality, WatchId};
+#[cfg(feature = "async")]
+pub use stream::MessageStream;
+pub use subscriber::{Message, MessageHandler, PausePolicy, Subscriber, SubscriberConfig};
 pub use topic::{MessageType, ParsedTopic};
-pub use types::{DataType, Metric, MetricAlias, MetricValue};
+pub use typed::{MetricField, SparkplugMetrics};
+pub use types::{
+    DataType, InternedMetric, Metric, MetricAlias, MetricRef, MetricValue, MetricValueRef,
+    PayloadSnapshot,
+};
+
+#[cfg(feature = "derive")]
+pub use sparkplug_rs_derive::SparkplugMetrics;
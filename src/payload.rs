@@ -1,12 +1,26 @@
 //! Sparkplug payload building and parsing.
 
 use crate::error::{Error, Result};
+use crate::intern::NameCache;
 use crate::sys;
-use crate::types::{DataType, Metric, MetricAlias, MetricValue};
+use crate::types::{
+    DataType, InternedMetric, Metric, MetricAlias, MetricRef, MetricValue, MetricValueRef,
+    PayloadSnapshot,
+};
+use crate::validate::{self, Violation};
+use crate::wire;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CStr;
+use std::time::SystemTime;
 
-/// Maximum payload size for serialization.
-const MAX_PAYLOAD_SIZE: usize = 65536;
+/// Maximum payload size for serialization (the MQTT packet size limit).
+const MAX_PAYLOAD_SIZE: usize = 268_435_455;
+
+/// Upper bound on the encoded size of the payload-level fields.
+const PAYLOAD_SIZE_BOUND: usize = 64;
+
+/// Upper bound on the encoded size of a metric, excluding its strings.
+const METRIC_SIZE_BOUND: usize = 64;
 
 /// A Sparkplug payload builder for creating NBIRTH, NDATA, and other messages.
 ///
@@ -27,6 +41,10 @@ const MAX_PAYLOAD_SIZE: usize = 65536;
 /// ```
 pub struct PayloadBuilder {
     inner: *mut sys::sparkplug_payload_t,
+    /// Upper bound on the serialized size, grown by every added metric.
+    size_bound: usize,
+    /// Index and datatype of the metrics added as null
+    nulls: Vec<(usize, DataType)>,
 }
 
 impl PayloadBuilder {
@@ -39,7 +57,17 @@ impl PayloadBuilder {
                 details: "sparkplug_payload_create returned null".to_string(),
             });
         }
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            size_bound: PAYLOAD_SIZE_BOUND,
+            nulls: Vec::new(),
+        })
+    }
+
+    fn count_metric(&mut self, string_bytes: usize) {
+        self.size_bound = self
+            .size_bound
+            .saturating_add(METRIC_SIZE_BOUND + string_bytes);
     }
 
     /// Sets the payload-level timestamp in milliseconds since Unix epoch.
@@ -50,6 +78,11 @@ impl PayloadBuilder {
         self
     }
 
+    /// Sets the payload-level timestamp from a [`SystemTime`].
+    pub fn set_time(&mut self, time: SystemTime) -> &mut Self {
+        self.set_timestamp(crate::time::to_millis(time))
+    }
+
     /// Sets the sequence number manually (not recommended in normal operation).
     pub fn set_seq(&mut self, seq: u64) -> &mut Self {
         unsafe {
@@ -67,6 +100,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_int8(&mut self, name: &str, value: i8) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_int8(self.inner, c_name.as_ptr(), value);
         }
@@ -78,6 +112,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_int16(&mut self, name: &str, value: i16) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_int16(self.inner, c_name.as_ptr(), value);
         }
@@ -89,6 +124,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_int32(&mut self, name: &str, value: i32) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_int32(self.inner, c_name.as_ptr(), value);
         }
@@ -100,6 +136,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_int64(&mut self, name: &str, value: i64) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_int64(self.inner, c_name.as_ptr(), value);
         }
@@ -111,6 +148,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_uint8(&mut self, name: &str, value: u8) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_uint8(self.inner, c_name.as_ptr(), value);
         }
@@ -122,6 +160,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_uint16(&mut self, name: &str, value: u16) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_uint16(self.inner, c_name.as_ptr(), value);
         }
@@ -133,6 +172,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_uint32(&mut self, name: &str, value: u32) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_uint32(self.inner, c_name.as_ptr(), value);
         }
@@ -144,6 +184,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_uint64(&mut self, name: &str, value: u64) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_uint64(self.inner, c_name.as_ptr(), value);
         }
@@ -155,6 +196,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_float(&mut self, name: &str, value: f32) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_float(self.inner, c_name.as_ptr(), value);
         }
@@ -166,6 +208,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_double(&mut self, name: &str, value: f64) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_double(self.inner, c_name.as_ptr(), value);
         }
@@ -177,6 +220,7 @@ impl PayloadBuilder {
     /// Returns an error if the name contains null bytes.
     pub fn add_bool(&mut self, name: &str, value: bool) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_bool(self.inner, c_name.as_ptr(), value);
         }
@@ -189,12 +233,37 @@ impl PayloadBuilder {
     pub fn add_string(&mut self, name: &str, value: &str) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let c_value = std::ffi::CString::new(value)?;
+        self.count_metric(name.len() + value.len());
         unsafe {
             sys::sparkplug_payload_add_string(self.inner, c_name.as_ptr(), c_value.as_ptr());
         }
         Ok(self)
     }
 
+    /// Adds a DateTime metric by name.
+    ///
+    /// Accepts a [`SystemTime`](std::time::SystemTime) or, with the `chrono`
+    /// feature, a `chrono::DateTime<Utc>`. The C API has no DateTime setter,
+    /// so the value is published as a UInt64 metric holding milliseconds
+    /// since the Unix epoch: births advertise UInt64 for it, and subscribers
+    /// read it back as [`MetricValue::UInt64`].
+    pub fn add_datetime(
+        &mut self,
+        name: &str,
+        time: impl Into<std::time::SystemTime>,
+    ) -> Result<&mut Self> {
+        self.add_uint64(name, crate::time::to_millis(time.into()))
+    }
+
+    /// Adds a UUID metric by name.
+    ///
+    /// The C API has no UUID datatype, so the value is published as a
+    /// hyphenated string metric.
+    #[cfg(feature = "uuid")]
+    pub fn add_uuid(&mut self, name: &str, value: uuid::Uuid) -> Result<&mut Self> {
+        self.add_string(name, &value.hyphenated().to_string())
+    }
+
     // ===== Metric functions with alias (for NBIRTH) =====
 
     /// Adds an int32 metric with both name and alias (for NBIRTH).
@@ -208,6 +277,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_int32_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -225,6 +295,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_int64_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -242,6 +313,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_uint32_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -259,6 +331,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_uint64_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -276,6 +349,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_float_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -293,6 +367,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_double_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -310,6 +385,7 @@ impl PayloadBuilder {
     ) -> Result<&mut Self> {
         let c_name = std::ffi::CString::new(name)?;
         let alias: u64 = alias.into().into();
+        self.count_metric(name.len());
         unsafe {
             sys::sparkplug_payload_add_bool_with_alias(self.inner, c_name.as_ptr(), alias, value);
         }
@@ -321,6 +397,7 @@ impl PayloadBuilder {
     /// Adds an int32 metric by alias only (for NDATA).
     pub fn add_int32_by_alias(&mut self, alias: impl Into<MetricAlias>, value: i32) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_int32_by_alias(self.inner, alias, value);
         }
@@ -330,6 +407,7 @@ impl PayloadBuilder {
     /// Adds an int64 metric by alias only (for NDATA).
     pub fn add_int64_by_alias(&mut self, alias: impl Into<MetricAlias>, value: i64) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_int64_by_alias(self.inner, alias, value);
         }
@@ -339,6 +417,7 @@ impl PayloadBuilder {
     /// Adds a uint32 metric by alias only (for NDATA).
     pub fn add_uint32_by_alias(&mut self, alias: impl Into<MetricAlias>, value: u32) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_uint32_by_alias(self.inner, alias, value);
         }
@@ -348,6 +427,7 @@ impl PayloadBuilder {
     /// Adds a uint64 metric by alias only (for NDATA).
     pub fn add_uint64_by_alias(&mut self, alias: impl Into<MetricAlias>, value: u64) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_uint64_by_alias(self.inner, alias, value);
         }
@@ -357,6 +437,7 @@ impl PayloadBuilder {
     /// Adds a float metric by alias only (for NDATA).
     pub fn add_float_by_alias(&mut self, alias: impl Into<MetricAlias>, value: f32) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_float_by_alias(self.inner, alias, value);
         }
@@ -366,6 +447,7 @@ impl PayloadBuilder {
     /// Adds a double metric by alias only (for NDATA).
     pub fn add_double_by_alias(&mut self, alias: impl Into<MetricAlias>, value: f64) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_double_by_alias(self.inner, alias, value);
         }
@@ -375,12 +457,251 @@ impl PayloadBuilder {
     /// Adds a boolean metric by alias only (for NDATA).
     pub fn add_bool_by_alias(&mut self, alias: impl Into<MetricAlias>, value: bool) -> &mut Self {
         let alias: u64 = alias.into().into();
+        self.count_metric(0);
         unsafe {
             sys::sparkplug_payload_add_bool_by_alias(self.inner, alias, value);
         }
         self
     }
 
+    // ===== Null metrics =====
+
+    /// Adds a null metric by name.
+    ///
+    /// The metric declares `datatype` but has no value, which tells
+    /// subscribers its value is unknown, for example after losing contact
+    /// with the tag. The C API always writes a value, so the metric is added
+    /// as a placeholder and marked null when the payload is serialized.
+    pub fn add_null(&mut self, name: &str, datatype: DataType) -> Result<&mut Self> {
+        self.add_uint64(name, 0)?;
+        self.mark_last_null(datatype);
+        Ok(self)
+    }
+
+    /// Adds a null metric with both name and alias; see [`add_null`](Self::add_null).
+    pub fn add_null_with_alias(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        datatype: DataType,
+    ) -> Result<&mut Self> {
+        self.add_uint64_with_alias(name, alias, 0)?;
+        self.mark_last_null(datatype);
+        Ok(self)
+    }
+
+    /// Adds a null metric by alias only; see [`add_null`](Self::add_null).
+    pub fn add_null_by_alias(
+        &mut self,
+        alias: impl Into<MetricAlias>,
+        datatype: DataType,
+    ) -> &mut Self {
+        self.add_uint64_by_alias(alias, 0);
+        self.mark_last_null(datatype);
+        self
+    }
+
+    fn mark_last_null(&mut self, datatype: DataType) {
+        let count = unsafe { sys::sparkplug_payload_get_metric_count(self.inner) };
+        self.nulls.push((count - 1, datatype));
+    }
+
+    // ===== Copying parsed metrics =====
+
+    /// Adds a copy of a parsed metric, keeping its name and alias.
+    ///
+    /// DateTime values are added as UInt64 millis. Per-metric timestamps are
+    /// not carried over (the C API cannot set them). Null values are added
+    /// with [`add_null`](Self::add_null). Returns [`Error::Unsupported`] for
+    /// null values without a datatype, for metrics without a name or alias,
+    /// and for name/alias combinations the C API cannot express (8/16-bit
+    /// integers and strings by alias).
+    pub fn add_metric<N: AsRef<str>>(&mut self, metric: &Metric<N>) -> Result<&mut Self> {
+        let name = metric.name.as_ref().map(|n| n.as_ref());
+        let alias = metric.alias;
+        if name.is_none() && alias.is_none() {
+            return Err(Error::Unsupported(
+                "metric without name or alias".to_string(),
+            ));
+        }
+        let unsupported = |what: &str| {
+            Error::Unsupported(format!(
+                "copying {} metric '{}'",
+                what,
+                name.map(str::to_string)
+                    .or_else(|| alias.map(|a| format!("<alias {}>", a)))
+                    .unwrap_or_default()
+            ))
+        };
+
+        macro_rules! aliased {
+            ($value:expr, $add:ident, $with_alias:ident, $by_alias:ident) => {
+                match (name, alias) {
+                    (Some(name), Some(alias)) => self.$with_alias(name, alias, $value)?,
+                    (Some(name), None) => self.$add(name, $value)?,
+                    (None, Some(alias)) => self.$by_alias(alias, $value),
+                    (None, None) => unreachable!(),
+                }
+            };
+        }
+        macro_rules! by_name {
+            ($value:expr, $add:ident, $what:literal) => {
+                match (name, alias) {
+                    (Some(name), None) => self.$add(name, $value)?,
+                    _ => return Err(unsupported(concat!("aliased ", $what))),
+                }
+            };
+        }
+
+        match &metric.value {
+            MetricValue::Int8(v) => by_name!(*v, add_int8, "int8"),
+            MetricValue::Int16(v) => by_name!(*v, add_int16, "int16"),
+            MetricValue::Int32(v) => {
+                aliased!(*v, add_int32, add_int32_with_alias, add_int32_by_alias)
+            }
+            MetricValue::Int64(v) => {
+                aliased!(*v, add_int64, add_int64_with_alias, add_int64_by_alias)
+            }
+            MetricValue::UInt8(v) => by_name!(*v, add_uint8, "uint8"),
+            MetricValue::UInt16(v) => by_name!(*v, add_uint16, "uint16"),
+            MetricValue::UInt32(v) => {
+                aliased!(*v, add_uint32, add_uint32_with_alias, add_uint32_by_alias)
+            }
+            MetricValue::UInt64(v) | MetricValue::DateTime(v) => {
+                aliased!(*v, add_uint64, add_uint64_with_alias, add_uint64_by_alias)
+            }
+            MetricValue::Float(v) => {
+                aliased!(*v, add_float, add_float_with_alias, add_float_by_alias)
+            }
+            MetricValue::Double(v) => {
+                aliased!(*v, add_double, add_double_with_alias, add_double_by_alias)
+            }
+            MetricValue::Boolean(v) => {
+                aliased!(*v, add_bool, add_bool_with_alias, add_bool_by_alias)
+            }
+            MetricValue::String(v) => by_name!(v, add_string, "string"),
+            MetricValue::Null if metric.datatype == DataType::Unknown => {
+                return Err(unsupported("untyped null"))
+            }
+            MetricValue::Null => {
+                aliased!(
+                    metric.datatype,
+                    add_null,
+                    add_null_with_alias,
+                    add_null_by_alias
+                )
+            }
+        };
+        Ok(self)
+    }
+
+    /// Creates a builder holding a copy of a parsed payload.
+    ///
+    /// The payload timestamp, sequence number and metrics are copied.
+    /// Metrics the C API cannot express (see [`add_metric`](Self::add_metric))
+    /// are left out and returned alongside the builder. Per-metric timestamps
+    /// are lost: the copied metrics carry only the payload timestamp. To drop
+    /// or change metrics, start from [`PayloadBuilder::new`] and add the
+    /// edited metrics one by one instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Payload, PayloadBuilder};
+    ///
+    /// # fn example(received: &Payload) -> Result<(), sparkplug_rs::Error> {
+    /// let mut edited = PayloadBuilder::new()?;
+    /// if let Some(ts) = received.timestamp() {
+    ///     edited.set_timestamp(ts);
+    /// }
+    /// for metric in received.metrics() {
+    ///     let mut metric = metric?;
+    ///     if metric.name.as_deref() == Some("Debug/Counter") {
+    ///         continue;
+    ///     }
+    ///     if let sparkplug_rs::MetricValue::Double(v) = &mut metric.value {
+    ///         *v *= 1000.0;
+    ///     }
+    ///     edited.add_metric(&metric)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_payload(payload: &Payload) -> Result<(Self, Vec<Metric>)> {
+        let mut builder = Self::new()?;
+        if let Some(timestamp) = payload.timestamp() {
+            builder.set_timestamp(timestamp);
+        }
+        if let Some(seq) = payload.seq() {
+            builder.set_seq(seq);
+        }
+        let mut skipped = Vec::new();
+        for metric in payload.metrics() {
+            builder.add_or_skip(metric?, &mut skipped)?;
+        }
+        Ok((builder, skipped))
+    }
+
+    /// Adds a copy of `metric`, or moves it to `skipped` if the C API cannot
+    /// express it.
+    fn add_or_skip(&mut self, metric: Metric, skipped: &mut Vec<Metric>) -> Result<()> {
+        match self.add_metric(&metric) {
+            Ok(_) => Ok(()),
+            Err(Error::Unsupported(_)) => {
+                skipped.push(metric);
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Merges the metrics of `payload` into this builder.
+    ///
+    /// A metric with the same name or alias as one already in the builder
+    /// replaces it in place (keeping the name or alias the newer metric
+    /// lacks); other metrics are appended. The payload timestamp and
+    /// sequence number, when present, also replace the builder's.
+    ///
+    /// The merge is partial: metrics of `payload` the C API cannot express
+    /// are left out as in [`from_payload`](Self::from_payload) and returned,
+    /// while every other metric is merged. An empty list means the whole
+    /// payload was merged; a caller that needs all or nothing must check it
+    /// and discard the builder otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Payload, PayloadBuilder};
+    ///
+    /// # fn example(buffered: Vec<Payload>) -> Result<(), sparkplug_rs::Error> {
+    /// let mut ndata = PayloadBuilder::new()?;
+    /// for sample in &buffered {
+    ///     let skipped = ndata.extend_from(sample)?;
+    ///     for metric in skipped {
+    ///         eprintln!("cannot coalesce {:?}", metric.name);
+    ///     }
+    /// }
+    /// // `ndata` holds the latest value of every buffered metric
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use = "metrics that could not be merged are returned, not added"]
+    pub fn extend_from(&mut self, payload: &Payload) -> Result<Vec<Metric>> {
+        // The C builder cannot be read back or edited, so round-trip it.
+        let metric_count = unsafe { sys::sparkplug_payload_get_metric_count(self.inner) };
+        let current = match self.serialize() {
+            Ok(bytes) => Some(Payload::parse(&bytes)?),
+            Err(_) if metric_count == 0 => None,
+            Err(err) => return Err(err),
+        };
+        let (mut merged, skipped) = match &current {
+            Some(current) => merge_payloads(&[current, payload])?,
+            None => merge_payloads(&[payload])?,
+        };
+        std::mem::swap(self, &mut merged);
+        Ok(skipped)
+    }
+
     // ===== Sparkplug Node Control Convenience Methods =====
 
     /// Adds the "Node Control/Rebirth" metric (for NBIRTH).
@@ -465,19 +786,69 @@ impl PayloadBuilder {
     ///
     /// Returns a vector of bytes that can be published via Publisher.
     pub fn serialize(&self) -> Result<Vec<u8>> {
-        let mut buffer = vec![0u8; MAX_PAYLOAD_SIZE];
-        let size = unsafe {
-            sys::sparkplug_payload_serialize(self.inner, buffer.as_mut_ptr(), buffer.len())
-        };
+        let mut buffer = Vec::new();
+        self.serialize_into(&mut buffer)?;
+        Ok(buffer)
+    }
 
-        if size == 0 {
-            return Err(Error::SerializeFailed {
-                required: MAX_PAYLOAD_SIZE,
+    /// Serializes the payload into `buffer`, replacing its contents.
+    ///
+    /// The buffer grows as needed and keeps its capacity, so it can be reused
+    /// across calls. Returns the serialized length.
+    ///
+    /// The C API does not report the required size, so the buffer is sized
+    /// from an upper bound kept while metrics are added, capped at the MQTT
+    /// packet limit of 256 MiB.
+    pub fn serialize_into(&self, buffer: &mut Vec<u8>) -> Result<usize> {
+        let size = self.size_bound.min(MAX_PAYLOAD_SIZE);
+        buffer.clear();
+        buffer.reserve(size);
+        let written =
+            unsafe { sys::sparkplug_payload_serialize(self.inner, buffer.as_mut_ptr(), size) };
+        if written == 0 || written > size {
+            // Only a payload over the packet limit can outgrow the bound.
+            return Err(if self.size_bound > MAX_PAYLOAD_SIZE {
+                Error::SerializeFailed {
+                    required: MAX_PAYLOAD_SIZE + 1,
+                }
+            } else {
+                Error::OperationFailed {
+                    operation: "serialize",
+                }
             });
         }
+        // The C library has initialized the first `written` bytes.
+        unsafe { buffer.set_len(written) };
+        if self.nulls.is_empty() {
+            return Ok(written);
+        }
+        *buffer = wire::mark_null(buffer, &self.nulls)?;
+        Ok(buffer.len())
+    }
 
-        buffer.truncate(size);
-        Ok(buffer)
+    /// Returns the size of the serialized payload in bytes.
+    ///
+    /// The C API has no size query, so this serializes into a scratch buffer;
+    /// use [`serialize_into`](Self::serialize_into) to avoid doing the work
+    /// twice when the payload is published anyway. The MQTT packet adds the
+    /// topic and a few header bytes on top of this.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::PayloadBuilder;
+    ///
+    /// const BROKER_MAX_PACKET: usize = 256 * 1024;
+    ///
+    /// let mut birth = PayloadBuilder::new()?;
+    /// birth.add_double_with_alias("Temperature", 1, 20.5)?;
+    /// if birth.serialized_size()? > BROKER_MAX_PACKET - 1024 {
+    ///     // split the metrics over several payloads
+    /// }
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn serialized_size(&self) -> Result<usize> {
+        self.serialize_into(&mut Vec::new())
     }
 
     /// Returns the raw C pointer (for internal use).
@@ -497,9 +868,367 @@ impl Drop for PayloadBuilder {
     }
 }
 
+/// Builds a payload from `payloads` in order, later metrics replacing
+/// earlier ones with the same name or alias.
+fn merge_payloads(payloads: &[&Payload]) -> Result<(PayloadBuilder, Vec<Metric>)> {
+    let mut builder = PayloadBuilder::new()?;
+    let mut metrics: Vec<Metric> = Vec::new();
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+    let mut by_alias: HashMap<MetricAlias, usize> = HashMap::new();
+
+    for payload in payloads {
+        if let Some(timestamp) = payload.timestamp() {
+            builder.set_timestamp(timestamp);
+        }
+        if let Some(seq) = payload.seq() {
+            builder.set_seq(seq);
+        }
+        for metric in payload.metrics() {
+            let mut metric = metric?;
+            let existing = metric
+                .name
+                .as_ref()
+                .and_then(|name| by_name.get(name))
+                .or_else(|| metric.alias.and_then(|alias| by_alias.get(&alias)))
+                .copied();
+            let index = match existing {
+                Some(index) => {
+                    let previous = &metrics[index];
+                    metric.name = metric.name.or_else(|| previous.name.clone());
+                    metric.alias = metric.alias.or(previous.alias);
+                    metrics[index] = metric;
+                    index
+                }
+                None => {
+                    metrics.push(metric);
+                    metrics.len() - 1
+                }
+            };
+            if let Some(name) = &metrics[index].name {
+                by_name.insert(name.clone(), index);
+            }
+            if let Some(alias) = metrics[index].alias {
+                by_alias.insert(alias, index);
+            }
+        }
+    }
+
+    let mut skipped = Vec::new();
+    for metric in metrics {
+        builder.add_or_skip(metric, &mut skipped)?;
+    }
+    Ok((builder, skipped))
+}
+
 unsafe impl Send for PayloadBuilder {}
 unsafe impl Sync for PayloadBuilder {}
 
+/// A builder for NDATA/DDATA payloads that only accepts alias-only metrics.
+///
+/// Data messages should reference metrics by the aliases declared in the
+/// birth; this type has no by-name methods, so a full metric name cannot
+/// end up in a data payload by accident.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::DataPayloadBuilder;
+///
+/// let mut data = DataPayloadBuilder::new()?;
+/// data.add_double(1, 21.0).add_bool(2, false);
+/// let bytes = data.serialize()?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+pub struct DataPayloadBuilder {
+    inner: PayloadBuilder,
+}
+
+impl DataPayloadBuilder {
+    /// Creates a new data payload builder.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            inner: PayloadBuilder::new()?,
+        })
+    }
+
+    /// Sets the payload-level timestamp in milliseconds since Unix epoch.
+    pub fn set_timestamp(&mut self, timestamp: u64) -> &mut Self {
+        self.inner.set_timestamp(timestamp);
+        self
+    }
+
+    /// Sets the payload-level timestamp from a [`SystemTime`].
+    pub fn set_time(&mut self, time: SystemTime) -> &mut Self {
+        self.inner.set_time(time);
+        self
+    }
+
+    /// Sets the sequence number manually (not recommended in normal operation).
+    pub fn set_seq(&mut self, seq: u64) -> &mut Self {
+        self.inner.set_seq(seq);
+        self
+    }
+
+    /// Adds an int32 metric by alias.
+    pub fn add_int32(&mut self, alias: impl Into<MetricAlias>, value: i32) -> &mut Self {
+        self.inner.add_int32_by_alias(alias, value);
+        self
+    }
+
+    /// Adds an int64 metric by alias.
+    pub fn add_int64(&mut self, alias: impl Into<MetricAlias>, value: i64) -> &mut Self {
+        self.inner.add_int64_by_alias(alias, value);
+        self
+    }
+
+    /// Adds a uint32 metric by alias.
+    pub fn add_uint32(&mut self, alias: impl Into<MetricAlias>, value: u32) -> &mut Self {
+        self.inner.add_uint32_by_alias(alias, value);
+        self
+    }
+
+    /// Adds a uint64 metric by alias.
+    pub fn add_uint64(&mut self, alias: impl Into<MetricAlias>, value: u64) -> &mut Self {
+        self.inner.add_uint64_by_alias(alias, value);
+        self
+    }
+
+    /// Adds a float metric by alias.
+    pub fn add_float(&mut self, alias: impl Into<MetricAlias>, value: f32) -> &mut Self {
+        self.inner.add_float_by_alias(alias, value);
+        self
+    }
+
+    /// Adds a double metric by alias.
+    pub fn add_double(&mut self, alias: impl Into<MetricAlias>, value: f64) -> &mut Self {
+        self.inner.add_double_by_alias(alias, value);
+        self
+    }
+
+    /// Adds a boolean metric by alias.
+    pub fn add_bool(&mut self, alias: impl Into<MetricAlias>, value: bool) -> &mut Self {
+        self.inner.add_bool_by_alias(alias, value);
+        self
+    }
+
+    /// Adds a null metric by alias; see [`PayloadBuilder::add_null`].
+    pub fn add_null(&mut self, alias: impl Into<MetricAlias>, datatype: DataType) -> &mut Self {
+        self.inner.add_null_by_alias(alias, datatype);
+        self
+    }
+
+    /// Serializes the payload to binary protobuf format.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        self.inner.serialize()
+    }
+
+    /// Serializes the payload into `buffer`; see [`PayloadBuilder::serialize_into`].
+    pub fn serialize_into(&self, buffer: &mut Vec<u8>) -> Result<usize> {
+        self.inner.serialize_into(buffer)
+    }
+
+    /// Returns the size of the serialized payload in bytes.
+    pub fn serialized_size(&self) -> Result<usize> {
+        self.inner.serialized_size()
+    }
+}
+
+/// A builder for NBIRTH/DBIRTH payloads that enforces Sparkplug birth rules.
+///
+/// Every metric is declared with a name, an alias and a typed value, and
+/// names and aliases are rejected when reused. A node birth starts with the
+/// `bdSeq` and `Node Control/Rebirth` metrics. [`serialize`](Self::serialize)
+/// runs [`validate`](crate::validate) on the result and refuses to return a
+/// payload that breaks the birth rules.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{BirthPayloadBuilder, Publisher, PublisherConfig};
+///
+/// let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+/// let mut publisher = Publisher::new(config)?;
+/// publisher.connect()?;
+///
+/// let mut birth = BirthPayloadBuilder::node(publisher.bd_seq())?;
+/// birth
+///     .add_double("Temperature", 1, 20.5)?
+///     .add_bool("Active", 2, true)?;
+/// publisher.publish_birth(&birth.serialize()?)?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+pub struct BirthPayloadBuilder {
+    inner: PayloadBuilder,
+    node: bool,
+    names: HashSet<String>,
+    aliases: HashSet<MetricAlias>,
+}
+
+impl BirthPayloadBuilder {
+    /// Creates an NBIRTH builder holding `bdSeq` and `Node Control/Rebirth`.
+    pub fn node(bd_seq: u64) -> Result<Self> {
+        let mut builder = Self::with_kind(true)?;
+        builder
+            .inner
+            .add_bd_seq(bd_seq)?
+            .add_node_control_rebirth(false)?;
+        builder.names.insert("bdSeq".to_string());
+        builder.names.insert("Node Control/Rebirth".to_string());
+        Ok(builder)
+    }
+
+    /// Creates a DBIRTH builder.
+    pub fn device() -> Result<Self> {
+        Self::with_kind(false)
+    }
+
+    fn with_kind(node: bool) -> Result<Self> {
+        Ok(Self {
+            inner: PayloadBuilder::new()?,
+            node,
+            names: HashSet::new(),
+            aliases: HashSet::new(),
+        })
+    }
+
+    /// Sets the payload-level timestamp in milliseconds since Unix epoch.
+    pub fn set_timestamp(&mut self, timestamp: u64) -> &mut Self {
+        self.inner.set_timestamp(timestamp);
+        self
+    }
+
+    /// Sets the payload-level timestamp from a [`SystemTime`].
+    pub fn set_time(&mut self, time: SystemTime) -> &mut Self {
+        self.inner.set_time(time);
+        self
+    }
+
+    fn declare(&mut self, name: &str, alias: MetricAlias) -> Result<MetricAlias> {
+        if self.names.contains(name) {
+            return Err(Error::InvalidPayload(vec![Violation::DuplicateName(
+                name.to_string(),
+            )]));
+        }
+        if !self.aliases.insert(alias) {
+            return Err(Error::InvalidPayload(vec![Violation::DuplicateAlias(
+                alias,
+            )]));
+        }
+        self.names.insert(name.to_string());
+        Ok(alias)
+    }
+
+    /// Adds an int32 metric with its name and alias.
+    pub fn add_int32(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: i32,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_int32_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds an int64 metric with its name and alias.
+    pub fn add_int64(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: i64,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_int64_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds a uint32 metric with its name and alias.
+    pub fn add_uint32(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: u32,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_uint32_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds a uint64 metric with its name and alias.
+    pub fn add_uint64(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: u64,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_uint64_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds a float metric with its name and alias.
+    pub fn add_float(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: f32,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_float_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds a double metric with its name and alias.
+    pub fn add_double(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: f64,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_double_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds a boolean metric with its name and alias.
+    pub fn add_bool(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: bool,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_bool_with_alias(name, alias, value)?;
+        Ok(self)
+    }
+
+    /// Adds a null metric with its name and alias; see [`PayloadBuilder::add_null`].
+    pub fn add_null(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        datatype: DataType,
+    ) -> Result<&mut Self> {
+        let alias = self.declare(name, alias.into())?;
+        self.inner.add_null_with_alias(name, alias, datatype)?;
+        Ok(self)
+    }
+
+    /// Serializes the payload after checking it against the birth rules.
+    ///
+    /// Returns [`Error::InvalidPayload`] if the payload is not a valid birth.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let bytes = self.inner.serialize()?;
+        let payload = Payload::parse(&bytes)?;
+        if self.node {
+            validate::validate_node_birth(&payload)?;
+        } else {
+            validate::validate_birth(&payload)?;
+        }
+        Ok(bytes)
+    }
+}
+
 /// A parsed Sparkplug payload.
 ///
 /// This provides read access to a payload's contents, including metrics.
@@ -529,6 +1258,11 @@ impl Payload {
         }
     }
 
+    /// Gets the payload-level timestamp as a [`SystemTime`], if present.
+    pub fn time(&self) -> Option<SystemTime> {
+        self.timestamp().map(crate::time::from_millis)
+    }
+
     /// Gets the payload-level sequence number, if present.
     pub fn seq(&self) -> Option<u64> {
         let mut seq: u64 = 0;
@@ -553,6 +1287,44 @@ impl Payload {
         }
     }
 
+    /// Gets the payload UUID as a [`uuid::Uuid`], validating its format.
+    ///
+    /// Returns `Ok(None)` if the payload has no UUID.
+    #[cfg(feature = "uuid")]
+    pub fn uuid_value(&self) -> Result<Option<uuid::Uuid>> {
+        self.uuid()
+            .map(uuid::Uuid::parse_str)
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    /// Copies the payload header and all metrics into a [`PayloadSnapshot`].
+    pub fn snapshot(&self) -> Result<PayloadSnapshot> {
+        Ok(PayloadSnapshot {
+            timestamp: self.timestamp(),
+            seq: self.seq(),
+            uuid: self.uuid().map(str::to_string),
+            metrics: self.to_metrics()?,
+        })
+    }
+
+    /// Converts the payload into an editable [`PayloadBuilder`], returning
+    /// the metrics that could not be copied.
+    ///
+    /// See [`PayloadBuilder::from_payload`].
+    pub fn to_builder(&self) -> Result<(PayloadBuilder, Vec<Metric>)> {
+        PayloadBuilder::from_payload(self)
+    }
+
+    /// Combines this payload with a later one into a new builder.
+    ///
+    /// Metrics of `later` win on duplicate name or alias; see
+    /// [`PayloadBuilder::extend_from`]. Returns the new builder and the
+    /// metrics that could not be copied.
+    pub fn merge(&self, later: &Payload) -> Result<(PayloadBuilder, Vec<Metric>)> {
+        merge_payloads(&[self, later])
+    }
+
     /// Returns the number of metrics in the payload.
     pub fn metric_count(&self) -> usize {
         unsafe { sys::sparkplug_payload_get_metric_count(self.inner) }
@@ -560,6 +1332,23 @@ impl Payload {
 
     /// Gets a metric at the specified index.
     pub fn metric_at(&self, index: usize) -> Result<Metric> {
+        self.metric_at_with(index, |name| name.to_string())
+    }
+
+    /// Gets a metric at the specified index, interning its name in `cache`.
+    pub fn metric_at_interned(
+        &self,
+        index: usize,
+        cache: &mut NameCache,
+    ) -> Result<InternedMetric> {
+        self.metric_at_with(index, |name| cache.intern(name))
+    }
+
+    /// Gets a borrowed view of the metric at the specified index.
+    ///
+    /// Unlike [`metric_at`](Self::metric_at), the name and string value are
+    /// not copied: they point into the parsed payload.
+    pub fn metric_ref_at(&self, index: usize) -> Result<MetricRef<'_>> {
         let count = self.metric_count();
         if index >= count {
             return Err(Error::InvalidMetricIndex { index, count });
@@ -573,8 +1362,10 @@ impl Payload {
             return Err(Error::InvalidMetricIndex { index, count });
         }
 
+        // The strings are owned by the parsed payload, which is never
+        // modified, so they live as long as `self`.
         let name = if raw_metric.has_name && !raw_metric.name.is_null() {
-            unsafe { Some(CStr::from_ptr(raw_metric.name).to_str()?.to_string()) }
+            unsafe { Some(CStr::from_ptr(raw_metric.name).to_str()?) }
         } else {
             None
         };
@@ -594,56 +1385,59 @@ impl Payload {
         let datatype = DataType::from(raw_metric.datatype);
 
         let value = if raw_metric.is_null {
-            MetricValue::Null
+            MetricValueRef::Null
         } else {
             // Access union fields using .as_ref() to get the inner value
             match datatype {
                 DataType::Int8 => unsafe {
-                    MetricValue::Int8(*raw_metric.value.int8_value.as_ref())
+                    MetricValueRef::Int8(*raw_metric.value.int8_value.as_ref())
                 },
                 DataType::Int16 => unsafe {
-                    MetricValue::Int16(*raw_metric.value.int16_value.as_ref())
+                    MetricValueRef::Int16(*raw_metric.value.int16_value.as_ref())
                 },
                 DataType::Int32 => unsafe {
-                    MetricValue::Int32(*raw_metric.value.int32_value.as_ref())
+                    MetricValueRef::Int32(*raw_metric.value.int32_value.as_ref())
                 },
                 DataType::Int64 => unsafe {
-                    MetricValue::Int64(*raw_metric.value.int64_value.as_ref())
+                    MetricValueRef::Int64(*raw_metric.value.int64_value.as_ref())
                 },
                 DataType::UInt8 => unsafe {
-                    MetricValue::UInt8(*raw_metric.value.uint8_value.as_ref())
+                    MetricValueRef::UInt8(*raw_metric.value.uint8_value.as_ref())
                 },
                 DataType::UInt16 => unsafe {
-                    MetricValue::UInt16(*raw_metric.value.uint16_value.as_ref())
+                    MetricValueRef::UInt16(*raw_metric.value.uint16_value.as_ref())
                 },
                 DataType::UInt32 => unsafe {
-                    MetricValue::UInt32(*raw_metric.value.uint32_value.as_ref())
+                    MetricValueRef::UInt32(*raw_metric.value.uint32_value.as_ref())
                 },
                 DataType::UInt64 => unsafe {
-                    MetricValue::UInt64(*raw_metric.value.uint64_value.as_ref())
+                    MetricValueRef::UInt64(*raw_metric.value.uint64_value.as_ref())
                 },
                 DataType::Float => unsafe {
-                    MetricValue::Float(*raw_metric.value.float_value.as_ref())
+                    MetricValueRef::Float(*raw_metric.value.float_value.as_ref())
                 },
                 DataType::Double => unsafe {
-                    MetricValue::Double(*raw_metric.value.double_value.as_ref())
+                    MetricValueRef::Double(*raw_metric.value.double_value.as_ref())
                 },
                 DataType::Boolean => unsafe {
-                    MetricValue::Boolean(*raw_metric.value.boolean_value.as_ref())
+                    MetricValueRef::Boolean(*raw_metric.value.boolean_value.as_ref())
+                },
+                DataType::DateTime => unsafe {
+                    MetricValueRef::DateTime(*raw_metric.value.uint64_value.as_ref())
                 },
                 DataType::String | DataType::Text => unsafe {
                     let string_ptr = *raw_metric.value.string_value.as_ref();
                     if string_ptr.is_null() {
-                        MetricValue::Null
+                        MetricValueRef::Null
                     } else {
-                        MetricValue::String(CStr::from_ptr(string_ptr).to_str()?.to_string())
+                        MetricValueRef::String(CStr::from_ptr(string_ptr).to_str()?)
                     }
                 },
-                _ => MetricValue::Null,
+                _ => MetricValueRef::Null,
             }
         };
 
-        Ok(Metric {
+        Ok(MetricRef {
             name,
             alias,
             timestamp,
@@ -652,6 +1446,21 @@ impl Payload {
         })
     }
 
+    fn metric_at_with<N>(
+        &self,
+        index: usize,
+        make_name: impl FnOnce(&str) -> N,
+    ) -> Result<Metric<N>> {
+        let metric = self.metric_ref_at(index)?;
+        Ok(Metric {
+            name: metric.name.map(make_name),
+            alias: metric.alias,
+            timestamp: metric.timestamp,
+            datatype: metric.datatype,
+            value: metric.value.to_value(),
+        })
+    }
+
     /// Returns an iterator over all metrics in the payload.
     pub fn metrics(&self) -> MetricIterator<'_> {
         MetricIterator {
@@ -660,6 +1469,88 @@ impl Payload {
             count: self.metric_count(),
         }
     }
+
+    /// Decodes all metrics into a vector.
+    ///
+    /// The C API only exposes metrics by index, so this still makes one
+    /// FFI call per metric, but it reads the count once, allocates the
+    /// vector up front and skips the per-item iterator bookkeeping.
+    pub fn to_metrics(&self) -> Result<Vec<Metric>> {
+        let count = self.metric_count();
+        let mut metrics = Vec::with_capacity(count);
+        for index in 0..count {
+            metrics.push(self.metric_at(index)?);
+        }
+        Ok(metrics)
+    }
+
+    /// Returns an iterator over borrowed views of all metrics.
+    ///
+    /// Avoids allocating names and string values; useful for high-rate
+    /// subscribers that only read the payload.
+    pub fn metrics_ref(&self) -> MetricRefIterator<'_> {
+        MetricRefIterator {
+            payload: self,
+            index: 0,
+            count: self.metric_count(),
+        }
+    }
+
+    /// Returns the alias to name mapping declared by a birth payload.
+    ///
+    /// Metrics without both a name and an alias are skipped.
+    pub fn alias_map(&self) -> Result<HashMap<MetricAlias, String>> {
+        let mut aliases = HashMap::new();
+        for metric in self.metrics_ref() {
+            let metric = metric?;
+            if let (Some(name), Some(alias)) = (metric.name, metric.alias) {
+                aliases.insert(alias, name.to_string());
+            }
+        }
+        Ok(aliases)
+    }
+
+    /// Returns an iterator over all metrics, filling in the names of
+    /// alias-only metrics from `aliases`.
+    ///
+    /// Aliases missing from the map leave the name unset.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::Payload;
+    ///
+    /// # fn example(nbirth: &Payload, ndata: &Payload) -> Result<(), sparkplug_rs::Error> {
+    /// let aliases = nbirth.alias_map()?;
+    /// for metric in ndata.metrics_resolved(&aliases) {
+    ///     let metric = metric?;
+    ///     println!("{:?} = {}", metric.name, metric.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn metrics_resolved<'a>(
+        &'a self,
+        aliases: &'a HashMap<MetricAlias, String>,
+    ) -> ResolvedMetricIterator<'a> {
+        ResolvedMetricIterator {
+            metrics: self.metrics(),
+            aliases,
+        }
+    }
+
+    /// Returns an iterator over all metrics, interning their names in `cache`.
+    ///
+    /// Useful for hosts parsing many payloads with the same metric names:
+    /// each distinct name is allocated once and shared.
+    pub fn metrics_interned<'a>(&'a self, cache: &'a mut NameCache) -> InternedMetricIterator<'a> {
+        InternedMetricIterator {
+            payload: self,
+            cache,
+            index: 0,
+            count: self.metric_count(),
+        }
+    }
 }
 
 impl Drop for Payload {
@@ -702,3 +1593,87 @@ impl<'a> Iterator for MetricIterator<'a> {
 }
 
 impl<'a> ExactSizeIterator for MetricIterator<'a> {}
+
+/// Iterator over metrics in a payload with interned names.
+pub struct InternedMetricIterator<'a> {
+    payload: &'a Payload,
+    cache: &'a mut NameCache,
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for InternedMetricIterator<'a> {
+    type Item = Result<InternedMetric>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            None
+        } else {
+            let result = self.payload.metric_at_interned(self.index, self.cache);
+            self.index += 1;
+            Some(result)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for InternedMetricIterator<'a> {}
+
+/// Iterator over borrowed metric views in a payload.
+pub struct MetricRefIterator<'a> {
+    payload: &'a Payload,
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for MetricRefIterator<'a> {
+    type Item = Result<MetricRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            None
+        } else {
+            let result = self.payload.metric_ref_at(self.index);
+            self.index += 1;
+            Some(result)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for MetricRefIterator<'a> {}
+
+/// Iterator over metrics with alias-only names resolved.
+pub struct ResolvedMetricIterator<'a> {
+    metrics: MetricIterator<'a>,
+    aliases: &'a HashMap<MetricAlias, String>,
+}
+
+impl<'a> Iterator for ResolvedMetricIterator<'a> {
+    type Item = Result<Metric>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let aliases = self.aliases;
+        self.metrics.next().map(|metric| {
+            let mut metric = metric?;
+            if metric.name.is_none() {
+                metric.name = metric.alias.and_then(|alias| aliases.get(&alias).cloned());
+            }
+            Ok(metric)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.metrics.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for ResolvedMetricIterator<'a> {}
@@ -1,8 +1,21 @@
 //! Sparkplug Publisher for publishing node and device data.
 
+use crate::alias::AliasAllocator;
+use crate::backlog::{Backlog, Entry};
+use crate::dry_run::DryRun;
 use crate::error::{Error, Result};
+use crate::payload::{Payload, PayloadBuilder};
+use crate::reconnect::ReconnectPolicy;
+use crate::registry::MetricRegistry;
+use crate::subscriber::{CommandCallback, Message, MessageCallback};
 use crate::sys;
+use crate::topic::MessageType;
+use crate::types::{Metric, MetricAlias, MetricValue, MetricValueRef};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ffi::CString;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Configuration for a Sparkplug Publisher.
 #[derive(Debug, Clone)]
@@ -15,6 +28,73 @@ pub struct PublisherConfig {
     pub group_id: String,
     /// Edge node identifier.
     pub edge_node_id: String,
+    /// What to do when device data is published for a device without a current DBIRTH.
+    pub device_birth_policy: DeviceBirthPolicy,
+    /// Reject operations that would violate the Sparkplug session state machine.
+    ///
+    /// When set, publishing before [`connect`](Publisher::connect) or after
+    /// [`disconnect`](Publisher::disconnect), NDATA/DBIRTH/DDATA/DDEATH before
+    /// the NBIRTH and rebirth while disconnected fail with
+    /// [`Error::InvalidState`] instead of reaching the C library.
+    pub strict: bool,
+    /// Retry policy for [`connect`](Publisher::connect); `None` makes a single attempt.
+    pub reconnect: Option<ReconnectPolicy>,
+    /// Recover from failed NDATA/DDATA publishes.
+    ///
+    /// When set, a failed [`publish_data`](Publisher::publish_data) or
+    /// [`publish_device_data`](Publisher::publish_device_data) reconnects and
+    /// retries the publish once. The reconnect follows [`reconnect`](Self::reconnect)
+    /// if it limits `max_attempts` and otherwise makes a single attempt, so a
+    /// publish never blocks indefinitely while the broker is down.
+    pub auto_reconnect: bool,
+    /// Capacity of the store-and-forward backlog; `None` disables it.
+    ///
+    /// When set, NDATA/DDATA that fail to publish are queued instead of
+    /// returning an error, and are sent in order once the connection is
+    /// back (see [`Publisher::flush_backlog`]). When the backlog is full the
+    /// oldest message is dropped.
+    pub store_and_forward: Option<usize>,
+    /// File holding the store-and-forward backlog; `None` keeps it in memory.
+    ///
+    /// When set, [`Publisher::new`] loads the messages still queued in the
+    /// file, creating it if needed, and every change to the backlog is
+    /// written back, so queued messages survive restarts. The file is not
+    /// synced after each write, so a power loss can lose the latest changes.
+    pub backlog_file: Option<PathBuf>,
+    /// Maximum age of a queued message; `None` keeps messages until they are sent.
+    ///
+    /// Older messages are dropped instead of sent and counted in
+    /// [`Publisher::backlog_dropped`].
+    pub backlog_max_age: Option<Duration>,
+    /// File holding the metric aliases assigned by [`Publisher::alias`]; `None` keeps them in memory.
+    ///
+    /// When set, [`Publisher::new`] loads the file if it exists and every
+    /// newly assigned alias is written back, so aliases stay stable across
+    /// restarts.
+    pub alias_file: Option<PathBuf>,
+    /// Absolute deadband applied by [`Publisher::publish_changed`] to numeric metrics.
+    ///
+    /// A numeric value is republished only when it differs from the last
+    /// published value by more than this amount; `0.0` publishes every change.
+    pub deadband: f64,
+}
+
+/// Handling of DDATA for devices without a DBIRTH in the current session.
+///
+/// A session starts with each NBIRTH (publish_birth, rebirth or a new connection).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeviceBirthPolicy {
+    /// Return [`Error::DeviceNotBirthed`].
+    Reject,
+    /// Republish the device's last DBIRTH first; reject if there is none.
+    AutoBirth,
+    /// Publish without checking, except for devices whose DDEATH was
+    /// published since their last DBIRTH.
+    ///
+    /// This is the default, so DDATA published after a rebirth without
+    /// republishing the DBIRTH keeps working as before the policy existed.
+    #[default]
+    Unchecked,
 }
 
 impl PublisherConfig {
@@ -30,7 +110,168 @@ impl PublisherConfig {
             client_id: client_id.into(),
             group_id: group_id.into(),
             edge_node_id: edge_node_id.into(),
+            device_birth_policy: DeviceBirthPolicy::default(),
+            strict: false,
+            reconnect: None,
+            auto_reconnect: false,
+            store_and_forward: None,
+            backlog_file: None,
+            backlog_max_age: None,
+            alias_file: None,
+            deadband: 0.0,
+        }
+    }
+
+    /// Starts a [`PublisherConfigBuilder`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{PublisherConfig, ReconnectPolicy};
+    ///
+    /// let config = PublisherConfig::builder()
+    ///     .broker("tcp://localhost:1883")
+    ///     .client_id("edge")
+    ///     .group_id("Energy")
+    ///     .edge_node_id("Gateway01")
+    ///     .strict(true)
+    ///     .reconnect(ReconnectPolicy::default())
+    ///     .build()?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn builder() -> PublisherConfigBuilder {
+        PublisherConfigBuilder::default()
+    }
+}
+
+/// Fluent builder for [`PublisherConfig`].
+///
+/// The broker URL, client ID, group ID and edge node ID are required; the
+/// other options keep the defaults of [`PublisherConfig::new`] unless set.
+///
+/// There are no `credentials` or `tls` options: `sparkplug_publisher_create`
+/// takes only the broker URL and IDs, and configures its MQTT client
+/// internally. An `ssl://` broker URL is passed through unchanged and
+/// connects only if the C library's defaults accept the broker.
+#[derive(Debug, Clone, Default)]
+pub struct PublisherConfigBuilder {
+    broker_url: Option<String>,
+    client_id: Option<String>,
+    group_id: Option<String>,
+    edge_node_id: Option<String>,
+    device_birth_policy: DeviceBirthPolicy,
+    strict: bool,
+    reconnect: Option<ReconnectPolicy>,
+    auto_reconnect: bool,
+    store_and_forward: Option<usize>,
+    backlog_file: Option<PathBuf>,
+    backlog_max_age: Option<Duration>,
+    alias_file: Option<PathBuf>,
+    deadband: f64,
+}
+
+impl PublisherConfigBuilder {
+    /// Sets the MQTT broker URL.
+    pub fn broker(mut self, broker_url: impl Into<String>) -> Self {
+        self.broker_url = Some(broker_url.into());
+        self
+    }
+
+    /// Sets the MQTT client identifier.
+    pub fn client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// Sets the Sparkplug group ID.
+    pub fn group_id(mut self, group_id: impl Into<String>) -> Self {
+        self.group_id = Some(group_id.into());
+        self
+    }
+
+    /// Sets the edge node identifier.
+    pub fn edge_node_id(mut self, edge_node_id: impl Into<String>) -> Self {
+        self.edge_node_id = Some(edge_node_id.into());
+        self
+    }
+
+    /// Sets [`PublisherConfig::device_birth_policy`].
+    pub fn device_birth_policy(mut self, policy: DeviceBirthPolicy) -> Self {
+        self.device_birth_policy = policy;
+        self
+    }
+
+    /// Sets [`PublisherConfig::strict`].
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets [`PublisherConfig::reconnect`].
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Sets [`PublisherConfig::auto_reconnect`].
+    pub fn auto_reconnect(mut self, auto_reconnect: bool) -> Self {
+        self.auto_reconnect = auto_reconnect;
+        self
+    }
+
+    /// Sets [`PublisherConfig::store_and_forward`].
+    pub fn store_and_forward(mut self, capacity: usize) -> Self {
+        self.store_and_forward = Some(capacity);
+        self
+    }
+
+    /// Sets [`PublisherConfig::backlog_file`].
+    pub fn backlog_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.backlog_file = Some(path.into());
+        self
+    }
+
+    /// Sets [`PublisherConfig::backlog_max_age`].
+    pub fn backlog_max_age(mut self, max_age: Duration) -> Self {
+        self.backlog_max_age = Some(max_age);
+        self
+    }
+
+    /// Sets [`PublisherConfig::alias_file`].
+    pub fn alias_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.alias_file = Some(path.into());
+        self
+    }
+
+    /// Sets [`PublisherConfig::deadband`].
+    pub fn deadband(mut self, deadband: f64) -> Self {
+        self.deadband = deadband;
+        self
+    }
+
+    /// Builds the configuration.
+    ///
+    /// Returns [`Error::InvalidConfig`] if a required field is missing.
+    pub fn build(self) -> Result<PublisherConfig> {
+        fn required(value: Option<String>, field: &str) -> Result<String> {
+            value.ok_or_else(|| Error::InvalidConfig(format!("{} is required", field)))
         }
+        let mut config = PublisherConfig::new(
+            required(self.broker_url, "broker URL")?,
+            required(self.client_id, "client ID")?,
+            required(self.group_id, "group ID")?,
+            required(self.edge_node_id, "edge node ID")?,
+        );
+        config.device_birth_policy = self.device_birth_policy;
+        config.strict = self.strict;
+        config.reconnect = self.reconnect;
+        config.auto_reconnect = self.auto_reconnect;
+        config.store_and_forward = self.store_and_forward;
+        config.backlog_file = self.backlog_file;
+        config.backlog_max_age = self.backlog_max_age;
+        config.alias_file = self.alias_file;
+        config.deadband = self.deadband;
+        Ok(config)
     }
 }
 
@@ -78,15 +319,57 @@ impl PublisherConfig {
 /// ```
 pub struct Publisher {
     inner: *mut sys::sparkplug_publisher_t,
+    group_id: String,
+    edge_node_id: String,
+    device_birth_policy: DeviceBirthPolicy,
+    /// Last DBIRTH payload published for each device
+    device_births: HashMap<String, Vec<u8>>,
+    /// Devices republished on rebirth
+    attached: BTreeSet<String>,
+    /// Devices with a DBIRTH in the current session
+    birthed: HashSet<String>,
+    /// Devices whose DDEATH was published since their last DBIRTH
+    dead: HashSet<String>,
+    strict: bool,
+    reconnect: Option<ReconnectPolicy>,
+    auto_reconnect: bool,
+    /// Last NBIRTH payload, republished by reconnect
+    node_birth: Option<Vec<u8>>,
+    backlog: Backlog,
+    backlog_capacity: Option<usize>,
+    backlog_max_age: Option<Duration>,
+    backlog_dropped: u64,
+    backlog_rejected: u64,
+    registry: MetricRegistry,
+    aliases: AliasAllocator,
+    alias_file: Option<PathBuf>,
+    /// Last value published through publish_changed, by (device ID, alias)
+    last_values: HashMap<(Option<String>, MetricAlias), MetricValue>,
+    deadband: f64,
+    /// Offline session replacing the MQTT client, set by `dry_run`
+    dry_run: Option<DryRun>,
+    /// Set by shutdown; every later publish is rejected
+    shut_down: bool,
+    connected: bool,
+    /// Whether an NBIRTH was published in the current session
+    node_birthed: bool,
 }
 
 impl Publisher {
     /// Creates a new Publisher with the given configuration.
     pub fn new(config: PublisherConfig) -> Result<Self> {
+        let aliases = match &config.alias_file {
+            Some(path) if path.exists() => AliasAllocator::load(path)?,
+            _ => AliasAllocator::new(),
+        };
+        let backlog = match &config.backlog_file {
+            Some(path) => Backlog::open(path)?,
+            None => Backlog::default(),
+        };
         let broker_url = CString::new(config.broker_url)?;
         let client_id = CString::new(config.client_id)?;
-        let group_id = CString::new(config.group_id)?;
-        let edge_node_id = CString::new(config.edge_node_id)?;
+        let group_id = CString::new(config.group_id.as_str())?;
+        let edge_node_id = CString::new(config.edge_node_id.as_str())?;
 
         let inner = unsafe {
             sys::sparkplug_publisher_create(
@@ -104,14 +387,112 @@ impl Publisher {
             });
         }
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            group_id: config.group_id,
+            edge_node_id: config.edge_node_id,
+            device_birth_policy: config.device_birth_policy,
+            device_births: HashMap::new(),
+            attached: BTreeSet::new(),
+            birthed: HashSet::new(),
+            dead: HashSet::new(),
+            strict: config.strict,
+            reconnect: config.reconnect,
+            auto_reconnect: config.auto_reconnect,
+            node_birth: None,
+            backlog,
+            backlog_capacity: config.store_and_forward.map(|capacity| capacity.max(1)),
+            backlog_max_age: config.backlog_max_age,
+            backlog_dropped: 0,
+            backlog_rejected: 0,
+            registry: MetricRegistry::new(),
+            aliases,
+            alias_file: config.alias_file,
+            last_values: HashMap::new(),
+            deadband: config.deadband,
+            dry_run: None,
+            shut_down: false,
+            connected: false,
+            node_birthed: false,
+        })
+    }
+
+    /// Creates a Publisher that sends every message to `sink` instead of a broker.
+    ///
+    /// Nothing is sent over MQTT. The session state machine, device birth
+    /// policy and metric registry work as usual; payloads are also checked
+    /// against the [`validate`](crate::validate) rules, and an invalid one is
+    /// rejected with [`Error::InvalidPayload`]. NBIRTH, NDATA, DBIRTH, DDATA
+    /// and DDEATH payloads are rebuilt with the seq the C library would
+    /// assign (and the bdSeq for NBIRTH/NDEATH) and a timestamp if they have
+    /// none, so the sink sees what a subscriber would receive. The rebuild
+    /// goes through [`PayloadBuilder::add_metric`], so its type limits apply
+    /// and the payload uuid is dropped. [`disconnect`](Self::disconnect)
+    /// emits the NDEATH that the broker would deliver for a session with an NBIRTH.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, PayloadBuilder, Publisher, PublisherConfig};
+    ///
+    /// let config = PublisherConfig::new("tcp://localhost:1883", "ci", "Energy", "Gateway01");
+    /// let mut publisher = Publisher::dry_run(
+    ///     config,
+    ///     Box::new(|msg: Message| println!("{} ({} bytes)", msg.topic, msg.payload_data.len())),
+    /// )?;
+    /// publisher.connect()?;
+    ///
+    /// let mut birth = PayloadBuilder::new()?;
+    /// birth.add_double_with_alias("Temperature", 1, 20.5)?;
+    /// publisher.publish_birth(&birth.serialize()?)?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn dry_run(config: PublisherConfig, sink: MessageCallback) -> Result<Self> {
+        let mut publisher = Self::new(config)?;
+        publisher.dry_run = Some(DryRun::new(
+            sink,
+            &publisher.group_id,
+            &publisher.edge_node_id,
+        ));
+        Ok(publisher)
+    }
+
+    /// Returns true if this Publisher was created with [`dry_run`](Self::dry_run).
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
     }
 
     /// Connects to the MQTT broker.
     ///
     /// This sets up the NDEATH message as the MQTT Last Will Testament before connecting.
+    /// Failed attempts are retried according to the configured [`ReconnectPolicy`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(group_id = %self.group_id, edge_node_id = %self.edge_node_id)))]
     pub fn connect(&mut self) -> Result<()> {
-        let ret = unsafe { sys::sparkplug_publisher_connect(self.inner) };
+        self.check_shut_down("connect")?;
+        if self.strict && self.connected {
+            return Err(Error::InvalidState {
+                operation: "connect",
+                details: "already connected".to_string(),
+            });
+        }
+        match self.reconnect.clone() {
+            Some(policy) => policy.retry(|| self.connect_once())?,
+            None => self.connect_once()?,
+        }
+        self.connected = true;
+        self.node_birthed = false;
+        self.birthed.clear();
+        Ok(())
+    }
+
+    fn connect_once(&mut self) -> Result<()> {
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.connect();
+                0
+            }
+            None => unsafe { sys::sparkplug_publisher_connect(self.inner) },
+        };
         if ret != 0 {
             return Err(Error::ConnectionFailed(
                 "Failed to connect to MQTT broker".to_string(),
@@ -123,13 +504,25 @@ impl Publisher {
     /// Disconnects from the MQTT broker.
     ///
     /// The NDEATH message is sent automatically via MQTT Last Will Testament.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id)))]
     pub fn disconnect(&mut self) -> Result<()> {
-        let ret = unsafe { sys::sparkplug_publisher_disconnect(self.inner) };
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                if self.connected && self.node_birthed {
+                    dry.node_death()?;
+                }
+                0
+            }
+            None => unsafe { sys::sparkplug_publisher_disconnect(self.inner) },
+        };
         if ret != 0 {
             return Err(Error::OperationFailed {
                 operation: "disconnect",
             });
         }
+        self.connected = false;
+        self.node_birthed = false;
+        self.birthed.clear();
         Ok(())
     }
 
@@ -137,9 +530,17 @@ impl Publisher {
     ///
     /// This must be called after connect() and before any publish_data() calls.
     /// The payload should contain all metrics with both names and aliases.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id, size = payload.len(), seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn publish_birth(&mut self, payload: &[u8]) -> Result<()> {
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_birth(self.inner, payload.as_ptr(), payload.len())
+        self.check_state("publish_birth", false)?;
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.node_birth(payload)?;
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_birth(self.inner, payload.as_ptr(), payload.len())
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -147,6 +548,11 @@ impl Publisher {
                 details: "publish_birth failed".to_string(),
             });
         }
+        self.node_birthed = true;
+        self.node_birth = Some(payload.to_vec());
+        self.birthed.clear();
+        self.last_values.clear();
+        self.trace_sent(MessageType::NBirth, None, payload.len());
         Ok(())
     }
 
@@ -154,9 +560,24 @@ impl Publisher {
     ///
     /// The sequence number is automatically incremented.
     /// The payload should typically use aliases only for bandwidth efficiency.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id, size = payload.len(), seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn publish_data(&mut self, payload: &[u8]) -> Result<()> {
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_data(self.inner, payload.as_ptr(), payload.len())
+        self.recovering(None, payload)
+    }
+
+    fn send_data(&mut self, payload: &[u8]) -> Result<()> {
+        self.check_state("publish_data", true)?;
+        if !self.registry.is_empty() {
+            self.registry.check_data(&Payload::parse(payload)?)?;
+        }
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.data(None, payload)?;
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_data(self.inner, payload.as_ptr(), payload.len())
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -164,58 +585,782 @@ impl Publisher {
                 details: "publish_data failed".to_string(),
             });
         }
+        self.trace_sent(MessageType::NData, None, payload.len());
+        Ok(())
+    }
+
+    /// Publishes an NDATA with the metrics whose value changed (Report by Exception).
+    ///
+    /// Each value is compared with the last one published for its alias
+    /// through this method. Numeric values must move by more than
+    /// [`PublisherConfig::deadband`]; other values must differ. Nothing is
+    /// published if no metric changed. The cache is cleared by every NBIRTH,
+    /// so the first call after a birth publishes every metric.
+    ///
+    /// Returns the number of metrics published. Values are added by alias
+    /// with [`PayloadBuilder::add_metric`], so only the types that the C API
+    /// can add by alias are supported.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{MetricValue, Publisher};
+    ///
+    /// # fn scan(publisher: &mut Publisher, temperature: f64, running: bool) -> Result<(), sparkplug_rs::Error> {
+    /// publisher.publish_changed(&[
+    ///     (1, MetricValue::Double(temperature)),
+    ///     (2, MetricValue::Boolean(running)),
+    /// ])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn publish_changed<A: Into<MetricAlias> + Copy>(
+        &mut self,
+        metrics: &[(A, MetricValue)],
+    ) -> Result<usize> {
+        self.publish_changed_for(None, metrics)
+    }
+
+    /// Publishes a DDATA with the device metrics whose value changed.
+    ///
+    /// See [`publish_changed`](Self::publish_changed); the cache of a device
+    /// is cleared by its DBIRTH.
+    pub fn publish_device_changed<A: Into<MetricAlias> + Copy>(
+        &mut self,
+        device_id: &str,
+        metrics: &[(A, MetricValue)],
+    ) -> Result<usize> {
+        self.publish_changed_for(Some(device_id), metrics)
+    }
+
+    fn publish_changed_for<A: Into<MetricAlias> + Copy>(
+        &mut self,
+        device_id: Option<&str>,
+        metrics: &[(A, MetricValue)],
+    ) -> Result<usize> {
+        let device = device_id.map(str::to_string);
+        let changed: Vec<(MetricAlias, &MetricValue)> = metrics
+            .iter()
+            .map(|(alias, value)| ((*alias).into(), value))
+            .filter(|(alias, value)| {
+                let last = self.last_values.get(&(device.clone(), *alias));
+                exceeds_deadband(last, value, self.deadband)
+            })
+            .collect();
+        if changed.is_empty() {
+            return Ok(0);
+        }
+
+        let mut builder = PayloadBuilder::new()?;
+        for (alias, value) in &changed {
+            builder.add_metric(&Metric::<String> {
+                name: None,
+                alias: Some(*alias),
+                timestamp: None,
+                datatype: value.datatype(),
+                value: (*value).clone(),
+            })?;
+        }
+        let payload = builder.serialize()?;
+        self.recovering(device_id, &payload)?;
+
+        let count = changed.len();
+        for (alias, value) in changed {
+            self.last_values
+                .insert((device.clone(), alias), value.clone());
+        }
+        Ok(count)
+    }
+
+    /// Publishes an NBIRTH containing `metrics`.
+    ///
+    /// Metrics are added with [`PayloadBuilder::add_metric`], so the same
+    /// type and alias limits apply.
+    pub fn publish_birth_metrics<N: AsRef<str>>(&mut self, metrics: &[Metric<N>]) -> Result<()> {
+        self.publish_birth(&metrics_payload(metrics)?)
+    }
+
+    /// Publishes an NDATA containing `metrics`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, Publisher};
+    ///
+    /// # fn forward(publisher: &mut Publisher, msg: Message) -> Result<(), sparkplug_rs::Error> {
+    /// let metrics = msg.parse_payload()?.to_metrics()?;
+    /// publisher.publish_data_metrics(&metrics)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn publish_data_metrics<N: AsRef<str>>(&mut self, metrics: &[Metric<N>]) -> Result<()> {
+        self.publish_data(&metrics_payload(metrics)?)
+    }
+
+    /// Publishes a DBIRTH containing `metrics` for a device.
+    pub fn publish_device_birth_metrics<N: AsRef<str>>(
+        &mut self,
+        device_id: &str,
+        metrics: &[Metric<N>],
+    ) -> Result<()> {
+        self.publish_device_birth(device_id, &metrics_payload(metrics)?)
+    }
+
+    /// Publishes a DDATA containing `metrics` for a device.
+    pub fn publish_device_data_metrics<N: AsRef<str>>(
+        &mut self,
+        device_id: &str,
+        metrics: &[Metric<N>],
+    ) -> Result<()> {
+        self.publish_device_data(device_id, &metrics_payload(metrics)?)
+    }
+
+    /// Declares a node metric with its alias and initial value.
+    ///
+    /// Once metrics are registered, [`publish_registered_birth`](Self::publish_registered_birth)
+    /// builds the NBIRTH from them, and [`publish_data`](Self::publish_data)
+    /// rejects payloads with metrics that were not registered
+    /// ([`Error::InvalidPayload`]) while tracking the latest values.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{MetricValue, PayloadBuilder, Publisher, PublisherConfig};
+    ///
+    /// let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    /// let mut publisher = Publisher::new(config)?;
+    /// publisher.register_metric("Temperature", 1, MetricValue::Double(20.5))?;
+    /// publisher.register_metric("Active", 2, MetricValue::Boolean(true))?;
+    ///
+    /// publisher.connect()?;
+    /// publisher.publish_registered_birth()?;
+    ///
+    /// let mut data = PayloadBuilder::new()?;
+    /// data.add_double_by_alias(1, 21.0);
+    /// publisher.publish_data(&data.serialize()?)?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn register_metric(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: MetricValue,
+    ) -> Result<()> {
+        self.registry.register(name, alias, value)
+    }
+
+    /// Returns the registered node metrics.
+    pub fn registry(&self) -> &MetricRegistry {
+        &self.registry
+    }
+
+    /// Returns the alias of a node or device metric, assigning one if the name is new.
+    ///
+    /// Aliases are unique across the edge node and its devices and stay the
+    /// same across rebirths. With [`PublisherConfig::alias_file`] set, a new
+    /// assignment is saved before it is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{PayloadBuilder, Publisher, PublisherConfig};
+    ///
+    /// # let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    /// let mut publisher = Publisher::new(config)?;
+    /// let temperature = publisher.alias("Temperature")?;
+    ///
+    /// let mut birth = PayloadBuilder::new()?;
+    /// birth.add_double_with_alias("Temperature", temperature, 20.5)?;
+    ///
+    /// let mut data = PayloadBuilder::new()?;
+    /// data.add_double_by_alias(temperature, 21.0);
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn alias(&mut self, name: &str) -> Result<MetricAlias> {
+        if let Some(alias) = self.aliases.get(name) {
+            return Ok(alias);
+        }
+        let alias = self.aliases.alias(name);
+        if let Some(path) = &self.alias_file {
+            self.aliases.save(path)?;
+        }
+        Ok(alias)
+    }
+
+    /// Returns the metric aliases assigned so far.
+    pub fn aliases(&self) -> &AliasAllocator {
+        &self.aliases
+    }
+
+    /// Publishes an NBIRTH built from the registered metrics and the current bdSeq.
+    ///
+    /// The birth carries the latest value published for each metric. Call
+    /// it again instead of [`rebirth`](Self::rebirth) to rebirth with
+    /// current values rather than those of the previous NBIRTH.
+    pub fn publish_registered_birth(&mut self) -> Result<()> {
+        let birth = self.registry.birth_payload(self.bd_seq())?.serialize()?;
+        self.publish_birth(&birth)
+    }
+
+    /// Re-establishes the session after a connection loss.
+    ///
+    /// Drops the current connection, connects again with the configured
+    /// [`ReconnectPolicy`] (or the default policy, which retries forever),
+    /// and, if the lost session had an NBIRTH, republishes the last NBIRTH
+    /// and the cached DBIRTH of every device birthed or attached in it. The
+    /// C library starts the new session with the next bdSeq.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::Publisher;
+    ///
+    /// # fn example(publisher: &mut Publisher, data: &[u8]) -> Result<(), sparkplug_rs::Error> {
+    /// if publisher.publish_data(data).is_err() {
+    ///     publisher.reconnect()?;
+    ///     publisher.publish_data(data)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id)))]
+    pub fn reconnect(&mut self) -> Result<()> {
+        let policy = self.reconnect.clone().unwrap_or_default();
+        self.reconnect_with(&policy)
+    }
+
+    /// Reconnects with `policy` and republishes the births and backlog.
+    fn reconnect_with(&mut self, policy: &ReconnectPolicy) -> Result<()> {
+        let birth = if self.node_birthed {
+            self.node_birth.clone()
+        } else {
+            None
+        };
+        let devices: BTreeSet<String> =
+            self.birthed.iter().chain(&self.attached).cloned().collect();
+
+        // The connection is usually already gone, so a failure is expected.
+        if self.dry_run.is_none() {
+            unsafe {
+                sys::sparkplug_publisher_disconnect(self.inner);
+            }
+        }
+        self.connected = false;
+
+        // The births are kept until the connection is back, so a failed
+        // attempt still republishes them on the next one.
+        policy.retry(|| self.connect_once())?;
+        self.connected = true;
+        self.node_birthed = false;
+        self.birthed.clear();
+
+        if let Some(birth) = birth {
+            self.publish_birth(&birth)?;
+            for device_id in devices {
+                if let Some(device_birth) = self.device_births.get(&device_id).cloned() {
+                    self.publish_device_birth(&device_id, &device_birth)?;
+                }
+            }
+            self.flush_backlog()?;
+        }
+        Ok(())
+    }
+
+    /// Publishes NDATA/DDATA, applying auto-reconnect and store-and-forward.
+    fn recovering(&mut self, device_id: Option<&str>, payload: &[u8]) -> Result<()> {
+        let send = |publisher: &mut Self| match device_id {
+            None => publisher.send_data(payload),
+            Some(device_id) => publisher.send_device_data(device_id, payload),
+        };
+        let mut result = self.flush_backlog().and_then(|_| send(self));
+        if self.auto_reconnect && matches!(result, Err(Error::PublishFailed { .. })) {
+            // Bounded so a publish falls back to the backlog instead of
+            // blocking while the broker is down.
+            let policy = match &self.reconnect {
+                Some(policy) if policy.max_attempts.is_some() => policy.clone(),
+                _ => ReconnectPolicy {
+                    max_attempts: Some(1),
+                    ..ReconnectPolicy::default()
+                },
+            };
+            result = self.reconnect_with(&policy).and_then(|()| send(self));
+        }
+        match result {
+            Err(Error::PublishFailed { .. } | Error::ConnectionFailed(_))
+                if self.backlog_capacity.is_some() =>
+            {
+                self.enqueue(device_id, payload)
+            }
+            result => result,
+        }
+    }
+
+    fn enqueue(&mut self, device_id: Option<&str>, payload: &[u8]) -> Result<()> {
+        let capacity = self.backlog_capacity.unwrap_or(0);
+        while self.backlog.len() >= capacity && self.backlog.pop_front()?.is_some() {
+            self.backlog_dropped += 1;
+        }
+        self.backlog.push_back(Entry {
+            device_id: device_id.map(str::to_string),
+            payload: payload.to_vec(),
+            queued_at: crate::time::now_millis(),
+        })
+    }
+
+    /// Sends the store-and-forward backlog in order.
+    ///
+    /// Stops at the first failure to reach the broker, or while the session
+    /// is not ready in strict mode, keeping that message and the ones after
+    /// it queued. Messages that can never be sent, such as DDATA for a device
+    /// that is no longer birthed, are dropped and counted in
+    /// [`backlog_rejected`](Self::backlog_rejected) so they do not block the
+    /// rest. Messages older than [`PublisherConfig::backlog_max_age`] are
+    /// dropped and counted in [`backlog_dropped`](Self::backlog_dropped).
+    /// Returns the number of messages sent. Called automatically by
+    /// [`reconnect`](Self::reconnect) and before each NDATA/DDATA.
+    ///
+    /// The C API cannot set the `is_historical` flag on metrics, so queued
+    /// messages are sent unchanged; their payload timestamps still carry
+    /// the original sample time.
+    pub fn flush_backlog(&mut self) -> Result<usize> {
+        let mut sent = 0;
+        let oldest = self
+            .backlog_max_age
+            .map(|max_age| crate::time::now_millis().saturating_sub(max_age.as_millis() as u64));
+        while let Some(entry) = self.backlog.front() {
+            if oldest.is_some_and(|oldest| entry.queued_at < oldest) {
+                self.backlog.pop_front()?;
+                self.backlog_dropped += 1;
+                continue;
+            }
+            let (device_id, payload) = (entry.device_id.clone(), entry.payload.clone());
+            let result = match &device_id {
+                None => self.send_data(&payload),
+                Some(device_id) => self.send_device_data(device_id, &payload),
+            };
+            match result {
+                Ok(()) => sent += 1,
+                Err(
+                    err @ (Error::PublishFailed { .. }
+                    | Error::ConnectionFailed(_)
+                    | Error::InvalidState { .. }),
+                ) => return Err(err),
+                Err(_) => self.backlog_rejected += 1,
+            }
+            self.backlog.pop_front()?;
+        }
+        Ok(sent)
+    }
+
+    /// Ends the session cleanly and stops the publisher.
+    ///
+    /// Sends the store-and-forward backlog, retrying until `timeout`
+    /// elapses. Then publishes the NDEATH if the session has an NBIRTH, and
+    /// disconnects. Afterwards every publish, and [`connect`](Self::connect),
+    /// fails with [`Error::InvalidState`]. The NDEATH and the disconnect still
+    /// happen when the backlog cannot be sent in time; the unsent messages
+    /// stay counted in [`backlog_len`](Self::backlog_len) and
+    /// [`Error::Timeout`] is returned.
+    ///
+    /// The C API reports only whether the client accepted a publish, so
+    /// messages that the C client already accepted are not tracked here.
+    /// Shutdown cannot wait for their QoS 1 acknowledgements beyond what
+    /// the C publish calls already wait for.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::Publisher;
+    /// use std::time::Duration;
+    ///
+    /// # fn stop(publisher: &mut Publisher) -> Result<(), sparkplug_rs::Error> {
+    /// publisher.shutdown(Duration::from_secs(5))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id)))]
+    pub fn shutdown(&mut self, timeout: Duration) -> Result<()> {
+        self.check_shut_down("shutdown")?;
+        let deadline = Instant::now() + timeout;
+        let mut flushed = true;
+        while self.connected && !self.backlog.is_empty() {
+            if self.flush_backlog().is_ok() {
+                break;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                flushed = false;
+                break;
+            }
+            std::thread::sleep((deadline - now).min(Duration::from_millis(100)));
+        }
+
+        let mut result = Ok(());
+        if self.connected {
+            if self.node_birthed {
+                result = self.publish_death();
+            }
+            result = result.and(self.disconnect());
+        }
+        self.shut_down = true;
+        result?;
+        if !flushed || !self.backlog.is_empty() {
+            return Err(Error::Timeout(format!(
+                "{} queued messages to be sent",
+                self.backlog.len()
+            )));
+        }
         Ok(())
     }
 
+    /// Returns true once [`shutdown`](Self::shutdown) has been called.
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    /// Returns the number of messages waiting in the store-and-forward backlog.
+    pub fn backlog_len(&self) -> usize {
+        self.backlog.len()
+    }
+
+    /// Returns the number of queued messages dropped because the backlog was
+    /// full or the message was older than [`PublisherConfig::backlog_max_age`].
+    pub fn backlog_dropped(&self) -> u64 {
+        self.backlog_dropped
+    }
+
+    /// Returns the number of queued messages dropped by
+    /// [`flush_backlog`](Self::flush_backlog) because they could not be sent
+    /// in the current session.
+    pub fn backlog_rejected(&self) -> u64 {
+        self.backlog_rejected
+    }
+
     /// Publishes an NDEATH (Node Death) message.
     ///
     /// Normally not needed as NDEATH is sent automatically on disconnect.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id, seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn publish_death(&mut self) -> Result<()> {
-        let ret = unsafe { sys::sparkplug_publisher_publish_death(self.inner) };
+        self.check_state("publish_death", false)?;
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.node_death()?;
+                0
+            }
+            None => unsafe { sys::sparkplug_publisher_publish_death(self.inner) },
+        };
         if ret != 0 {
             return Err(Error::PublishFailed {
                 message_type: "NDEATH",
                 details: "publish_death failed".to_string(),
             });
         }
+        self.node_birthed = false;
+        self.birthed.clear();
+        self.trace_sent(MessageType::NDeath, None, 0);
         Ok(())
     }
 
     /// Triggers a rebirth (publishes new NBIRTH with incremented bdSeq).
     ///
+    /// The cached DBIRTH of every attached device (see
+    /// [`attach_device`](Self::attach_device)) is republished afterwards.
     /// This is typically called in response to an NCMD rebirth command.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(edge_node_id = %self.edge_node_id, seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn rebirth(&mut self) -> Result<()> {
-        let ret = unsafe { sys::sparkplug_publisher_rebirth(self.inner) };
+        self.check_state("rebirth", false)?;
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.rebirth(self.node_birth.as_deref())?;
+                0
+            }
+            None => unsafe { sys::sparkplug_publisher_rebirth(self.inner) },
+        };
         if ret != 0 {
             return Err(Error::OperationFailed {
                 operation: "rebirth",
             });
         }
+        self.node_birthed = true;
+        self.birthed.clear();
+        self.last_values.clear();
+        self.trace_sent(MessageType::NBirth, None, 0);
+        let births: Vec<(String, Vec<u8>)> = self
+            .attached
+            .iter()
+            .filter_map(|id| Some((id.clone(), self.device_births.get(id)?.clone())))
+            .collect();
+        for (device_id, birth) in births {
+            self.publish_device_birth(&device_id, &birth)?;
+        }
         Ok(())
     }
 
+    /// Handles a "Node Control/Rebirth" NCMD addressed to this node.
+    ///
+    /// If `message` is an NCMD for this publisher's group and edge node with
+    /// `Node Control/Rebirth` set to true, calls [`rebirth`](Self::rebirth)
+    /// (which also republishes the attached devices' DBIRTHs) and returns
+    /// `Ok(true)`. Other messages are ignored and return `Ok(false)`.
+    pub fn handle_command(&mut self, message: &Message) -> Result<bool> {
+        let topic = message.parse_topic()?;
+        if topic.message_type() != Some(MessageType::NCmd)
+            || topic.group_id() != Some(self.group_id.as_str())
+            || topic.edge_node_id() != Some(self.edge_node_id.as_str())
+        {
+            return Ok(false);
+        }
+        let payload = message.parse_payload()?;
+        for metric in payload.metrics_ref() {
+            let metric = metric?;
+            if metric.name == Some("Node Control/Rebirth")
+                && metric.value == MetricValueRef::Boolean(true)
+            {
+                self.rebirth()?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Turns the publisher into a shareable [`PublisherHandle`].
+    pub fn into_handle(self) -> PublisherHandle {
+        PublisherHandle::new(self)
+    }
+
+    /// Returns a command callback that answers rebirth requests for `publisher`.
+    ///
+    /// Install it with [`Subscriber::set_command_callback`](crate::Subscriber::set_command_callback)
+    /// on a subscriber of the node's group; see [`handle_command`](Self::handle_command).
+    /// Failed rebirths are dropped; the next request retries.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, Publisher, PublisherConfig, Subscriber, SubscriberConfig};
+    /// use std::sync::{Arc, Mutex, MutexGuard};
+    ///
+    /// let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    /// let publisher = Arc::new(Mutex::new(Publisher::new(config)?));
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "edge_cmd", "Energy");
+    /// let mut commands = Subscriber::new(config, Box::new(|_: Message| {}))?;
+    /// commands.set_command_callback(Publisher::rebirth_on_command(publisher.clone()))?;
+    /// commands.connect()?;
+    /// commands.subscribe_node("Gateway01")?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn rebirth_on_command(publisher: Arc<Mutex<Publisher>>) -> CommandCallback {
+        Box::new(move |message: Message| {
+            if let Ok(mut publisher) = publisher.lock() {
+                let _ = publisher.handle_command(&message);
+            }
+        })
+    }
+
+    /// Attaches a device at runtime: publishes its DBIRTH and caches it.
+    ///
+    /// Attached devices are reborn automatically on [`rebirth`](Self::rebirth).
+    /// Attaching an already attached device replaces its cached DBIRTH.
+    pub fn attach_device(&mut self, device_id: &str, birth: &[u8]) -> Result<()> {
+        self.publish_device_birth(device_id, birth)?;
+        self.attached.insert(device_id.to_string());
+        Ok(())
+    }
+
+    /// Detaches a device: publishes its DDEATH and forgets its cached DBIRTH.
+    ///
+    /// Further [`publish_device_data`](Self::publish_device_data) calls for the
+    /// device fail with [`Error::DeviceNotBirthed`] until it is birthed again,
+    /// whatever the [`DeviceBirthPolicy`].
+    pub fn detach_device(&mut self, device_id: &str) -> Result<()> {
+        self.publish_device_death(device_id)?;
+        self.attached.remove(device_id);
+        self.device_births.remove(device_id);
+        Ok(())
+    }
+
+    /// Returns a handle scoped to one device.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{PayloadBuilder, Publisher, PublisherConfig};
+    ///
+    /// let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    /// let mut publisher = Publisher::new(config)?;
+    /// publisher.connect()?;
+    /// # let nbirth = PayloadBuilder::new()?.serialize()?;
+    /// publisher.publish_birth(&nbirth)?;
+    ///
+    /// let mut motor = publisher.device("Motor01");
+    /// let mut birth = PayloadBuilder::new()?;
+    /// birth.add_double_with_alias("Speed", 1, 0.0)?;
+    /// motor.publish_birth(&birth.serialize()?)?;
+    ///
+    /// let mut data = PayloadBuilder::new()?;
+    /// data.add_double_by_alias(1, 1450.0);
+    /// motor.publish_data(&data.serialize()?)?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn device(&mut self, device_id: impl Into<String>) -> DeviceHandle<'_> {
+        DeviceHandle {
+            publisher: self,
+            device_id: device_id.into(),
+        }
+    }
+
+    /// Returns the IDs of the attached devices.
+    pub fn attached_devices(&self) -> impl Iterator<Item = &str> {
+        self.attached.iter().map(|id| id.as_str())
+    }
+
+    /// Returns true if the device has a DBIRTH in the current session.
+    pub fn is_device_birthed(&self, device_id: &str) -> bool {
+        self.birthed.contains(device_id)
+    }
+
+    /// Returns the IDs of the devices with a DBIRTH in the current session.
+    pub fn birthed_devices(&self) -> impl Iterator<Item = &str> {
+        self.birthed.iter().map(|id| id.as_str())
+    }
+
+    /// Returns true if connected to the broker.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Returns true if an NBIRTH was published in the current session.
+    pub fn is_birthed(&self) -> bool {
+        self.node_birthed
+    }
+
+    fn check_shut_down(&self, operation: &'static str) -> Result<()> {
+        if self.shut_down {
+            return Err(Error::InvalidState {
+                operation,
+                details: "publisher is shut down".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Emits a `tracing` event for a message published by this node and
+    /// records the sequence numbers on the current publish span.
+    ///
+    /// `size` is the payload size passed in, or 0 when the C library builds
+    /// the payload.
+    fn trace_sent(&self, message_type: MessageType, device_id: Option<&str>, size: usize) {
+        #[cfg(feature = "tracing")]
+        {
+            let topic = crate::topic::ParsedTopic::Sparkplug {
+                message_type,
+                group_id: self.group_id.clone(),
+                edge_node_id: self.edge_node_id.clone(),
+                device_id: device_id.map(str::to_string),
+            };
+            let span = tracing::Span::current();
+            span.record("seq", self.seq());
+            span.record("bd_seq", self.bd_seq());
+            tracing::debug!(
+                topic = %topic,
+                seq = self.seq(),
+                bd_seq = self.bd_seq(),
+                size,
+                "published {}",
+                message_type
+            );
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = (message_type, device_id, size);
+    }
+
+    /// In strict mode, checks that `operation` is allowed in the current state.
+    fn check_state(&self, operation: &'static str, requires_birth: bool) -> Result<()> {
+        self.check_shut_down(operation)?;
+        if !self.strict {
+            return Ok(());
+        }
+        if !self.connected {
+            return Err(Error::InvalidState {
+                operation,
+                details: "not connected (call connect first)".to_string(),
+            });
+        }
+        if requires_birth && !self.node_birthed {
+            return Err(Error::InvalidState {
+                operation,
+                details: "no NBIRTH in the current session (call publish_birth first)".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies the [`DeviceBirthPolicy`] before publishing device data.
+    fn ensure_device_birthed(&mut self, device_id: &str) -> Result<()> {
+        if self.birthed.contains(device_id) {
+            return Ok(());
+        }
+        let not_birthed = || Error::DeviceNotBirthed {
+            device_id: device_id.to_string(),
+        };
+        match self.device_birth_policy {
+            DeviceBirthPolicy::Unchecked if !self.dead.contains(device_id) => Ok(()),
+            DeviceBirthPolicy::Unchecked | DeviceBirthPolicy::Reject => Err(not_birthed()),
+            DeviceBirthPolicy::AutoBirth => {
+                let birth = self.device_births.get(device_id).ok_or_else(not_birthed)?;
+                let birth = birth.clone();
+                self.publish_device_birth(device_id, &birth)
+            }
+        }
+    }
+
+    /// Returns the underlying `sparkplug_publisher_t` handle.
+    ///
+    /// This is an escape hatch for calling `sparkplug_c` functions that are
+    /// not wrapped yet. The handle stays owned by this `Publisher`: do not
+    /// destroy it. Messages published through it bypass the session tracking
+    /// of this wrapper (device births, strict mode).
+    pub fn as_raw_ptr(&self) -> *mut std::ffi::c_void {
+        self.inner as *mut std::ffi::c_void
+    }
+
     /// Gets the current message sequence number (0-255).
     pub fn seq(&self) -> u64 {
-        unsafe { sys::sparkplug_publisher_get_seq(self.inner) }
+        match &self.dry_run {
+            Some(dry) => dry.seq(),
+            None => unsafe { sys::sparkplug_publisher_get_seq(self.inner) },
+        }
     }
 
     /// Gets the current birth/death sequence number.
     pub fn bd_seq(&self) -> u64 {
-        unsafe { sys::sparkplug_publisher_get_bd_seq(self.inner) }
+        match &self.dry_run {
+            Some(dry) => dry.bd_seq(),
+            None => unsafe { sys::sparkplug_publisher_get_bd_seq(self.inner) },
+        }
     }
 
     /// Publishes a DBIRTH (Device Birth) message for a device.
     ///
     /// Must call publish_birth() before publishing any device births.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(device_id = %device_id, size = payload.len(), seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn publish_device_birth(&mut self, device_id: &str, payload: &[u8]) -> Result<()> {
+        self.check_state("publish_device_birth", true)?;
         let c_device_id = CString::new(device_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_device_birth(
-                self.inner,
-                c_device_id.as_ptr(),
-                payload.as_ptr(),
-                payload.len(),
-            )
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.device_birth(device_id, payload)?;
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_device_birth(
+                    self.inner,
+                    c_device_id.as_ptr(),
+                    payload.as_ptr(),
+                    payload.len(),
+                )
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -223,21 +1368,74 @@ impl Publisher {
                 details: format!("publish_device_birth failed for device '{}'", device_id),
             });
         }
+        self.device_births
+            .insert(device_id.to_string(), payload.to_vec());
+        self.birthed.insert(device_id.to_string());
+        self.dead.remove(device_id);
+        self.last_values
+            .retain(|(device, _), _| device.as_deref() != Some(device_id));
+        self.trace_sent(MessageType::DBirth, Some(device_id), payload.len());
         Ok(())
     }
 
     /// Publishes a DDATA (Device Data) message for a device.
     ///
-    /// Must call publish_device_birth() before the first publish_device_data().
+    /// Must call publish_device_birth() before the first publish_device_data()
+    /// of each session; otherwise the configured [`DeviceBirthPolicy`] applies.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(device_id = %device_id, size = payload.len(), seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn publish_device_data(&mut self, device_id: &str, payload: &[u8]) -> Result<()> {
+        self.recovering(Some(device_id), payload)
+    }
+
+    /// Publishes DDATA for several devices.
+    ///
+    /// The session state and the [`DeviceBirthPolicy`] are checked for every
+    /// device before anything is sent, so an unbirthed device rejects the
+    /// whole batch. The C API has no batched publish, so messages are then
+    /// sent one by one in order; on a publish failure the earlier messages
+    /// have already been sent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::Publisher;
+    ///
+    /// # fn scan(publisher: &mut Publisher, motor: Vec<u8>, pump: Vec<u8>) -> Result<(), sparkplug_rs::Error> {
+    /// publisher.publish_device_data_batch(&[("Motor01", motor), ("Pump01", pump)])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn publish_device_data_batch<D: AsRef<str>, P: AsRef<[u8]>>(
+        &mut self,
+        batch: &[(D, P)],
+    ) -> Result<()> {
+        self.check_state("publish_device_data_batch", true)?;
+        for (device_id, _) in batch {
+            self.ensure_device_birthed(device_id.as_ref())?;
+        }
+        for (device_id, payload) in batch {
+            self.publish_device_data(device_id.as_ref(), payload.as_ref())?;
+        }
+        Ok(())
+    }
+
+    fn send_device_data(&mut self, device_id: &str, payload: &[u8]) -> Result<()> {
+        self.check_state("publish_device_data", true)?;
+        self.ensure_device_birthed(device_id)?;
         let c_device_id = CString::new(device_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_device_data(
-                self.inner,
-                c_device_id.as_ptr(),
-                payload.as_ptr(),
-                payload.len(),
-            )
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.data(Some(device_id), payload)?;
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_device_data(
+                    self.inner,
+                    c_device_id.as_ptr(),
+                    payload.as_ptr(),
+                    payload.len(),
+                )
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -245,14 +1443,23 @@ impl Publisher {
                 details: format!("publish_device_data failed for device '{}'", device_id),
             });
         }
+        self.trace_sent(MessageType::DData, Some(device_id), payload.len());
         Ok(())
     }
 
     /// Publishes a DDEATH (Device Death) message for a device.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(device_id = %device_id, seq = tracing::field::Empty, bd_seq = tracing::field::Empty)))]
     pub fn publish_device_death(&mut self, device_id: &str) -> Result<()> {
+        self.check_state("publish_device_death", true)?;
         let c_device_id = CString::new(device_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_device_death(self.inner, c_device_id.as_ptr())
+        let ret = match &mut self.dry_run {
+            Some(dry) => {
+                dry.device_death(device_id)?;
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_device_death(self.inner, c_device_id.as_ptr())
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -260,23 +1467,34 @@ impl Publisher {
                 details: format!("publish_device_death failed for device '{}'", device_id),
             });
         }
+        self.birthed.remove(device_id);
+        self.dead.insert(device_id.to_string());
+        self.trace_sent(MessageType::DDeath, Some(device_id), 0);
         Ok(())
     }
 
     /// Publishes an NCMD (Node Command) message to another edge node.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(target_edge_node_id = %target_edge_node_id, size = payload.len())))]
     pub fn publish_node_command(
         &mut self,
         target_edge_node_id: &str,
         payload: &[u8],
     ) -> Result<()> {
+        self.check_state("publish_node_command", false)?;
         let c_target = CString::new(target_edge_node_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_node_command(
-                self.inner,
-                c_target.as_ptr(),
-                payload.as_ptr(),
-                payload.len(),
-            )
+        let ret = match &self.dry_run {
+            Some(dry) => {
+                dry.command(target_edge_node_id, None, payload);
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_node_command(
+                    self.inner,
+                    c_target.as_ptr(),
+                    payload.as_ptr(),
+                    payload.len(),
+                )
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -291,22 +1509,30 @@ impl Publisher {
     }
 
     /// Publishes a DCMD (Device Command) message to a device on another edge node.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, err, fields(target_edge_node_id = %target_edge_node_id, target_device_id = %target_device_id, size = payload.len())))]
     pub fn publish_device_command(
         &mut self,
         target_edge_node_id: &str,
         target_device_id: &str,
         payload: &[u8],
     ) -> Result<()> {
+        self.check_state("publish_device_command", false)?;
         let c_edge_node = CString::new(target_edge_node_id)?;
         let c_device = CString::new(target_device_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_device_command(
-                self.inner,
-                c_edge_node.as_ptr(),
-                c_device.as_ptr(),
-                payload.as_ptr(),
-                payload.len(),
-            )
+        let ret = match &self.dry_run {
+            Some(dry) => {
+                dry.command(target_edge_node_id, Some(target_device_id), payload);
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_device_command(
+                    self.inner,
+                    c_edge_node.as_ptr(),
+                    c_device.as_ptr(),
+                    payload.as_ptr(),
+                    payload.len(),
+                )
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -341,7 +1567,6 @@ impl Publisher {
     ///
     /// ```no_run
     /// use sparkplug_rs::{Publisher, PublisherConfig};
-    /// use std::time::{SystemTime, UNIX_EPOCH};
     ///
     /// let config = PublisherConfig::new(
     ///     "tcp://localhost:1883",
@@ -353,17 +1578,30 @@ impl Publisher {
     /// let mut publisher = Publisher::new(config)?;
     /// publisher.connect()?;
     ///
-    /// let timestamp = SystemTime::now()
-    ///     .duration_since(UNIX_EPOCH)?
-    ///     .as_millis() as u64;
+    /// let timestamp = sparkplug_rs::time::now_millis();
     ///
     /// publisher.publish_state_birth("SCADA01", timestamp)?;
     /// # Ok::<(), sparkplug_rs::Error>(())
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, err, fields(host_id = %host_id, timestamp = timestamp))
+    )]
     pub fn publish_state_birth(&mut self, host_id: &str, timestamp: u64) -> Result<()> {
+        self.check_state("publish_state_birth", false)?;
         let c_host_id = CString::new(host_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_state_birth(self.inner, c_host_id.as_ptr(), timestamp)
+        let ret = match &self.dry_run {
+            Some(dry) => {
+                dry.state(host_id, true, timestamp);
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_state_birth(
+                    self.inner,
+                    c_host_id.as_ptr(),
+                    timestamp,
+                )
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -396,7 +1634,6 @@ impl Publisher {
     ///
     /// ```no_run
     /// use sparkplug_rs::{Publisher, PublisherConfig};
-    /// use std::time::{SystemTime, UNIX_EPOCH};
     ///
     /// let config = PublisherConfig::new(
     ///     "tcp://localhost:1883",
@@ -408,19 +1645,32 @@ impl Publisher {
     /// let mut publisher = Publisher::new(config)?;
     /// publisher.connect()?;
     ///
-    /// let timestamp = SystemTime::now()
-    ///     .duration_since(UNIX_EPOCH)?
-    ///     .as_millis() as u64;
+    /// let timestamp = sparkplug_rs::time::now_millis();
     ///
     /// publisher.publish_state_birth("SCADA01", timestamp)?;
     /// // ... later ...
     /// publisher.publish_state_death("SCADA01", timestamp)?;
     /// # Ok::<(), sparkplug_rs::Error>(())
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, err, fields(host_id = %host_id, timestamp = timestamp))
+    )]
     pub fn publish_state_death(&mut self, host_id: &str, timestamp: u64) -> Result<()> {
+        self.check_state("publish_state_death", false)?;
         let c_host_id = CString::new(host_id)?;
-        let ret = unsafe {
-            sys::sparkplug_publisher_publish_state_death(self.inner, c_host_id.as_ptr(), timestamp)
+        let ret = match &self.dry_run {
+            Some(dry) => {
+                dry.state(host_id, false, timestamp);
+                0
+            }
+            None => unsafe {
+                sys::sparkplug_publisher_publish_state_death(
+                    self.inner,
+                    c_host_id.as_ptr(),
+                    timestamp,
+                )
+            },
         };
         if ret != 0 {
             return Err(Error::PublishFailed {
@@ -430,6 +1680,250 @@ impl Publisher {
         }
         Ok(())
     }
+
+    /// Publishes a STATE birth message with a [`SystemTime`] timestamp.
+    ///
+    /// See [`publish_state_birth`](Self::publish_state_birth).
+    pub fn publish_state_birth_at(&mut self, host_id: &str, time: SystemTime) -> Result<()> {
+        self.publish_state_birth(host_id, crate::time::to_millis(time))
+    }
+
+    /// Publishes a STATE death message with a [`SystemTime`] timestamp.
+    ///
+    /// See [`publish_state_death`](Self::publish_state_death); the time must
+    /// match the one used for the birth.
+    pub fn publish_state_death_at(&mut self, host_id: &str, time: SystemTime) -> Result<()> {
+        self.publish_state_death(host_id, crate::time::to_millis(time))
+    }
+}
+
+/// Returns true if `value` should be republished after `last`.
+fn exceeds_deadband(last: Option<&MetricValue>, value: &MetricValue, deadband: f64) -> bool {
+    let Some(last) = last else {
+        return true;
+    };
+    match (last, value) {
+        (MetricValue::Boolean(_), _) | (_, MetricValue::Boolean(_)) => last != value,
+        _ if last.datatype() != value.datatype() => true,
+        _ => match (integer(last), integer(value)) {
+            // Exact, as 64-bit integers do not all fit in an f64.
+            (Some(last), Some(value)) => (value - last).unsigned_abs() as f64 > deadband,
+            _ => match (last.as_f64(), value.as_f64()) {
+                // NaN never exceeds a deadband, so entering or leaving NaN is a change.
+                (Some(last), Some(value)) if last.is_nan() || value.is_nan() => {
+                    last.is_nan() != value.is_nan()
+                }
+                (Some(last), Some(value)) => (value - last).abs() > deadband,
+                _ => last != value,
+            },
+        },
+    }
+}
+
+fn integer(value: &MetricValue) -> Option<i128> {
+    match value {
+        MetricValue::Int8(v) => Some(*v as i128),
+        MetricValue::Int16(v) => Some(*v as i128),
+        MetricValue::Int32(v) => Some(*v as i128),
+        MetricValue::Int64(v) => Some(*v as i128),
+        MetricValue::UInt8(v) => Some(*v as i128),
+        MetricValue::UInt16(v) => Some(*v as i128),
+        MetricValue::UInt32(v) => Some(*v as i128),
+        MetricValue::UInt64(v) | MetricValue::DateTime(v) => Some(*v as i128),
+        _ => None,
+    }
+}
+
+fn metrics_payload<N: AsRef<str>>(metrics: &[Metric<N>]) -> Result<Vec<u8>> {
+    let mut builder = PayloadBuilder::new()?;
+    for metric in metrics {
+        builder.add_metric(metric)?;
+    }
+    builder.serialize()
+}
+
+/// A [`Publisher`] scoped to one device, returned by [`Publisher::device`].
+pub struct DeviceHandle<'a> {
+    publisher: &'a mut Publisher,
+    device_id: String,
+}
+
+impl DeviceHandle<'_> {
+    /// Returns the device ID.
+    pub fn id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Publishes the device's DBIRTH; see [`Publisher::publish_device_birth`].
+    pub fn publish_birth(&mut self, payload: &[u8]) -> Result<()> {
+        self.publisher
+            .publish_device_birth(&self.device_id, payload)
+    }
+
+    /// Publishes the device's DDATA; see [`Publisher::publish_device_data`].
+    pub fn publish_data(&mut self, payload: &[u8]) -> Result<()> {
+        self.publisher.publish_device_data(&self.device_id, payload)
+    }
+
+    /// Publishes the device's DDEATH; see [`Publisher::publish_device_death`].
+    pub fn publish_death(&mut self) -> Result<()> {
+        self.publisher.publish_device_death(&self.device_id)
+    }
+
+    /// Attaches the device; see [`Publisher::attach_device`].
+    pub fn attach(&mut self, birth: &[u8]) -> Result<()> {
+        self.publisher.attach_device(&self.device_id, birth)
+    }
+
+    /// Detaches the device; see [`Publisher::detach_device`].
+    pub fn detach(&mut self) -> Result<()> {
+        self.publisher.detach_device(&self.device_id)
+    }
+
+    /// Returns the last DBIRTH payload published for the device.
+    pub fn birth(&self) -> Option<&[u8]> {
+        self.publisher
+            .device_births
+            .get(&self.device_id)
+            .map(Vec::as_slice)
+    }
+
+    /// Returns true if the device has a DBIRTH in the current session.
+    pub fn is_birthed(&self) -> bool {
+        self.publisher.is_device_birthed(&self.device_id)
+    }
+}
+
+/// A cheaply cloneable, shareable [`Publisher`].
+///
+/// Every clone refers to the same publisher, and all methods take `&self`,
+/// so the handle can be moved into threads and callbacks without wrapping
+/// it in `Arc<Mutex<_>>` first. Calls are serialized by an internal lock,
+/// which keeps the session state (sequence numbers, device births) consistent
+/// across threads. Use [`lock`](Self::lock) for the methods that are not
+/// forwarded.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{PayloadBuilder, Publisher, PublisherConfig};
+/// use std::thread;
+///
+/// let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+/// let publisher = Publisher::new(config)?.into_handle();
+/// publisher.connect()?;
+///
+/// let mut birth = PayloadBuilder::new()?;
+/// birth.add_double_with_alias("Temperature", 1, 20.5)?;
+/// publisher.publish_birth(&birth.serialize()?)?;
+///
+/// let worker = publisher.clone();
+/// thread::spawn(move || -> sparkplug_rs::Result<()> {
+///     let mut data = PayloadBuilder::new()?;
+///     data.add_double_by_alias(1, 21.0);
+///     worker.publish_data(&data.serialize()?)
+/// });
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct PublisherHandle {
+    inner: Arc<Mutex<Publisher>>,
+}
+
+impl PublisherHandle {
+    /// Wraps a publisher in a shareable handle.
+    pub fn new(publisher: Publisher) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(publisher)),
+        }
+    }
+
+    /// Locks the publisher for calls that are not forwarded by the handle.
+    ///
+    /// A panic in another thread holding the lock does not poison the
+    /// handle: the publisher is returned as it was left.
+    pub fn lock(&self) -> MutexGuard<'_, Publisher> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Connects to the MQTT broker; see [`Publisher::connect`].
+    pub fn connect(&self) -> Result<()> {
+        self.lock().connect()
+    }
+
+    /// Disconnects from the broker; see [`Publisher::disconnect`].
+    pub fn disconnect(&self) -> Result<()> {
+        self.lock().disconnect()
+    }
+
+    /// Publishes an NBIRTH; see [`Publisher::publish_birth`].
+    pub fn publish_birth(&self, payload: &[u8]) -> Result<()> {
+        self.lock().publish_birth(payload)
+    }
+
+    /// Publishes an NDATA; see [`Publisher::publish_data`].
+    pub fn publish_data(&self, payload: &[u8]) -> Result<()> {
+        self.lock().publish_data(payload)
+    }
+
+    /// Publishes an NDATA with the changed metrics; see [`Publisher::publish_changed`].
+    pub fn publish_changed<A: Into<MetricAlias> + Copy>(
+        &self,
+        metrics: &[(A, MetricValue)],
+    ) -> Result<usize> {
+        self.lock().publish_changed(metrics)
+    }
+
+    /// Triggers a rebirth; see [`Publisher::rebirth`].
+    pub fn rebirth(&self) -> Result<()> {
+        self.lock().rebirth()
+    }
+
+    /// Publishes a DBIRTH; see [`Publisher::publish_device_birth`].
+    pub fn publish_device_birth(&self, device_id: &str, payload: &[u8]) -> Result<()> {
+        self.lock().publish_device_birth(device_id, payload)
+    }
+
+    /// Publishes a DDATA; see [`Publisher::publish_device_data`].
+    pub fn publish_device_data(&self, device_id: &str, payload: &[u8]) -> Result<()> {
+        self.lock().publish_device_data(device_id, payload)
+    }
+
+    /// Publishes a DDATA with the changed metrics; see [`Publisher::publish_device_changed`].
+    pub fn publish_device_changed<A: Into<MetricAlias> + Copy>(
+        &self,
+        device_id: &str,
+        metrics: &[(A, MetricValue)],
+    ) -> Result<usize> {
+        self.lock().publish_device_changed(device_id, metrics)
+    }
+
+    /// Publishes a DDEATH; see [`Publisher::publish_device_death`].
+    pub fn publish_device_death(&self, device_id: &str) -> Result<()> {
+        self.lock().publish_device_death(device_id)
+    }
+
+    /// Gets the current message sequence number (0-255).
+    pub fn seq(&self) -> u64 {
+        self.lock().seq()
+    }
+
+    /// Gets the current birth/death sequence number.
+    pub fn bd_seq(&self) -> u64 {
+        self.lock().bd_seq()
+    }
+
+    /// Returns a command callback that answers rebirth requests; see
+    /// [`Publisher::rebirth_on_command`].
+    pub fn rebirth_on_command(&self) -> CommandCallback {
+        Publisher::rebirth_on_command(self.inner.clone())
+    }
+}
+
+impl From<Publisher> for PublisherHandle {
+    fn from(publisher: Publisher) -> Self {
+        Self::new(publisher)
+    }
 }
 
 impl Drop for Publisher {
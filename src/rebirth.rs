@@ -0,0 +1,170 @@
+//! Automatic rebirth requests for primary hosts.
+//!
+//! A Sparkplug 2.2 primary host that receives data from an edge node it has
+//! no current NBIRTH for, or that detects a gap in a node's sequence numbers,
+//! must ask the node to republish its births with a `Node Control/Rebirth`
+//! NCMD. [`AutoRebirth`] tracks the birth and sequence state of every node and
+//! decides when such a request is due, backing off between repeated requests
+//! to the same node according to a [`ReconnectPolicy`].
+//!
+//! [`HostApplication::set_auto_rebirth`](crate::HostApplication::set_auto_rebirth)
+//! wires it to a host so the requests are published automatically.
+
+use crate::reconnect::ReconnectPolicy;
+use crate::sequence::{SequenceStatus, SequenceTracker};
+use crate::subscriber::Message;
+use crate::topic::MessageType;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Why a rebirth is requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebirthReason {
+    /// A message arrived from a node without a current NBIRTH.
+    UnknownNode,
+    /// A node's sequence number broke continuity.
+    SequenceError(SequenceStatus),
+}
+
+/// A rebirth request decided by [`AutoRebirth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebirthRequest {
+    /// Group of the edge node
+    pub group_id: String,
+    /// Edge node to send the rebirth NCMD to
+    pub edge_node_id: String,
+    /// Why the rebirth is requested
+    pub reason: RebirthReason,
+    /// Number of requests sent to the node since its last birth or death, including this one
+    pub attempt: u32,
+}
+
+#[derive(Default)]
+struct NodeState {
+    birthed: bool,
+    sequence: SequenceTracker,
+    requests: u32,
+    next_request: Option<Instant>,
+}
+
+/// Decides when a primary host should ask an edge node to rebirth.
+///
+/// The first request to a node is due immediately; each further request
+/// waits for the policy's delay, and no requests are sent once
+/// `max_attempts` is reached. An NBIRTH or NDEATH from the node resets its
+/// backoff.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{AutoRebirth, Message, ReconnectPolicy};
+///
+/// let mut rebirth = AutoRebirth::new(ReconnectPolicy::default());
+/// # let message: Message = unimplemented!();
+/// if let Some(request) = rebirth.observe(&message) {
+///     println!(
+///         "rebirth {}/{} (attempt {}, {:?})",
+///         request.group_id, request.edge_node_id, request.attempt, request.reason
+///     );
+/// }
+/// ```
+pub struct AutoRebirth {
+    backoff: ReconnectPolicy,
+    nodes: HashMap<(String, String), NodeState>,
+}
+
+impl AutoRebirth {
+    /// Creates a scheduler that spaces repeated requests according to `backoff`.
+    pub fn new(backoff: ReconnectPolicy) -> Self {
+        Self {
+            backoff,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Feeds a received message; returns the rebirth request it calls for, if any.
+    pub fn observe(&mut self, message: &Message) -> Option<RebirthRequest> {
+        self.observe_at(message, Instant::now())
+    }
+
+    /// Like [`observe`](Self::observe), with an explicit current time.
+    pub fn observe_at(&mut self, message: &Message, now: Instant) -> Option<RebirthRequest> {
+        let topic = message.parse_topic().ok()?;
+        let (Some(msg_type), Some(group_id), Some(edge_node_id)) =
+            (topic.message_type(), topic.group_id(), topic.edge_node_id())
+        else {
+            return None;
+        };
+        if msg_type.is_command() {
+            return None;
+        }
+
+        let node = self
+            .nodes
+            .entry((group_id.to_string(), edge_node_id.to_string()))
+            .or_default();
+
+        let reason = match msg_type {
+            MessageType::NBirth => {
+                node.birthed = true;
+                node.requests = 0;
+                node.next_request = None;
+                if let Some(seq) = message.parse_payload().ok().and_then(|p| p.seq()) {
+                    node.sequence.check(msg_type, seq);
+                }
+                return None;
+            }
+            MessageType::NDeath => {
+                node.birthed = false;
+                node.sequence.reset();
+                node.requests = 0;
+                node.next_request = None;
+                return None;
+            }
+            _ if !node.birthed => RebirthReason::UnknownNode,
+            _ => {
+                let seq = message.parse_payload().ok()?.seq()?;
+                let status = node.sequence.check(msg_type, seq);
+                if status.is_ok() {
+                    return None;
+                }
+                node.birthed = false;
+                node.sequence.reset();
+                RebirthReason::SequenceError(status)
+            }
+        };
+
+        if !self.backoff.allows_retry(node.requests)
+            || node.next_request.is_some_and(|due| now < due)
+        {
+            return None;
+        }
+        node.next_request = Some(now + self.backoff.delay(node.requests));
+        node.requests += 1;
+
+        Some(RebirthRequest {
+            group_id: group_id.to_string(),
+            edge_node_id: edge_node_id.to_string(),
+            reason,
+            attempt: node.requests,
+        })
+    }
+
+    /// Returns true if the node's current NBIRTH has been seen and its
+    /// sequence is intact.
+    pub fn is_birthed(&self, group_id: &str, edge_node_id: &str) -> bool {
+        self.node(group_id, edge_node_id)
+            .is_some_and(|node| node.birthed)
+    }
+
+    /// Returns the number of requests sent to a node since its last birth or death.
+    pub fn requests(&self, group_id: &str, edge_node_id: &str) -> u32 {
+        self.node(group_id, edge_node_id)
+            .map_or(0, |node| node.requests)
+    }
+
+    fn node(&self, group_id: &str, edge_node_id: &str) -> Option<&NodeState> {
+        self.nodes
+            .get(&(group_id.to_string(), edge_node_id.to_string()))
+    }
+}
@@ -0,0 +1,113 @@
+//! Connection retry with exponential backoff.
+//!
+//! [`ReconnectPolicy`] describes how [`Publisher::connect`](crate::Publisher::connect)
+//! and [`Subscriber::connect`](crate::Subscriber::connect) retry a failed
+//! connection attempt: the delay starts at `initial_delay`, is multiplied by
+//! `multiplier` after each failure up to `max_delay`, and is randomized by
+//! `jitter` so a fleet of nodes does not reconnect in lockstep after a broker
+//! restart.
+
+use crate::error::Result;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Backoff strategy for connection attempts.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{Publisher, PublisherConfig, ReconnectPolicy};
+/// use std::time::Duration;
+///
+/// let mut config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+/// config.reconnect = Some(ReconnectPolicy {
+///     initial_delay: Duration::from_millis(500),
+///     max_delay: Duration::from_secs(30),
+///     max_attempts: Some(10),
+///     ..ReconnectPolicy::default()
+/// });
+///
+/// let mut publisher = Publisher::new(config)?;
+/// publisher.connect()?; // retries up to 10 times
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first retry.
+    pub initial_delay: Duration,
+    /// Upper bound of the delay between attempts.
+    pub max_delay: Duration,
+    /// Factor applied to the delay after each failed attempt.
+    pub multiplier: f64,
+    /// Random variation of each delay, as a fraction in `0.0..=1.0`
+    /// (0.2 means ±20%).
+    pub jitter: f64,
+    /// Total number of attempts, including the first; `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.1,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Returns the delay before retry number `retry` (0-based), without jitter.
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(retry.min(i32::MAX as u32) as i32);
+        let delay = self.initial_delay.as_secs_f64() * factor;
+        // A zero initial delay times an overflowed factor is NaN.
+        if !delay.is_finite() || delay >= self.max_delay.as_secs_f64() {
+            self.max_delay
+        } else {
+            Duration::from_secs_f64(delay)
+        }
+    }
+
+    /// Returns the delay before retry number `retry` (0-based), with jitter applied.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return base;
+        }
+        // Uniform in [-1, 1]; no cryptographic quality needed.
+        let unit = RandomState::new().hash_one(retry) as f64 / u64::MAX as f64 * 2.0 - 1.0;
+        base.mul_f64(1.0 + jitter * unit)
+    }
+
+    /// Returns true if another attempt is allowed after `attempts` failures.
+    pub fn allows_retry(&self, attempts: u32) -> bool {
+        self.max_attempts.is_none_or(|max| attempts < max)
+    }
+
+    /// Runs `attempt` until it succeeds or the attempts are exhausted.
+    ///
+    /// Sleeps between attempts and returns the last error on exhaustion.
+    pub fn retry<T>(&self, mut attempt: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut failures = 0;
+        loop {
+            match attempt() {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    failures += 1;
+                    if !self.allows_retry(failures) {
+                        return Err(err);
+                    }
+                    std::thread::sleep(self.delay(failures - 1));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Declared node metrics.
+//!
+//! A [`MetricRegistry`] holds the metrics an edge node declares in its
+//! NBIRTH. [`Publisher::register_metric`](crate::Publisher::register_metric)
+//! fills the publisher's registry; the publisher then builds the NBIRTH from
+//! it and rejects NDATA that references metrics the birth did not declare.
+
+use crate::error::{Error, Result};
+use crate::payload::{Payload, PayloadBuilder};
+use crate::types::{Metric, MetricAlias, MetricValue};
+use crate::validate::Violation;
+use std::collections::HashMap;
+
+/// The metrics declared in a node's NBIRTH, with their latest values.
+#[derive(Debug, Clone, Default)]
+pub struct MetricRegistry {
+    metrics: Vec<Metric>,
+    by_name: HashMap<String, usize>,
+    by_alias: HashMap<MetricAlias, usize>,
+}
+
+impl MetricRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a metric with its alias and initial value.
+    ///
+    /// The data type follows the value. Names and aliases must be unique.
+    pub fn register(
+        &mut self,
+        name: &str,
+        alias: impl Into<MetricAlias>,
+        value: MetricValue,
+    ) -> Result<()> {
+        let alias = alias.into();
+        if self.by_name.contains_key(name) {
+            return Err(Error::InvalidPayload(vec![Violation::DuplicateName(
+                name.to_string(),
+            )]));
+        }
+        if self.by_alias.contains_key(&alias) {
+            return Err(Error::InvalidPayload(vec![Violation::DuplicateAlias(
+                alias,
+            )]));
+        }
+        let index = self.metrics.len();
+        self.by_name.insert(name.to_string(), index);
+        self.by_alias.insert(alias, index);
+        self.metrics.push(Metric {
+            name: Some(name.to_string()),
+            alias: Some(alias),
+            timestamp: None,
+            datatype: value.datatype(),
+            value,
+        });
+        Ok(())
+    }
+
+    /// Returns the declared metrics in registration order.
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// Returns the number of declared metrics.
+    pub fn len(&self) -> usize {
+        self.metrics.len()
+    }
+
+    /// Returns true if no metric is declared.
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    /// Builds an NBIRTH holding `bdSeq`, `Node Control/Rebirth` and every
+    /// declared metric with its latest value.
+    pub fn birth_payload(&self, bd_seq: u64) -> Result<PayloadBuilder> {
+        let mut birth = PayloadBuilder::new()?;
+        birth.add_bd_seq(bd_seq)?.add_node_control_rebirth(false)?;
+        for metric in &self.metrics {
+            birth.add_metric(metric)?;
+        }
+        Ok(birth)
+    }
+
+    /// Checks that every metric of a data payload was declared, and records
+    /// the new values for the next birth.
+    pub fn check_data(&mut self, payload: &Payload) -> Result<()> {
+        let mut violations = Vec::new();
+        let mut updates = Vec::new();
+        for (position, metric) in payload.metrics_ref().enumerate() {
+            let metric = metric?;
+            let index = match (metric.name, metric.alias) {
+                (Some(name), _) => self
+                    .by_name
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| Violation::UndeclaredName(name.to_string())),
+                (None, Some(alias)) => self
+                    .by_alias
+                    .get(&alias)
+                    .copied()
+                    .ok_or(Violation::UndeclaredAlias(alias)),
+                (None, None) => Err(Violation::MissingIdentifier { index: position }),
+            };
+            match index {
+                Ok(index) => updates.push((index, metric.value.to_value())),
+                Err(violation) => violations.push(violation),
+            }
+        }
+        if !violations.is_empty() {
+            return Err(Error::InvalidPayload(violations));
+        }
+        for (index, value) in updates {
+            if value != MetricValue::Null {
+                self.metrics[index].value = value;
+            }
+        }
+        Ok(())
+    }
+}
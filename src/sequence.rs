@@ -0,0 +1,128 @@
+//! Sequence number validation.
+//!
+//! Every NBIRTH, NDATA, DBIRTH and DDATA of an edge node carries a sequence
+//! number in `0..=255` that increments by one per message and wraps from 255
+//! back to 0. NBIRTH resets the sequence to 0. [`SequenceTracker`] applies
+//! these rules so host applications don't have to re-implement them.
+
+use crate::topic::MessageType;
+
+/// Result of checking a received sequence number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceStatus {
+    /// The sequence number is the expected successor.
+    InOrder,
+    /// First sequence number seen since creation or [`reset`](SequenceTracker::reset).
+    ///
+    /// No birth has been observed, so continuity cannot be checked yet.
+    Untracked,
+    /// The NBIRTH sequence number was reset to 0, as required.
+    Reset,
+    /// An NBIRTH carried a non-zero sequence number.
+    InvalidBirth {
+        /// The received sequence number
+        received: u8,
+    },
+    /// The same sequence number was received twice in a row.
+    Duplicate,
+    /// One or more messages were missed or reordered.
+    Gap {
+        /// The sequence number that was expected
+        expected: u8,
+        /// The sequence number that was received
+        received: u8,
+    },
+    /// The sequence number is outside `0..=255`.
+    OutOfRange(u64),
+}
+
+impl SequenceStatus {
+    /// Returns true if the message is acceptable (in order, untracked or a valid reset).
+    pub fn is_ok(&self) -> bool {
+        matches!(
+            self,
+            SequenceStatus::InOrder | SequenceStatus::Untracked | SequenceStatus::Reset
+        )
+    }
+}
+
+/// Tracks the sequence numbers of one edge node.
+///
+/// Feed it every NBIRTH, NDATA, DBIRTH and DDATA from the node (devices share
+/// the node's sequence). After a gap the tracker resynchronizes on the
+/// received value, so a single lost message is reported only once.
+///
+/// # Example
+///
+/// ```
+/// use sparkplug_rs::sequence::{SequenceStatus, SequenceTracker};
+/// use sparkplug_rs::MessageType;
+///
+/// let mut tracker = SequenceTracker::new();
+/// assert_eq!(tracker.check(MessageType::NBirth, 0), SequenceStatus::Reset);
+/// assert_eq!(tracker.check(MessageType::NData, 1), SequenceStatus::InOrder);
+/// assert_eq!(
+///     tracker.check(MessageType::NData, 3),
+///     SequenceStatus::Gap { expected: 2, received: 3 }
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    last: Option<u8>,
+}
+
+impl SequenceTracker {
+    /// Creates a tracker that has not seen any message yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks a received sequence number and records it.
+    ///
+    /// Message types that carry no sequence number (deaths, commands, STATE)
+    /// leave the tracker unchanged and return [`SequenceStatus::InOrder`].
+    pub fn check(&mut self, msg_type: MessageType, seq: u64) -> SequenceStatus {
+        if !(msg_type.is_birth() || msg_type.is_data()) {
+            return SequenceStatus::InOrder;
+        }
+        let Ok(seq) = u8::try_from(seq) else {
+            return SequenceStatus::OutOfRange(seq);
+        };
+
+        if msg_type == MessageType::NBirth {
+            self.last = Some(seq);
+            return if seq == 0 {
+                SequenceStatus::Reset
+            } else {
+                SequenceStatus::InvalidBirth { received: seq }
+            };
+        }
+
+        let status = match self.last {
+            None => SequenceStatus::Untracked,
+            Some(last) if seq == last => SequenceStatus::Duplicate,
+            Some(last) if seq == last.wrapping_add(1) => SequenceStatus::InOrder,
+            Some(last) => SequenceStatus::Gap {
+                expected: last.wrapping_add(1),
+                received: seq,
+            },
+        };
+        self.last = Some(seq);
+        status
+    }
+
+    /// Returns the last recorded sequence number.
+    pub fn last(&self) -> Option<u8> {
+        self.last
+    }
+
+    /// Returns the next expected sequence number, if known.
+    pub fn expected(&self) -> Option<u8> {
+        self.last.map(|last| last.wrapping_add(1))
+    }
+
+    /// Forgets the recorded sequence (e.g. after an NDEATH).
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
@@ -0,0 +1,580 @@
+//! Host-side store of the latest metric values.
+//!
+//! [`MetricStore`] ingests received messages, resolves aliases using the
+//! names declared in NBIRTH/DBIRTH, and keeps the most recent value of every
+//! metric keyed by group, edge node, device and metric name. It can optionally
+//! retain the last N samples of every metric for mini-trends, notify
+//! glob-style watches when matching metrics change, and stream the updates of
+//! single metrics over channels. An NDEATH or DDEATH marks the metrics of the
+//! node or device [`Quality::Stale`] until they are reported again.
+
+use crate::alarm::{AlarmEvent, AlarmSeverity, AlarmTransition};
+use crate::error::Result;
+use crate::payload::Payload;
+use crate::subscriber::Message;
+use crate::topic::ParsedTopic;
+use crate::types::{DataType, MetricAlias, MetricValue};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{mpsc, Arc, Mutex};
+
+/// Identifies a metric within the Sparkplug namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MetricKey {
+    /// Sparkplug group ID
+    pub group_id: String,
+    /// Edge node ID
+    pub edge_node_id: String,
+    /// Device ID (None for node-level metrics)
+    pub device_id: Option<String>,
+    /// Metric name
+    pub name: String,
+}
+
+impl MetricKey {
+    /// Creates a new metric key.
+    pub fn new(
+        group_id: impl Into<String>,
+        edge_node_id: impl Into<String>,
+        device_id: Option<&str>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            group_id: group_id.into(),
+            edge_node_id: edge_node_id.into(),
+            device_id: device_id.map(|d| d.to_string()),
+            name: name.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for MetricKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.device_id {
+            Some(device) => write!(
+                f,
+                "{}/{}/{}/{}",
+                self.group_id, self.edge_node_id, device, self.name
+            ),
+            None => write!(f, "{}/{}/{}", self.group_id, self.edge_node_id, self.name),
+        }
+    }
+}
+
+/// Whether a stored value is current.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quality {
+    /// Reported in a birth or data message since the last death
+    Good,
+    /// The node or device has died since the value was reported
+    Stale,
+}
+
+/// The latest known value of a metric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    /// Metric value
+    pub value: MetricValue,
+    /// Data type declared for the metric
+    pub datatype: DataType,
+    /// Metric timestamp, falling back to the payload timestamp (ms since Unix epoch)
+    pub timestamp: Option<u64>,
+    /// Whether the value is current or its node or device has died
+    pub quality: Quality,
+}
+
+/// Callback invoked when an alarm tag changes state.
+pub type AlarmCallback = Box<dyn Fn(&AlarmEvent) + Send + 'static>;
+
+/// Callback invoked when a watched metric is updated.
+pub type WatchCallback = Box<dyn Fn(&MetricKey, &MetricSample) + Send + 'static>;
+
+/// Handle returned by [`MetricStore::watch`], used to remove the watch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchId(u64);
+
+struct Watch {
+    id: WatchId,
+    pattern: String,
+    callback: Arc<Mutex<WatchCallback>>,
+}
+
+/// Alarm and watch callbacks due after an ingest.
+///
+/// They are collected instead of called so that the owner of the store can
+/// run them after unlocking it.
+#[derive(Default)]
+pub(crate) struct Notifications(Vec<Box<dyn FnOnce()>>);
+
+impl Notifications {
+    /// Calls the collected callbacks in order.
+    pub(crate) fn fire(self) {
+        for notification in self.0 {
+            notification();
+        }
+    }
+}
+
+/// Locks a callback, recovering it if a previous call panicked.
+fn lock<T>(callback: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    callback.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// (group, edge node, device) scope for alias maps.
+type Scope = (String, String, Option<String>);
+
+/// A store of the latest metric values seen by a host application.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::{Message, MetricStore};
+/// use std::sync::{Arc, Mutex};
+///
+/// let store = Arc::new(Mutex::new(MetricStore::new()));
+/// let store_clone = store.clone();
+/// let callback = Box::new(move |msg: Message| {
+///     let _ = store_clone.lock().unwrap().ingest(&msg);
+/// });
+/// // pass `callback` to Subscriber::new, then later:
+/// let soc = store
+///     .lock()
+///     .unwrap()
+///     .get("VPP_R2", "BAL01", Some("BESS"), "DATA/BESS_SOC_ACT")
+///     .cloned();
+/// ```
+#[derive(Default)]
+pub struct MetricStore {
+    values: HashMap<MetricKey, MetricSample>,
+    history: HashMap<MetricKey, VecDeque<MetricSample>>,
+    history_depth: usize,
+    aliases: HashMap<Scope, HashMap<MetricAlias, String>>,
+    alarm_callbacks: Vec<Arc<Mutex<AlarmCallback>>>,
+    watches: Vec<Watch>,
+    next_watch_id: u64,
+    channels: HashMap<MetricKey, Vec<mpsc::Sender<MetricSample>>>,
+}
+
+impl MetricStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty store that retains the last `depth` samples per metric.
+    pub fn with_history(depth: usize) -> Self {
+        Self {
+            history_depth: depth,
+            ..Self::default()
+        }
+    }
+
+    /// Sets how many samples are retained per metric (0 disables history).
+    ///
+    /// Existing histories are truncated to the new depth.
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history_depth = depth;
+        if depth == 0 {
+            self.history.clear();
+        }
+        for samples in self.history.values_mut() {
+            while samples.len() > depth {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Ingests a received message.
+    ///
+    /// Returns the keys of the metrics that were updated, or for NDEATH and
+    /// DDEATH the keys marked stale. STATE messages update nothing.
+    pub fn ingest(&mut self, message: &Message) -> Result<Vec<MetricKey>> {
+        let topic = message.parse_topic()?;
+        let payload = match topic.message_type() {
+            Some(msg_type) if msg_type.is_birth() || msg_type.is_data() => {
+                Some(message.parse_payload()?)
+            }
+            _ => None,
+        };
+        let (updated, notifications) = self.ingest_deferred(&topic, payload.as_ref())?;
+        notifications.fire();
+        Ok(updated)
+    }
+
+    /// Like [`ingest`](Self::ingest) for an already parsed message, but
+    /// returns the alarm and watch callbacks due instead of calling them.
+    /// `payload` is only read for births and data messages.
+    pub(crate) fn ingest_deferred(
+        &mut self,
+        topic: &ParsedTopic,
+        payload: Option<&Payload>,
+    ) -> Result<(Vec<MetricKey>, Notifications)> {
+        match (topic.message_type(), payload) {
+            (Some(msg_type), Some(payload)) if msg_type.is_birth() || msg_type.is_data() => {
+                self.update(topic, payload)
+            }
+            (Some(msg_type), _) if msg_type.is_death() => Ok(self.mark_stale(topic)),
+            _ => Ok((Vec::new(), Notifications::default())),
+        }
+    }
+
+    /// Ingests an already parsed birth, data or death payload.
+    ///
+    /// Births replace the alias map of their node or device; data messages
+    /// resolve alias-only metrics through it. Metrics whose alias is unknown
+    /// are skipped. Deaths mark the metrics of the node or device stale.
+    pub fn ingest_payload(
+        &mut self,
+        topic: &ParsedTopic,
+        payload: &Payload,
+    ) -> Result<Vec<MetricKey>> {
+        let (updated, notifications) = self.update(topic, payload)?;
+        notifications.fire();
+        Ok(updated)
+    }
+
+    fn update(
+        &mut self,
+        topic: &ParsedTopic,
+        payload: &Payload,
+    ) -> Result<(Vec<MetricKey>, Notifications)> {
+        let (Some(msg_type), Some(group_id), Some(edge_node_id)) =
+            (topic.message_type(), topic.group_id(), topic.edge_node_id())
+        else {
+            return Ok((Vec::new(), Notifications::default()));
+        };
+        if msg_type.is_death() {
+            return Ok(self.mark_stale(topic));
+        }
+        let device_id = topic.device_id();
+        let scope: Scope = (
+            group_id.to_string(),
+            edge_node_id.to_string(),
+            device_id.map(|d| d.to_string()),
+        );
+
+        if msg_type.is_birth() {
+            self.aliases.insert(scope.clone(), HashMap::new());
+        }
+
+        let payload_timestamp = payload.timestamp();
+        let mut updated = Vec::new();
+        let mut previous_values = Vec::new();
+        for metric in payload.metrics() {
+            let metric = metric?;
+
+            let name = match (&metric.name, metric.alias) {
+                (Some(name), alias) => {
+                    if let (true, Some(alias)) = (msg_type.is_birth(), alias) {
+                        self.aliases
+                            .entry(scope.clone())
+                            .or_default()
+                            .insert(alias, name.clone());
+                    }
+                    name.clone()
+                }
+                (None, Some(alias)) => match self.aliases.get(&scope).and_then(|m| m.get(&alias)) {
+                    Some(name) => name.clone(),
+                    None => continue,
+                },
+                (None, None) => continue,
+            };
+
+            let key = MetricKey::new(group_id, edge_node_id, device_id, name);
+            let sample = MetricSample {
+                value: metric.value,
+                datatype: metric.datatype,
+                timestamp: metric.timestamp.or(payload_timestamp),
+                quality: Quality::Good,
+            };
+            if self.history_depth > 0 {
+                let samples = self.history.entry(key.clone()).or_default();
+                if samples.len() == self.history_depth {
+                    samples.pop_front();
+                }
+                samples.push_back(sample.clone());
+            }
+            previous_values.push(self.values.insert(key.clone(), sample));
+            updated.push(key);
+        }
+
+        // Checked after the whole payload so alarm metadata sent alongside
+        // the alarm tag is already stored.
+        let mut notifications = Notifications::default();
+        for (key, previous) in updated.iter().zip(&previous_values) {
+            if let Some(event) = self.check_alarm(key, previous.as_ref()) {
+                let callbacks = self.alarm_callbacks.clone();
+                notifications.0.push(Box::new(move || {
+                    for callback in &callbacks {
+                        (lock(callback))(&event);
+                    }
+                }));
+            }
+        }
+        self.notify_watches(&updated, &mut notifications);
+
+        Ok((updated, notifications))
+    }
+
+    /// Marks the metrics of a dead node, its devices, or a dead device stale.
+    fn mark_stale(&mut self, topic: &ParsedTopic) -> (Vec<MetricKey>, Notifications) {
+        let mut notifications = Notifications::default();
+        let (Some(group_id), Some(edge_node_id)) = (topic.group_id(), topic.edge_node_id()) else {
+            return (Vec::new(), notifications);
+        };
+        let device_id = topic.device_id();
+        let mut stale = Vec::new();
+        for (key, sample) in &mut self.values {
+            if sample.quality == Quality::Good
+                && key.group_id == group_id
+                && key.edge_node_id == edge_node_id
+                && (device_id.is_none() || key.device_id.as_deref() == device_id)
+            {
+                sample.quality = Quality::Stale;
+                stale.push(key.clone());
+            }
+        }
+        self.notify_watches(&stale, &mut notifications);
+        (stale, notifications)
+    }
+
+    /// Gets the latest value of a metric.
+    pub fn get(
+        &self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        name: &str,
+    ) -> Option<&MetricSample> {
+        self.values
+            .get(&MetricKey::new(group_id, edge_node_id, device_id, name))
+    }
+
+    /// Gets the retained samples of a metric, oldest first.
+    ///
+    /// Returns `None` if history is disabled or the metric has not been seen.
+    pub fn history(
+        &self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        name: &str,
+    ) -> Option<&VecDeque<MetricSample>> {
+        self.history
+            .get(&MetricKey::new(group_id, edge_node_id, device_id, name))
+    }
+
+    /// Resolves an alias declared in the latest birth of a node or device.
+    pub fn resolve_alias(
+        &self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        alias: impl Into<MetricAlias>,
+    ) -> Option<&str> {
+        let scope: Scope = (
+            group_id.to_string(),
+            edge_node_id.to_string(),
+            device_id.map(|d| d.to_string()),
+        );
+        self.aliases
+            .get(&scope)?
+            .get(&alias.into())
+            .map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over all stored metrics.
+    pub fn iter(&self) -> impl Iterator<Item = (&MetricKey, &MetricSample)> {
+        self.values.iter()
+    }
+
+    /// Returns the number of stored metrics.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if no metrics are stored.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Registers a callback invoked whenever an alarm tag is raised or cleared.
+    ///
+    /// Alarm tags are boolean metrics under [`ALARM_PREFIX`](crate::alarm::ALARM_PREFIX),
+    /// as written by [`Alarm`](crate::alarm::Alarm).
+    pub fn on_alarm(&mut self, callback: AlarmCallback) {
+        self.alarm_callbacks.push(Arc::new(Mutex::new(callback)));
+    }
+
+    /// Registers a callback invoked whenever a metric matching `pattern` is updated.
+    ///
+    /// The pattern is matched against the metric path
+    /// `group/edge_node[/device]/name` (see [`MetricKey`]'s `Display`). `*`
+    /// matches any run of characters, including `/`, and `?` matches a single
+    /// character, so `"VPP_R2/*/DATA/BESS_P_ACT"` fires for that metric on
+    /// every node and device of the group. It also fires when a death marks
+    /// the metric stale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sparkplug_rs::MetricStore;
+    ///
+    /// let mut store = MetricStore::new();
+    /// store.watch(
+    ///     "VPP_R2/*/DATA/BESS_P_ACT",
+    ///     Box::new(|key, sample| println!("{} = {}", key, sample.value)),
+    /// );
+    /// ```
+    pub fn watch(&mut self, pattern: impl Into<String>, callback: WatchCallback) -> WatchId {
+        let id = WatchId(self.next_watch_id);
+        self.next_watch_id += 1;
+        self.watches.push(Watch {
+            id,
+            pattern: pattern.into(),
+            callback: Arc::new(Mutex::new(callback)),
+        });
+        id
+    }
+
+    /// Removes a watch. Returns false if it was already removed.
+    pub fn unwatch(&mut self, id: WatchId) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|w| w.id != id);
+        self.watches.len() != before
+    }
+
+    /// Returns a channel receiving every update of a single metric.
+    ///
+    /// Each sample carries its value and timestamp. The sender is dropped
+    /// from the store once the receiver is gone.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::MetricStore;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let store = Arc::new(Mutex::new(MetricStore::new()));
+    /// let updates = store
+    ///     .lock()
+    ///     .unwrap()
+    ///     .watch_metric("VPP_R2", "BAL01", Some("BESS"), "DATA/BESS_SOC_ACT");
+    /// // feed the store from a subscriber callback, then in the control loop:
+    /// for sample in updates {
+    ///     println!("SOC {} at {:?}", sample.value, sample.timestamp);
+    /// }
+    /// ```
+    pub fn watch_metric(
+        &mut self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        name: &str,
+    ) -> mpsc::Receiver<MetricSample> {
+        let (tx, rx) = mpsc::channel();
+        self.channels
+            .entry(MetricKey::new(group_id, edge_node_id, device_id, name))
+            .or_default()
+            .push(tx);
+        rx
+    }
+
+    fn notify_watches(&mut self, updated: &[MetricKey], notifications: &mut Notifications) {
+        if !self.channels.is_empty() {
+            for key in updated {
+                let (Some(senders), Some(sample)) =
+                    (self.channels.get_mut(key), self.values.get(key))
+                else {
+                    continue;
+                };
+                senders.retain(|tx| tx.send(sample.clone()).is_ok());
+                if senders.is_empty() {
+                    self.channels.remove(key);
+                }
+            }
+        }
+
+        if self.watches.is_empty() {
+            return;
+        }
+        for key in updated {
+            let Some(sample) = self.values.get(key) else {
+                continue;
+            };
+            let path = key.to_string();
+            for watch in &self.watches {
+                if glob_match(&watch.pattern, &path) {
+                    let callback = watch.callback.clone();
+                    let (key, sample) = (key.clone(), sample.clone());
+                    notifications
+                        .0
+                        .push(Box::new(move || (lock(&callback))(&key, &sample)));
+                }
+            }
+        }
+    }
+
+    fn check_alarm(&self, key: &MetricKey, previous: Option<&MetricSample>) -> Option<AlarmEvent> {
+        if self.alarm_callbacks.is_empty() || !crate::alarm::is_alarm_tag(&key.name) {
+            return None;
+        }
+        let sample = self.values.get(key)?;
+        let MetricValue::Boolean(active) = sample.value else {
+            return None;
+        };
+        let was_active = matches!(previous.map(|p| &p.value), Some(MetricValue::Boolean(true)));
+
+        let transition = match (was_active, active) {
+            (false, true) => AlarmTransition::Raised,
+            (true, false) => AlarmTransition::Cleared,
+            _ => return None,
+        };
+
+        let sibling = |suffix: &str| {
+            let mut k = key.clone();
+            k.name = format!("{}/{}", key.name, suffix);
+            self.values.get(&k).map(|s| &s.value)
+        };
+        Some(AlarmEvent {
+            key: key.clone(),
+            transition,
+            severity: sibling(crate::alarm::SEVERITY_SUFFIX)
+                .and_then(|v| v.as_f64())
+                .and_then(|code| AlarmSeverity::from_code(code as i32)),
+            setpoint: sibling(crate::alarm::SETPOINT_SUFFIX).and_then(|v| v.as_f64()),
+            timestamp: sample.timestamp,
+        })
+    }
+}
+
+/// Matches `text` against a pattern where `*` matches any run of characters
+/// and `?` matches exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently covers up to.
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,87 @@
+//! Async message delivery (`async` feature).
+//!
+//! [`Subscriber::messages`](crate::Subscriber::messages) returns a
+//! [`MessageStream`], a `futures_core::Stream` of received messages, so
+//! async applications can consume them with
+//! `while let Some(msg) = stream.next().await` instead of bridging the
+//! callback to a channel. The stream does not depend on a particular runtime.
+
+use crate::subscriber::Message;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Default)]
+struct Queue {
+    messages: VecDeque<Message>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// A stream of the messages received by a [`Subscriber`](crate::Subscriber).
+///
+/// Messages are queued from the client's callback thread without a bound,
+/// so a stream that is not polled keeps every message it receives. The
+/// stream ends when the subscriber is dropped.
+pub struct MessageStream {
+    queue: Arc<Mutex<Queue>>,
+}
+
+/// The subscriber side of a [`MessageStream`].
+pub(crate) struct StreamSender {
+    queue: Arc<Mutex<Queue>>,
+}
+
+pub(crate) fn channel() -> (StreamSender, MessageStream) {
+    let queue = Arc::new(Mutex::new(Queue::default()));
+    (
+        StreamSender {
+            queue: queue.clone(),
+        },
+        MessageStream { queue },
+    )
+}
+
+impl StreamSender {
+    /// Queues a message; returns false once the stream has been dropped.
+    pub(crate) fn send(&self, message: Message) -> bool {
+        if Arc::strong_count(&self.queue) == 1 {
+            return false;
+        }
+        let mut queue = self.queue.lock().unwrap();
+        queue.messages.push_back(message);
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+        true
+    }
+}
+
+impl Drop for StreamSender {
+    fn drop(&mut self) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.closed = true;
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Stream for MessageStream {
+    type Item = Message;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Message>> {
+        let mut queue = self.queue.lock().unwrap();
+        if let Some(message) = queue.messages.pop_front() {
+            return Poll::Ready(Some(message));
+        }
+        if queue.closed {
+            return Poll::Ready(None);
+        }
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -1,13 +1,22 @@
 //! Sparkplug Subscriber for receiving messages.
 
 use crate::error::{Error, Result};
+use crate::filter::MessageFilter;
+use crate::host::StatePayload;
 use crate::payload::Payload;
+use crate::reconnect::ReconnectPolicy;
+use crate::store::{MetricSample, MetricStore};
 use crate::sys;
-use crate::topic::ParsedTopic;
+use crate::topic::{MessageType, ParsedTopic};
+use crate::types::MetricAlias;
+use std::cell::OnceCell;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_void;
 use std::ptr;
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, ThreadId};
 
 /// Message received by a subscriber.
 #[derive(Debug, Clone)]
@@ -44,6 +53,12 @@ impl Message {
     pub fn parse_topic(&self) -> Result<ParsedTopic> {
         ParsedTopic::parse(&self.topic)
     }
+
+    /// Parses the payload of a STATE message (either `ONLINE`/`OFFLINE` or
+    /// the JSON form).
+    pub fn parse_state(&self) -> Result<StatePayload> {
+        StatePayload::parse(&self.payload_data)
+    }
 }
 
 /// Callback function type for receiving messages.
@@ -52,6 +67,47 @@ pub type MessageCallback = Box<dyn Fn(Message) + Send + 'static>;
 /// Callback function type for receiving command messages (NCMD/DCMD).
 pub type CommandCallback = Box<dyn Fn(Message) + Send + 'static>;
 
+/// Hook invoked with the raw topic and payload before anything is decoded or copied.
+///
+/// Return `false` to drop the message before the message callback runs.
+pub type RawMessageHook = Box<dyn Fn(&str, &[u8]) -> bool + Send + 'static>;
+
+/// Callback receiving messages with the topic and payload already parsed.
+pub type ParsedMessageCallback = Box<dyn Fn(ParsedTopic, Payload) + Send + 'static>;
+
+/// Callback receiving messages whose topic or payload failed to parse, with the error.
+pub type ErrorCallback = Box<dyn Fn(&Message, &Error) + Send + 'static>;
+
+/// Callback invoked with `true` after a successful connect and `false` after a disconnect.
+pub type ConnectionCallback = Box<dyn Fn(bool) + Send + 'static>;
+
+/// A stateful message handler, an alternative to the boxed callbacks.
+///
+/// The handler is called with `&mut self`, so it can keep its state in
+/// plain fields instead of `Arc<Mutex<...>>` captures. Only
+/// [`on_message`](Self::on_message) is required. See
+/// [`Subscriber::with_handler`].
+pub trait MessageHandler: Send + 'static {
+    /// Called for every message accepted by the filters, like the message callback.
+    fn on_message(&mut self, message: Message);
+
+    /// Called for command messages (NCMD/DCMD), like the command callback.
+    fn on_command(&mut self, _message: Message) {}
+
+    /// Called for messages that fail to parse; see [`Subscriber::on_error`].
+    fn on_error(&mut self, _message: &Message, _error: &Error) {}
+
+    /// Called when the subscriber connects (`true`) or disconnects (`false`);
+    /// see [`Subscriber::on_connection_change`].
+    fn on_connection_change(&mut self, _connected: bool) {}
+}
+
+/// Handler for one kind of Sparkplug message, called with the parsed topic and payload.
+pub type PayloadHandler = Box<dyn Fn(&ParsedTopic, &Payload) + Send + 'static>;
+
+/// Handler for STATE messages, called with the parsed topic and the decoded state.
+pub type StateHandler = Box<dyn Fn(&ParsedTopic, &StatePayload) + Send + 'static>;
+
 /// Configuration for a Sparkplug Subscriber.
 #[derive(Clone)]
 pub struct SubscriberConfig {
@@ -61,6 +117,10 @@ pub struct SubscriberConfig {
     pub client_id: String,
     /// Sparkplug group ID to subscribe to.
     pub group_id: String,
+    /// Retry policy for [`connect`](Subscriber::connect); `None` makes a single attempt.
+    pub reconnect: Option<ReconnectPolicy>,
+    /// Keep the latest value of every metric, queryable with [`Subscriber::value`].
+    pub store_values: bool,
 }
 
 impl SubscriberConfig {
@@ -74,14 +134,416 @@ impl SubscriberConfig {
             broker_url: broker_url.into(),
             client_id: client_id.into(),
             group_id: group_id.into(),
+            reconnect: None,
+            store_values: false,
+        }
+    }
+}
+
+/// What a paused [`Subscriber`] does with the messages it receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausePolicy {
+    /// Discard messages until [`resume`](Subscriber::resume).
+    Drop,
+    /// Keep up to `capacity` messages and deliver them on
+    /// [`resume`](Subscriber::resume), discarding the oldest when full.
+    Buffer {
+        /// Maximum number of buffered messages
+        capacity: usize,
+    },
+}
+
+/// Messages held back while dispatch is paused.
+struct Paused {
+    policy: PausePolicy,
+    buffer: VecDeque<Message>,
+    dropped: usize,
+}
+
+impl Paused {
+    fn hold(&mut self, message: Message) {
+        match self.policy {
+            PausePolicy::Buffer { capacity } if capacity > 0 => {
+                if self.buffer.len() >= capacity {
+                    self.buffer.pop_front();
+                    self.dropped += 1;
+                }
+                self.buffer.push_back(message);
+            }
+            _ => self.dropped += 1,
+        }
+    }
+}
+
+/// A received message whose topic and payload are parsed at most once, and
+/// only when needed, for the alias cache, store, filters and handlers.
+struct Received {
+    message: Message,
+    topic: Result<ParsedTopic>,
+    payload: OnceCell<Result<Payload>>,
+}
+
+impl Received {
+    fn new(message: Message) -> Self {
+        Self {
+            topic: message.parse_topic(),
+            message,
+            payload: OnceCell::new(),
+        }
+    }
+
+    fn payload(&self) -> std::result::Result<&Payload, &Error> {
+        self.payload
+            .get_or_init(|| self.message.parse_payload())
+            .as_ref()
+    }
+}
+
+/// A callback behind its own lock, so it can be cloned out of
+/// [`SubscriberCallbacks`] and run without holding that lock.
+type Shared<T> = Arc<Mutex<T>>;
+
+fn shared<T>(callback: T) -> Shared<T> {
+    Arc::new(Mutex::new(callback))
+}
+
+/// State shared with the C callbacks through `user_data`.
+struct CallbackState {
+    callbacks: Mutex<SubscriberCallbacks>,
+    /// Held while a message is delivered, so messages reach the callbacks
+    /// one at a time and in order without `callbacks` being locked.
+    delivery: Mutex<()>,
+    reentry: Mutex<Reentry>,
+    aliases: Mutex<AliasCache>,
+    store: Option<Arc<Mutex<MetricStore>>>,
+}
+
+/// Messages received by the delivering thread itself, which happens when a
+/// callback publishes to this subscriber in memory. They are delivered once
+/// the current message is done instead of re-entering the callbacks.
+#[derive(Default)]
+struct Reentry {
+    thread: Option<ThreadId>,
+    pending: VecDeque<Pending>,
+}
+
+enum Pending {
+    Message(Message),
+    Command(Message),
+}
+
+/// The right to deliver messages, held by one thread at a time.
+struct Delivering<'a> {
+    state: &'a CallbackState,
+    _guard: MutexGuard<'a, ()>,
+}
+
+impl Delivering<'_> {
+    /// Delivers the messages queued meanwhile and ends the delivery.
+    fn finish(self) {
+        loop {
+            let next = lock(&self.state.reentry).pending.pop_front();
+            match next {
+                Some(Pending::Message(message)) => {
+                    self.state.receive(message.topic, &message.payload_data)
+                }
+                Some(Pending::Command(message)) => self.state.receive_command(message),
+                None => break,
+            }
+        }
+    }
+}
+
+impl Drop for Delivering<'_> {
+    fn drop(&mut self) {
+        lock(&self.state.reentry).thread = None;
+    }
+}
+
+impl CallbackState {
+    /// Starts a delivery, or returns `None` if this thread is already
+    /// delivering a message.
+    fn begin(&self) -> Option<Delivering<'_>> {
+        let current = thread::current().id();
+        if lock(&self.reentry).thread == Some(current) {
+            return None;
+        }
+        let guard = lock(&self.delivery);
+        lock(&self.reentry).thread = Some(current);
+        Some(Delivering {
+            state: self,
+            _guard: guard,
+        })
+    }
+
+    fn defer(&self, pending: Pending) {
+        lock(&self.reentry).pending.push_back(pending);
+    }
+
+    fn deliver(&self, topic: String, payload: &[u8]) {
+        let Some(delivering) = self.begin() else {
+            self.defer(Pending::Message(Message {
+                topic,
+                payload_data: payload.to_vec(),
+            }));
+            return;
+        };
+        self.receive(topic, payload);
+        delivering.finish();
+    }
+
+    /// Runs a received message through the hook, alias cache, store, pause
+    /// buffer, filters and callbacks.
+    fn receive(&self, topic: String, payload: &[u8]) {
+        let hook = match self.callbacks.lock() {
+            Ok(guard) => guard.raw_hook.clone(),
+            Err(_) => return,
+        };
+        if let Some(hook) = hook {
+            if !(lock(&hook))(&topic, payload) {
+                return;
+            }
+        }
+        let received = Received::new(Message {
+            topic,
+            payload_data: payload.to_vec(),
+        });
+        if let Ok(mut aliases) = self.aliases.lock() {
+            aliases.record(&received);
+        }
+        if let (Some(store), Ok(topic)) = (&self.store, &received.topic) {
+            let payload = topic
+                .message_type()
+                .filter(|msg_type| msg_type.is_birth() || msg_type.is_data())
+                .and_then(|_| received.payload().ok());
+            // Watches run after the store is unlocked, so they can query it.
+            let notifications = match store.lock() {
+                Ok(mut store) => store.ingest_deferred(topic, payload).ok(),
+                Err(_) => None,
+            };
+            if let Some((_, notifications)) = notifications {
+                notifications.fire();
+            }
+        }
+        let delivery = match self.callbacks.lock() {
+            Ok(mut guard) => match &mut guard.paused {
+                Some(paused) => {
+                    paused.hold(received.message);
+                    None
+                }
+                None => guard.accept(received),
+            },
+            Err(_) => None,
+        };
+        if let Some(delivery) = delivery {
+            delivery.run();
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    fn inject(&self, message: Message) {
+        let is_command = message
+            .parse_topic()
+            .ok()
+            .and_then(|topic| topic.message_type())
+            .is_some_and(|msg_type| msg_type.is_command());
+        let command = is_command.then(|| message.clone());
+        self.deliver(message.topic, &message.payload_data);
+        if let Some(command) = command {
+            self.deliver_command(command);
+        }
+    }
+
+    fn deliver_command(&self, message: Message) {
+        let Some(delivering) = self.begin() else {
+            self.defer(Pending::Command(message));
+            return;
+        };
+        self.receive_command(message);
+        delivering.finish();
+    }
+
+    fn receive_command(&self, message: Message) {
+        let callback = match self.callbacks.lock() {
+            Ok(guard) => guard.command_callback.clone(),
+            Err(_) => return,
+        };
+        if let Some(callback) = callback {
+            (lock(&callback))(message);
         }
     }
 }
 
 /// Internal state for subscriber callbacks.
+///
+/// Only the filters run with this locked; callbacks and handlers are cloned
+/// out first, so they can replace callbacks or query the subscriber.
 struct SubscriberCallbacks {
-    message_callback: Option<MessageCallback>,
-    command_callback: Option<CommandCallback>,
+    message_callback: Option<Shared<MessageCallback>>,
+    command_callback: Option<Shared<CommandCallback>>,
+    filters: Vec<MessageFilter>,
+    raw_hook: Option<Shared<RawMessageHook>>,
+    handlers: TypedHandlers,
+    paused: Option<Paused>,
+    connection_callback: Option<Shared<ConnectionCallback>>,
+    #[cfg(feature = "async")]
+    streams: Vec<crate::stream::StreamSender>,
+}
+
+impl SubscriberCallbacks {
+    /// Runs a message through the filters and streams and returns the
+    /// callbacks it is to be delivered to.
+    fn accept(&mut self, received: Received) -> Option<Delivery> {
+        if !self.filters.is_empty() {
+            let Ok(topic) = &received.topic else {
+                return None;
+            };
+            if !crate::filter::accepts_parsed(&self.filters, topic, || received.payload().ok()) {
+                return None;
+            }
+        }
+        #[cfg(feature = "async")]
+        self.streams
+            .retain(|stream| stream.send(received.message.clone()));
+        Some(Delivery {
+            received,
+            handlers: self.handlers.clone(),
+            callback: self.message_callback.clone(),
+        })
+    }
+}
+
+/// A message and the callbacks it goes to, taken out of [`SubscriberCallbacks`].
+struct Delivery {
+    received: Received,
+    handlers: TypedHandlers,
+    callback: Option<Shared<MessageCallback>>,
+}
+
+impl Delivery {
+    fn run(mut self) {
+        self.handlers.dispatch(&mut self.received);
+        if let Some(callback) = self.callback {
+            (lock(&callback))(self.received.message);
+        }
+    }
+}
+
+/// Alias maps declared in the latest NBIRTH/DBIRTH, keyed by (group, edge
+/// node, device).
+#[derive(Default)]
+struct AliasCache {
+    maps: HashMap<(String, String, Option<String>), HashMap<MetricAlias, String>>,
+}
+
+impl AliasCache {
+    /// Replaces the alias map of the node or device if `received` is a
+    /// birth, and drops it if `received` is a death.
+    fn record(&mut self, received: &Received) {
+        let Ok(topic) = &received.topic else {
+            return;
+        };
+        let (Some(msg_type), Some(group_id), Some(edge_node_id)) =
+            (topic.message_type(), topic.group_id(), topic.edge_node_id())
+        else {
+            return;
+        };
+        let device_id = topic.device_id();
+        if msg_type.is_death() {
+            // An NDEATH also ends the births of the node's devices.
+            self.maps.retain(|(group, node, device), _| {
+                group != group_id
+                    || node != edge_node_id
+                    || (device_id.is_some() && device.as_deref() != device_id)
+            });
+            return;
+        }
+        if !msg_type.is_birth() {
+            return;
+        }
+        if let Ok(Ok(aliases)) = received.payload().map(Payload::alias_map) {
+            self.maps.insert(
+                (
+                    group_id.to_string(),
+                    edge_node_id.to_string(),
+                    device_id.map(str::to_string),
+                ),
+                aliases,
+            );
+        }
+    }
+}
+
+/// Handlers registered with the `on_*` methods.
+#[derive(Default, Clone)]
+struct TypedHandlers {
+    nbirth: Option<Shared<PayloadHandler>>,
+    ndata: Option<Shared<PayloadHandler>>,
+    dbirth: Option<Shared<PayloadHandler>>,
+    ddata: Option<Shared<PayloadHandler>>,
+    death: Option<Shared<PayloadHandler>>,
+    state: Option<Shared<StateHandler>>,
+    parsed: Option<Shared<ParsedMessageCallback>>,
+    error: Option<Shared<ErrorCallback>>,
+}
+
+impl TypedHandlers {
+    /// Calls the handler registered for the message's type and the parsed
+    /// callback, sharing the payload parsed on receipt and parsing it only
+    /// if one of them is set.
+    fn dispatch(&self, received: &mut Received) {
+        let topic = match &received.topic {
+            Ok(topic) => topic.clone(),
+            Err(err) => {
+                self.report(&received.message, err);
+                return;
+            }
+        };
+        let handler = match topic.message_type() {
+            Some(MessageType::NBirth) => self.nbirth.as_ref(),
+            Some(MessageType::NData) => self.ndata.as_ref(),
+            Some(MessageType::DBirth) => self.dbirth.as_ref(),
+            Some(MessageType::DData) => self.ddata.as_ref(),
+            Some(MessageType::NDeath | MessageType::DDeath) => self.death.as_ref(),
+            Some(MessageType::State) | None => {
+                if self.state.is_none() && self.error.is_none() {
+                    return;
+                }
+                match received.message.parse_state() {
+                    Ok(state) => {
+                        if let Some(handler) = &self.state {
+                            (lock(handler))(&topic, &state);
+                        }
+                    }
+                    Err(err) => self.report(&received.message, &err),
+                }
+                return;
+            }
+            Some(MessageType::NCmd | MessageType::DCmd) => None,
+        };
+        if handler.is_none() && self.parsed.is_none() && self.error.is_none() {
+            return;
+        }
+        if let Err(err) = received.payload() {
+            self.report(&received.message, err);
+            return;
+        }
+        let Some(Ok(payload)) = received.payload.take() else {
+            return;
+        };
+        if let Some(handler) = handler {
+            (lock(handler))(&topic, &payload);
+        }
+        if let Some(callback) = &self.parsed {
+            (lock(callback))(topic, payload);
+        }
+    }
+
+    fn report(&self, message: &Message, err: &Error) {
+        if let Some(callback) = &self.error {
+            (lock(callback))(message, err);
+        }
+    }
 }
 
 /// A Sparkplug Subscriber for receiving messages.
@@ -124,23 +586,39 @@ struct SubscriberCallbacks {
 /// ```
 pub struct Subscriber {
     inner: *mut sys::sparkplug_subscriber_t,
-    callbacks: Arc<Mutex<SubscriberCallbacks>>,
+    state: Arc<CallbackState>,
+    reconnect: Option<ReconnectPolicy>,
 }
 
 impl Subscriber {
     /// Creates a new Subscriber with the given configuration and message callback.
     pub fn new(config: SubscriberConfig, message_callback: MessageCallback) -> Result<Self> {
-        let callbacks = Arc::new(Mutex::new(SubscriberCallbacks {
-            message_callback: Some(message_callback),
-            command_callback: None,
-        }));
+        let state = Arc::new(CallbackState {
+            callbacks: Mutex::new(SubscriberCallbacks {
+                message_callback: Some(shared(message_callback)),
+                command_callback: None,
+                filters: Vec::new(),
+                raw_hook: None,
+                handlers: TypedHandlers::default(),
+                paused: None,
+                connection_callback: None,
+                #[cfg(feature = "async")]
+                streams: Vec::new(),
+            }),
+            delivery: Mutex::new(()),
+            reentry: Mutex::new(Reentry::default()),
+            aliases: Mutex::new(AliasCache::default()),
+            store: config
+                .store_values
+                .then(|| Arc::new(Mutex::new(MetricStore::new()))),
+        });
 
         let broker_url = CString::new(config.broker_url)?;
         let client_id = CString::new(config.client_id)?;
         let group_id = CString::new(config.group_id)?;
 
-        // Create a raw pointer to the callbacks Arc to pass as user_data
-        let user_data = Arc::into_raw(Arc::clone(&callbacks)) as *mut c_void;
+        // Create a raw pointer to the callback state Arc to pass as user_data
+        let user_data = Arc::into_raw(Arc::clone(&state)) as *mut c_void;
 
         let inner = unsafe {
             sys::sparkplug_subscriber_create(
@@ -155,7 +633,7 @@ impl Subscriber {
         if inner.is_null() {
             // Clean up the Arc we created for user_data
             unsafe {
-                Arc::from_raw(user_data as *const Mutex<SubscriberCallbacks>);
+                Arc::from_raw(user_data as *const CallbackState);
             }
             return Err(Error::CreateFailed {
                 component: "Subscriber",
@@ -163,7 +641,175 @@ impl Subscriber {
             });
         }
 
-        Ok(Self { inner, callbacks })
+        Ok(Self {
+            inner,
+            state,
+            reconnect: config.reconnect,
+        })
+    }
+
+    /// Creates a Subscriber that delivers messages to a channel instead of a callback.
+    ///
+    /// Messages accepted by the filters are sent to the returned
+    /// [`Receiver`], so they can be processed on the application's own
+    /// thread rather than inside the client's callback. The channel is
+    /// unbounded; the receiver reports disconnection once the subscriber is
+    /// dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Subscriber, SubscriberConfig};
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let (mut subscriber, messages) = Subscriber::with_channel(config)?;
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    ///
+    /// for msg in messages {
+    ///     println!("{}", msg.topic);
+    /// }
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn with_channel(config: SubscriberConfig) -> Result<(Self, Receiver<Message>)> {
+        let (sender, receiver) = mpsc::channel();
+        let subscriber = Self::new(
+            config,
+            Box::new(move |message: Message| {
+                let _ = sender.send(message);
+            }),
+        )?;
+        Ok((subscriber, receiver))
+    }
+
+    /// Creates a Subscriber that delivers to a [`MessageHandler`].
+    ///
+    /// The handler receives messages, commands, parse errors and connection
+    /// changes. It is called on the client's callback thread behind a mutex,
+    /// so its methods can take `&mut self`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, MessageHandler, Subscriber, SubscriberConfig};
+    ///
+    /// #[derive(Default)]
+    /// struct Counter {
+    ///     messages: u64,
+    /// }
+    ///
+    /// impl MessageHandler for Counter {
+    ///     fn on_message(&mut self, msg: Message) {
+    ///         self.messages += 1;
+    ///         println!("#{}: {}", self.messages, msg.topic);
+    ///     }
+    ///
+    ///     fn on_connection_change(&mut self, connected: bool) {
+    ///         println!("connected: {}", connected);
+    ///     }
+    /// }
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let mut subscriber = Subscriber::with_handler(config, Counter::default())?;
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn with_handler(config: SubscriberConfig, handler: impl MessageHandler) -> Result<Self> {
+        let handler = Arc::new(Mutex::new(handler));
+
+        let h = handler.clone();
+        let mut subscriber = Self::new(
+            config,
+            Box::new(move |message: Message| lock(&h).on_message(message)),
+        )?;
+        let h = handler.clone();
+        subscriber.set_command_callback(Box::new(move |message: Message| {
+            lock(&h).on_command(message)
+        }))?;
+        let h = handler.clone();
+        subscriber.on_error(Box::new(move |message: &Message, error: &Error| {
+            lock(&h).on_error(message, error)
+        }));
+        subscriber.on_connection_change(Box::new(move |connected| {
+            lock(&handler).on_connection_change(connected)
+        }));
+        Ok(subscriber)
+    }
+
+    /// Creates a Subscriber whose callback receives the parsed topic and payload.
+    ///
+    /// Each message is parsed once, together with the `on_*` handlers,
+    /// instead of in every consumer. STATE messages and messages whose topic
+    /// or payload cannot be parsed are not passed to the callback; use
+    /// [`on_state`](Self::on_state) for STATE.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Payload, ParsedTopic, Subscriber, SubscriberConfig};
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let mut subscriber = Subscriber::with_parsed_callback(
+    ///     config,
+    ///     Box::new(|topic: ParsedTopic, payload: Payload| {
+    ///         println!("{}: {} metrics", topic, payload.metric_count());
+    ///     }),
+    /// )?;
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn with_parsed_callback(
+        config: SubscriberConfig,
+        callback: ParsedMessageCallback,
+    ) -> Result<Self> {
+        let subscriber = Self::new(config, Box::new(|_: Message| {}))?;
+        if let Ok(mut guard) = subscriber.state.callbacks.lock() {
+            guard.message_callback = None;
+            guard.handlers.parsed = Some(shared(callback));
+        }
+        Ok(subscriber)
+    }
+
+    /// Delivers `message` as if it had been received from the broker.
+    ///
+    /// The message takes the same path as one from the C client: the raw
+    /// hook, alias cache, value store, pause buffer, filters, `on_*`
+    /// handlers and message callback, and NCMD/DCMD also reach the command
+    /// callback. It is delivered whether or not the subscriber is connected
+    /// or subscribed to its topic.
+    #[cfg(feature = "test-util")]
+    pub fn inject(&self, message: Message) {
+        self.state.inject(message);
+    }
+
+    /// Returns a callback that [`inject`](Self::inject)s every message it receives.
+    ///
+    /// Used as the sink of [`Publisher::dry_run`](crate::Publisher::dry_run),
+    /// it routes the publisher's messages to this subscriber in memory, on
+    /// the publishing thread, so birth sequencing, command handling and
+    /// host-side tracking can be tested without a broker. A message
+    /// published from one of this subscriber's own callbacks is delivered
+    /// after that callback returns.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, Publisher, PublisherConfig, Subscriber, SubscriberConfig};
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let host = Subscriber::new(config, Box::new(|msg: Message| println!("{}", msg.topic)))?;
+    ///
+    /// let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    /// let mut edge = Publisher::dry_run(config, host.sink())?;
+    /// edge.connect()?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn sink(&self) -> MessageCallback {
+        let state = Arc::clone(&self.state);
+        Box::new(move |message: Message| state.inject(message))
     }
 
     /// Internal wrapper for the message callback.
@@ -178,7 +824,7 @@ impl Subscriber {
         }
 
         // Reconstruct the Arc (but don't drop it - just borrow)
-        let callbacks = unsafe { &*(user_data as *const Mutex<SubscriberCallbacks>) };
+        let state = unsafe { &*(user_data as *const CallbackState) };
 
         let topic_str = if topic.is_null() {
             String::new()
@@ -186,22 +832,13 @@ impl Subscriber {
             unsafe { CStr::from_ptr(topic).to_string_lossy().into_owned() }
         };
 
-        let payload_vec = if payload_data.is_null() || payload_len == 0 {
-            Vec::new()
+        let payload = if payload_data.is_null() || payload_len == 0 {
+            &[][..]
         } else {
-            unsafe { std::slice::from_raw_parts(payload_data, payload_len).to_vec() }
-        };
-
-        let message = Message {
-            topic: topic_str,
-            payload_data: payload_vec,
+            unsafe { std::slice::from_raw_parts(payload_data, payload_len) }
         };
 
-        if let Ok(guard) = callbacks.lock() {
-            if let Some(ref callback) = guard.message_callback {
-                callback(message);
-            }
-        }
+        state.deliver(topic_str, payload);
     }
 
     /// Internal wrapper for the command callback.
@@ -215,7 +852,7 @@ impl Subscriber {
             return;
         }
 
-        let callbacks = unsafe { &*(user_data as *const Mutex<SubscriberCallbacks>) };
+        let state = unsafe { &*(user_data as *const CallbackState) };
 
         let topic_str = if topic.is_null() {
             String::new()
@@ -229,15 +866,30 @@ impl Subscriber {
             unsafe { std::slice::from_raw_parts(payload_data, payload_len).to_vec() }
         };
 
-        let message = Message {
+        state.deliver_command(Message {
             topic: topic_str,
             payload_data: payload_vec,
-        };
+        });
+    }
 
-        if let Ok(guard) = callbacks.lock() {
-            if let Some(ref callback) = guard.command_callback {
-                callback(message);
-            }
+    /// Replaces the message callback.
+    ///
+    /// Takes effect from the next received message; a callback that is
+    /// running keeps the previous one until it returns. Callbacks run without
+    /// the subscriber's lock held, so this can be called from any of them,
+    /// including the message callback itself.
+    pub fn set_message_callback(&mut self, callback: MessageCallback) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.message_callback = Some(shared(callback));
+        }
+    }
+
+    /// Removes the message callback.
+    ///
+    /// The filters, `on_*` handlers and streams keep receiving messages.
+    pub fn clear_message_callback(&mut self) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.message_callback = None;
         }
     }
 
@@ -245,11 +897,11 @@ impl Subscriber {
     ///
     /// This callback is invoked in addition to the general message callback.
     pub fn set_command_callback(&mut self, callback: CommandCallback) -> Result<()> {
-        if let Ok(mut guard) = self.callbacks.lock() {
-            guard.command_callback = Some(callback);
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.command_callback = Some(shared(callback));
         }
 
-        let user_data = Arc::as_ptr(&self.callbacks) as *mut c_void;
+        let user_data = Arc::as_ptr(&self.state) as *mut c_void;
         unsafe {
             sys::sparkplug_subscriber_set_command_callback(
                 self.inner,
@@ -262,7 +914,7 @@ impl Subscriber {
 
     /// Removes the command callback.
     pub fn clear_command_callback(&mut self) {
-        if let Ok(mut guard) = self.callbacks.lock() {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
             guard.command_callback = None;
         }
 
@@ -271,8 +923,375 @@ impl Subscriber {
         }
     }
 
+    /// Sets a hook that sees every message before it is copied or decoded.
+    ///
+    /// The hook runs on the client's callback thread ahead of the filters and
+    /// the message callback, with borrowed topic and payload bytes. Use it
+    /// for raw logging, mirroring or dropping traffic cheaply; returning
+    /// `false` discards the message.
+    pub fn set_raw_hook(&mut self, hook: RawMessageHook) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.raw_hook = Some(shared(hook));
+        }
+    }
+
+    /// Removes the raw message hook.
+    pub fn clear_raw_hook(&mut self) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.raw_hook = None;
+        }
+    }
+
+    /// Stops delivering messages until [`resume`](Self::resume).
+    ///
+    /// While paused, received messages are buffered or dropped according to
+    /// `policy` instead of reaching the filters, the `on_*` handlers, the
+    /// streams and the message callback. The raw hook and the alias cache
+    /// still see every message, and the command callback is not paused.
+    /// Pausing a paused subscriber only changes the policy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, PausePolicy, Subscriber, SubscriberConfig};
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let mut subscriber = Subscriber::new(config, Box::new(|_: Message| {}))?;
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    ///
+    /// subscriber.pause(PausePolicy::Buffer { capacity: 10_000 });
+    /// // ... reconfigure during failover ...
+    /// let dropped = subscriber.resume();
+    /// println!("{} messages dropped while paused", dropped);
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn pause(&mut self, policy: PausePolicy) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            match &mut guard.paused {
+                Some(paused) => paused.policy = policy,
+                None => {
+                    guard.paused = Some(Paused {
+                        policy,
+                        buffer: VecDeque::new(),
+                        dropped: 0,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Resumes delivery, first delivering the buffered messages in order.
+    ///
+    /// Buffered messages are delivered on the calling thread; messages
+    /// arriving meanwhile wait until the buffer has been replayed. Returns
+    /// the number of messages dropped while paused.
+    ///
+    /// Called from a subscriber callback, it returns 0 and leaves delivery
+    /// paused, since the replay would have to wait for the message being
+    /// delivered.
+    pub fn resume(&mut self) -> usize {
+        let Some(delivering) = self.state.begin() else {
+            return 0;
+        };
+        let paused = match self.state.callbacks.lock() {
+            Ok(mut guard) => guard.paused.take(),
+            Err(_) => None,
+        };
+        let Some(mut paused) = paused else {
+            return 0;
+        };
+        while let Some(message) = paused.buffer.pop_front() {
+            let delivery = match self.state.callbacks.lock() {
+                Ok(mut guard) => guard.accept(Received::new(message)),
+                Err(_) => None,
+            };
+            if let Some(delivery) = delivery {
+                delivery.run();
+            }
+        }
+        delivering.finish();
+        paused.dropped
+    }
+
+    /// Returns true between [`pause`](Self::pause) and [`resume`](Self::resume).
+    pub fn is_paused(&self) -> bool {
+        self.state
+            .callbacks
+            .lock()
+            .map(|guard| guard.paused.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Sets the handler for NBIRTH messages.
+    ///
+    /// The `on_*` handlers run on the client's callback thread for messages
+    /// accepted by the filters, before the message callback. Messages whose
+    /// payload cannot be parsed are not passed to them. Setting a handler
+    /// replaces the previous one for that message type.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, Payload, ParsedTopic, Subscriber, SubscriberConfig};
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let mut subscriber = Subscriber::new(config, Box::new(|_: Message| {}))?;
+    /// subscriber.on_nbirth(Box::new(|topic: &ParsedTopic, payload: &Payload| {
+    ///     println!("{} born with {} metrics", topic, payload.metric_count());
+    /// }));
+    /// subscriber.on_death(Box::new(|topic: &ParsedTopic, _: &Payload| {
+    ///     println!("{} died", topic);
+    /// }));
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn on_nbirth(&mut self, handler: PayloadHandler) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.nbirth = Some(shared(handler));
+        }
+    }
+
+    /// Sets the handler for NDATA messages; see [`on_nbirth`](Self::on_nbirth).
+    pub fn on_ndata(&mut self, handler: PayloadHandler) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.ndata = Some(shared(handler));
+        }
+    }
+
+    /// Sets the handler for DBIRTH messages; see [`on_nbirth`](Self::on_nbirth).
+    pub fn on_dbirth(&mut self, handler: PayloadHandler) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.dbirth = Some(shared(handler));
+        }
+    }
+
+    /// Sets the handler for DDATA messages; see [`on_nbirth`](Self::on_nbirth).
+    pub fn on_ddata(&mut self, handler: PayloadHandler) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.ddata = Some(shared(handler));
+        }
+    }
+
+    /// Sets the handler for NDEATH and DDEATH messages; see [`on_nbirth`](Self::on_nbirth).
+    pub fn on_death(&mut self, handler: PayloadHandler) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.death = Some(shared(handler));
+        }
+    }
+
+    /// Sets the handler for STATE messages.
+    ///
+    /// The handler gets the decoded [`StatePayload`]; payloads that are
+    /// neither format are skipped. See [`on_nbirth`](Self::on_nbirth).
+    pub fn on_state(&mut self, handler: StateHandler) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.state = Some(shared(handler));
+        }
+    }
+
+    /// Sets a callback for messages that fail to parse.
+    ///
+    /// The callback receives the raw message and the error for every message
+    /// accepted by the filters whose topic, Sparkplug payload or STATE
+    /// payload cannot be decoded, so misbehaving publishers can be detected.
+    /// Such messages are still passed to the message callback.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Error, Message, Subscriber, SubscriberConfig};
+    ///
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let mut subscriber = Subscriber::new(config, Box::new(|_: Message| {}))?;
+    /// subscriber.on_error(Box::new(|msg: &Message, err: &Error| {
+    ///     eprintln!("{} ({} bytes): {}", msg.topic, msg.payload_data.len(), err);
+    /// }));
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn on_error(&mut self, callback: ErrorCallback) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.error = Some(shared(callback));
+        }
+    }
+
+    /// Removes the parse error callback.
+    pub fn clear_on_error(&mut self) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.handlers.error = None;
+        }
+    }
+
+    /// Sets a callback for connection changes.
+    ///
+    /// It is called with `true` after [`connect`](Self::connect) succeeds and
+    /// with `false` after [`disconnect`](Self::disconnect). The C client does
+    /// not report connection losses, so a dropped connection is not signalled.
+    pub fn on_connection_change(&mut self, callback: ConnectionCallback) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.connection_callback = Some(shared(callback));
+        }
+    }
+
+    fn notify_connection(&self, connected: bool) {
+        let callback = match self.state.callbacks.lock() {
+            Ok(guard) => guard.connection_callback.clone(),
+            Err(_) => return,
+        };
+        if let Some(callback) = callback {
+            (lock(&callback))(connected);
+        }
+    }
+
+    /// Returns a stream of the received messages (`async` feature).
+    ///
+    /// The stream sees the messages accepted by the filters, in addition to
+    /// the message callback; each call returns a new stream that receives
+    /// every later message. It ends when the subscriber is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use sparkplug_rs::{Message, Subscriber, SubscriberConfig};
+    ///
+    /// # async fn run() -> Result<(), sparkplug_rs::Error> {
+    /// let config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    /// let mut subscriber = Subscriber::new(config, Box::new(|_: Message| {}))?;
+    /// let mut messages = subscriber.messages();
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    ///
+    /// while let Some(msg) = messages.next().await {
+    ///     println!("{}", msg.topic);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn messages(&mut self) -> crate::stream::MessageStream {
+        let (sender, stream) = crate::stream::channel();
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.streams.push(sender);
+        }
+        stream
+    }
+
+    /// Resolves an alias declared in the latest NBIRTH or DBIRTH received
+    /// from a node or device.
+    ///
+    /// The subscriber records the alias maps of every birth it receives,
+    /// including births dropped by the filters, so alias-only NDATA/DDATA
+    /// metrics can be named. Returns `None` until a birth declaring the
+    /// alias has been received, and again after the node's NDEATH or the
+    /// device's DDEATH.
+    pub fn resolve_alias(
+        &self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        alias: impl Into<MetricAlias>,
+    ) -> Option<String> {
+        let aliases = self.state.aliases.lock().ok()?;
+        aliases
+            .maps
+            .get(&(
+                group_id.to_string(),
+                edge_node_id.to_string(),
+                device_id.map(str::to_string),
+            ))?
+            .get(&alias.into())
+            .cloned()
+    }
+
+    /// Returns the latest value of a metric (`store_values` must be enabled).
+    ///
+    /// Like the alias cache, the store is updated for every received birth
+    /// and data message, including those dropped by the filters or held
+    /// while paused. Alias-only metrics are resolved through the births.
+    /// After an NDEATH or DDEATH the last values of the node or device are
+    /// kept with [`Quality::Stale`](crate::Quality::Stale) until reported
+    /// again. A metric the sender marked null is stored as
+    /// [`MetricValue::Null`](crate::MetricValue::Null).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sparkplug_rs::{Message, Subscriber, SubscriberConfig};
+    ///
+    /// let mut config = SubscriberConfig::new("tcp://localhost:1883", "host", "VPP_R2");
+    /// config.store_values = true;
+    /// let mut subscriber = Subscriber::new(config, Box::new(|_: Message| {}))?;
+    /// subscriber.connect()?;
+    /// subscriber.subscribe_all()?;
+    ///
+    /// if let Some(soc) = subscriber.value("VPP_R2", "BAL01", Some("BESS"), "DATA/BESS_SOC_ACT") {
+    ///     println!("SOC {:?} at {:?}", soc.value, soc.timestamp);
+    /// }
+    /// # Ok::<(), sparkplug_rs::Error>(())
+    /// ```
+    pub fn value(
+        &self,
+        group_id: &str,
+        edge_node_id: &str,
+        device_id: Option<&str>,
+        name: &str,
+    ) -> Option<MetricSample> {
+        let store = self.state.store.as_ref()?.lock().ok()?;
+        store.get(group_id, edge_node_id, device_id, name).cloned()
+    }
+
+    /// Returns the value store, if `store_values` is enabled.
+    ///
+    /// The [`MetricStore`] gives access to every stored metric, history and
+    /// watches. Its watch and alarm callbacks run on the client's callback
+    /// thread after the store has been unlocked, so they may lock it or call
+    /// [`value`](Self::value).
+    pub fn value_store(&self) -> Option<Arc<Mutex<MetricStore>>> {
+        self.state.store.clone()
+    }
+
+    /// Returns the underlying `sparkplug_subscriber_t` handle.
+    ///
+    /// This is an escape hatch for calling `sparkplug_c` functions that are
+    /// not wrapped yet. The handle stays owned by this `Subscriber`: do not
+    /// destroy it, and do not replace the message or command callbacks
+    /// through it, as the wrapper relies on them.
+    pub fn as_raw_ptr(&self) -> *mut c_void {
+        self.inner as *mut c_void
+    }
+
+    /// Adds a filter evaluated before the message callback.
+    ///
+    /// A message is delivered only if every registered filter accepts it.
+    /// Command callbacks are not filtered.
+    pub fn add_filter(&mut self, filter: MessageFilter) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.filters.push(filter);
+        }
+    }
+
+    /// Removes all message filters.
+    pub fn clear_filters(&mut self) {
+        if let Ok(mut guard) = self.state.callbacks.lock() {
+            guard.filters.clear();
+        }
+    }
+
     /// Connects to the MQTT broker.
+    ///
+    /// Failed attempts are retried according to the configured [`ReconnectPolicy`].
     pub fn connect(&mut self) -> Result<()> {
+        match &self.reconnect {
+            Some(policy) => policy.retry(|| self.connect_once())?,
+            None => self.connect_once()?,
+        }
+        self.notify_connection(true);
+        Ok(())
+    }
+
+    fn connect_once(&self) -> Result<()> {
         let ret = unsafe { sys::sparkplug_subscriber_connect(self.inner) };
         if ret != 0 {
             return Err(Error::ConnectionFailed(
@@ -290,6 +1309,7 @@ impl Subscriber {
                 operation: "disconnect",
             });
         }
+        self.notify_connection(false);
         Ok(())
     }
 
@@ -338,6 +1358,12 @@ impl Subscriber {
     }
 }
 
+/// Locks a callback or the handler of [`Subscriber::with_handler`],
+/// recovering it if a previous call panicked.
+fn lock<T>(callback: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    callback.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 impl Drop for Subscriber {
     fn drop(&mut self) {
         if !self.inner.is_null() {
@@ -348,11 +1374,11 @@ impl Drop for Subscriber {
 
         // Clean up the Arc we created for callbacks
         // We need to reconstruct and drop it
-        let user_data = Arc::as_ptr(&self.callbacks) as *mut c_void;
+        let user_data = Arc::as_ptr(&self.state) as *mut c_void;
         if !user_data.is_null() {
             unsafe {
                 // This reconstructs the Arc and then drops it, decrementing the ref count
-                Arc::from_raw(user_data as *const Mutex<SubscriberCallbacks>);
+                Arc::from_raw(user_data as *const CallbackState);
             }
         }
     }
@@ -0,0 +1,25 @@
+//! Timestamp conversion helpers.
+//!
+//! Sparkplug timestamps are UTC milliseconds since the Unix epoch. These
+//! helpers convert between that representation and [`SystemTime`].
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Converts a [`SystemTime`] to Sparkplug milliseconds since the Unix epoch.
+///
+/// Times before the epoch map to 0.
+pub fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Converts Sparkplug milliseconds since the Unix epoch to a [`SystemTime`].
+pub fn from_millis(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
+
+/// Returns the current time in Sparkplug milliseconds since the Unix epoch.
+pub fn now_millis() -> u64 {
+    to_millis(SystemTime::now())
+}
@@ -0,0 +1,195 @@
+//! Typed metric structs.
+//!
+//! [`SparkplugMetrics`] maps the fields of a Rust struct to Sparkplug metrics.
+//! It is normally implemented with `#[derive(SparkplugMetrics)]` (enable the
+//! `derive` feature), which relies on [`MetricField`] for the per-type
+//! conversions.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use sparkplug_rs::SparkplugMetrics;
+//!
+//! #[derive(SparkplugMetrics, Clone, PartialEq)]
+//! struct Pump {
+//!     #[sparkplug(name = "DATA/Speed", alias = 1)]
+//!     speed: f64,
+//!     #[sparkplug(alias = 2, readonly)]
+//!     running: bool,
+//!     #[sparkplug(skip)]
+//!     internal_counter: u32,
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::payload::{Payload, PayloadBuilder};
+use crate::types::MetricValue;
+
+/// A struct whose fields map to Sparkplug metrics.
+pub trait SparkplugMetrics {
+    /// Builds a birth payload containing every field (with aliases, if declared).
+    fn to_birth_payload(&self) -> Result<PayloadBuilder>;
+
+    /// Builds a data payload containing only the fields that differ from `previous`.
+    ///
+    /// Returns `None` when nothing changed.
+    fn to_data_payload(&self, previous: &Self) -> Result<Option<PayloadBuilder>>;
+
+    /// Applies the metrics of a command payload (NCMD/DCMD) to matching fields.
+    ///
+    /// Metrics are matched by name or alias; read-only fields and values of
+    /// the wrong type are ignored. Returns the number of fields updated.
+    fn apply_command(&mut self, payload: &Payload) -> Result<usize>;
+}
+
+/// Conversion between a Rust field type and a Sparkplug metric.
+pub trait MetricField: Sized + PartialEq {
+    /// Adds the value to a birth payload, by name and optionally with an alias.
+    fn add_to_birth(
+        &self,
+        builder: &mut PayloadBuilder,
+        name: &str,
+        alias: Option<u64>,
+    ) -> Result<()>;
+
+    /// Adds the value to a data payload, by alias when one is declared.
+    fn add_to_data(
+        &self,
+        builder: &mut PayloadBuilder,
+        name: &str,
+        alias: Option<u64>,
+    ) -> Result<()>;
+
+    /// Extracts a value of this type from a received metric value.
+    fn from_metric_value(value: &MetricValue) -> Option<Self>;
+}
+
+macro_rules! impl_metric_field_with_alias {
+    ($($ty:ty, $variant:ident, $add:ident, $with_alias:ident, $by_alias:ident;)*) => {$(
+        impl MetricField for $ty {
+            fn add_to_birth(
+                &self,
+                builder: &mut PayloadBuilder,
+                name: &str,
+                alias: Option<u64>,
+            ) -> Result<()> {
+                match alias {
+                    Some(alias) => builder.$with_alias(name, alias, *self)?,
+                    None => builder.$add(name, *self)?,
+                };
+                Ok(())
+            }
+
+            fn add_to_data(
+                &self,
+                builder: &mut PayloadBuilder,
+                name: &str,
+                alias: Option<u64>,
+            ) -> Result<()> {
+                match alias {
+                    Some(alias) => {
+                        builder.$by_alias(alias, *self);
+                    }
+                    None => {
+                        builder.$add(name, *self)?;
+                    }
+                }
+                Ok(())
+            }
+
+            fn from_metric_value(value: &MetricValue) -> Option<Self> {
+                match value {
+                    MetricValue::$variant(v) => Some(*v),
+                    _ => None,
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_metric_field_by_name {
+    ($($ty:ty, $variant:ident, $add:ident;)*) => {$(
+        impl MetricField for $ty {
+            fn add_to_birth(
+                &self,
+                builder: &mut PayloadBuilder,
+                name: &str,
+                alias: Option<u64>,
+            ) -> Result<()> {
+                if alias.is_some() {
+                    return Err(alias_unsupported(stringify!($ty)));
+                }
+                builder.$add(name, *self)?;
+                Ok(())
+            }
+
+            fn add_to_data(
+                &self,
+                builder: &mut PayloadBuilder,
+                name: &str,
+                alias: Option<u64>,
+            ) -> Result<()> {
+                self.add_to_birth(builder, name, alias)
+            }
+
+            fn from_metric_value(value: &MetricValue) -> Option<Self> {
+                match value {
+                    MetricValue::$variant(v) => Some(*v),
+                    _ => None,
+                }
+            }
+        }
+    )*};
+}
+
+fn alias_unsupported(ty: &str) -> Error {
+    Error::Unsupported(format!("{} metrics cannot be published with an alias", ty))
+}
+
+impl_metric_field_with_alias! {
+    i32, Int32, add_int32, add_int32_with_alias, add_int32_by_alias;
+    i64, Int64, add_int64, add_int64_with_alias, add_int64_by_alias;
+    u32, UInt32, add_uint32, add_uint32_with_alias, add_uint32_by_alias;
+    u64, UInt64, add_uint64, add_uint64_with_alias, add_uint64_by_alias;
+    f32, Float, add_float, add_float_with_alias, add_float_by_alias;
+    f64, Double, add_double, add_double_with_alias, add_double_by_alias;
+    bool, Boolean, add_bool, add_bool_with_alias, add_bool_by_alias;
+}
+
+impl_metric_field_by_name! {
+    i8, Int8, add_int8;
+    i16, Int16, add_int16;
+    u8, UInt8, add_uint8;
+    u16, UInt16, add_uint16;
+}
+
+impl MetricField for String {
+    fn add_to_birth(
+        &self,
+        builder: &mut PayloadBuilder,
+        name: &str,
+        alias: Option<u64>,
+    ) -> Result<()> {
+        if alias.is_some() {
+            return Err(alias_unsupported("String"));
+        }
+        builder.add_string(name, self)?;
+        Ok(())
+    }
+
+    fn add_to_data(
+        &self,
+        builder: &mut PayloadBuilder,
+        name: &str,
+        alias: Option<u64>,
+    ) -> Result<()> {
+        self.add_to_birth(builder, name, alias)
+    }
+
+    fn from_metric_value(value: &MetricValue) -> Option<Self> {
+        match value {
+            MetricValue::String(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+}
@@ -7,6 +7,8 @@ use crate::sys;
 /// Aliases are used in birth certificates to establish a mapping between
 /// metric names and numeric identifiers for bandwidth-efficient updates.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct MetricAlias(pub u64);
 
 impl MetricAlias {
@@ -41,6 +43,7 @@ impl std::fmt::Display for MetricAlias {
 
 /// Sparkplug data types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum DataType {
     /// Unknown or unsupported type
@@ -97,8 +100,60 @@ impl From<sys::sparkplug_data_type_t> for DataType {
     }
 }
 
+impl DataType {
+    /// Returns the Sparkplug name of this data type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataType::Unknown => "Unknown",
+            DataType::Int8 => "Int8",
+            DataType::Int16 => "Int16",
+            DataType::Int32 => "Int32",
+            DataType::Int64 => "Int64",
+            DataType::UInt8 => "UInt8",
+            DataType::UInt16 => "UInt16",
+            DataType::UInt32 => "UInt32",
+            DataType::UInt64 => "UInt64",
+            DataType::Float => "Float",
+            DataType::Double => "Double",
+            DataType::Boolean => "Boolean",
+            DataType::String => "String",
+            DataType::DateTime => "DateTime",
+            DataType::Text => "Text",
+        }
+    }
+
+    /// Looks up a data type by its Sparkplug name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        const ALL: [DataType; 14] = [
+            DataType::Int8,
+            DataType::Int16,
+            DataType::Int32,
+            DataType::Int64,
+            DataType::UInt8,
+            DataType::UInt16,
+            DataType::UInt32,
+            DataType::UInt64,
+            DataType::Float,
+            DataType::Double,
+            DataType::Boolean,
+            DataType::String,
+            DataType::DateTime,
+            DataType::Text,
+        ];
+        ALL.into_iter()
+            .find(|dt| dt.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl std::fmt::Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Metric value type.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MetricValue {
     /// Signed 8-bit integer value
     Int8(i8),
@@ -124,21 +179,271 @@ pub enum MetricValue {
     Boolean(bool),
     /// String value
     String(String),
+    /// DateTime value (ms since Unix epoch)
+    DateTime(u64),
     /// Null value
     Null,
 }
 
+impl MetricValue {
+    /// Returns the value as an `f64` if it is numeric or boolean.
+    ///
+    /// Booleans map to `0.0`/`1.0`; strings and nulls return `None`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetricValue::Int8(v) => Some(*v as f64),
+            MetricValue::Int16(v) => Some(*v as f64),
+            MetricValue::Int32(v) => Some(*v as f64),
+            MetricValue::Int64(v) => Some(*v as f64),
+            MetricValue::UInt8(v) => Some(*v as f64),
+            MetricValue::UInt16(v) => Some(*v as f64),
+            MetricValue::UInt32(v) => Some(*v as f64),
+            MetricValue::UInt64(v) | MetricValue::DateTime(v) => Some(*v as f64),
+            MetricValue::Float(v) => Some(*v as f64),
+            MetricValue::Double(v) => Some(*v),
+            MetricValue::Boolean(v) => Some(if *v { 1.0 } else { 0.0 }),
+            MetricValue::String(_) | MetricValue::Null => None,
+        }
+    }
+
+    /// Returns the data type matching the value ([`DataType::Unknown`] for `Null`).
+    pub fn datatype(&self) -> DataType {
+        match self {
+            MetricValue::Int8(_) => DataType::Int8,
+            MetricValue::Int16(_) => DataType::Int16,
+            MetricValue::Int32(_) => DataType::Int32,
+            MetricValue::Int64(_) => DataType::Int64,
+            MetricValue::UInt8(_) => DataType::UInt8,
+            MetricValue::UInt16(_) => DataType::UInt16,
+            MetricValue::UInt32(_) => DataType::UInt32,
+            MetricValue::UInt64(_) => DataType::UInt64,
+            MetricValue::Float(_) => DataType::Float,
+            MetricValue::Double(_) => DataType::Double,
+            MetricValue::Boolean(_) => DataType::Boolean,
+            MetricValue::String(_) => DataType::String,
+            MetricValue::DateTime(_) => DataType::DateTime,
+            MetricValue::Null => DataType::Unknown,
+        }
+    }
+
+    /// Returns a DateTime value in milliseconds since the Unix epoch.
+    pub fn as_datetime_millis(&self) -> Option<u64> {
+        match self {
+            MetricValue::DateTime(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns a DateTime value as a [`SystemTime`](std::time::SystemTime).
+    pub fn as_system_time(&self) -> Option<std::time::SystemTime> {
+        self.as_datetime_millis().map(crate::time::from_millis)
+    }
+
+    /// Returns a DateTime value as a [`chrono::DateTime<Utc>`](chrono::DateTime).
+    #[cfg(feature = "chrono")]
+    pub fn as_chrono(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::from_timestamp_millis(i64::try_from(self.as_datetime_millis()?).ok()?)
+    }
+
+    /// Parses a string value as a [`uuid::Uuid`].
+    ///
+    /// Returns `Ok(None)` for non-string values and an error if the string is
+    /// not a valid UUID.
+    #[cfg(feature = "uuid")]
+    pub fn as_uuid(&self) -> crate::error::Result<Option<uuid::Uuid>> {
+        match self {
+            MetricValue::String(s) => Ok(Some(uuid::Uuid::parse_str(s)?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl std::fmt::Display for MetricValue {
+    /// Formats the bare value; `Null` formats as an empty string.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricValue::Int8(v) => write!(f, "{}", v),
+            MetricValue::Int16(v) => write!(f, "{}", v),
+            MetricValue::Int32(v) => write!(f, "{}", v),
+            MetricValue::Int64(v) => write!(f, "{}", v),
+            MetricValue::UInt8(v) => write!(f, "{}", v),
+            MetricValue::UInt16(v) => write!(f, "{}", v),
+            MetricValue::UInt32(v) => write!(f, "{}", v),
+            MetricValue::UInt64(v) => write!(f, "{}", v),
+            MetricValue::Float(v) => write!(f, "{}", v),
+            MetricValue::Double(v) => write!(f, "{}", v),
+            MetricValue::Boolean(v) => write!(f, "{}", v),
+            MetricValue::String(v) => write!(f, "{}", v),
+            MetricValue::DateTime(v) => write!(f, "{}", v),
+            MetricValue::Null => Ok(()),
+        }
+    }
+}
+
+/// Borrowed metric value, pointing into a parsed payload.
+///
+/// Returned by [`Payload::metrics_ref`](crate::Payload::metrics_ref); convert
+/// with [`to_value`](Self::to_value) to keep it beyond the payload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricValueRef<'a> {
+    /// Signed 8-bit integer value
+    Int8(i8),
+    /// Signed 16-bit integer value
+    Int16(i16),
+    /// Signed 32-bit integer value
+    Int32(i32),
+    /// Signed 64-bit integer value
+    Int64(i64),
+    /// Unsigned 8-bit integer value
+    UInt8(u8),
+    /// Unsigned 16-bit integer value
+    UInt16(u16),
+    /// Unsigned 32-bit integer value
+    UInt32(u32),
+    /// Unsigned 64-bit integer value
+    UInt64(u64),
+    /// 32-bit floating point value
+    Float(f32),
+    /// 64-bit floating point value
+    Double(f64),
+    /// Boolean value
+    Boolean(bool),
+    /// Borrowed string value
+    String(&'a str),
+    /// DateTime value (ms since Unix epoch)
+    DateTime(u64),
+    /// Null value
+    Null,
+}
+
+impl MetricValueRef<'_> {
+    /// Copies the value into an owned [`MetricValue`].
+    pub fn to_value(&self) -> MetricValue {
+        match *self {
+            MetricValueRef::Int8(v) => MetricValue::Int8(v),
+            MetricValueRef::Int16(v) => MetricValue::Int16(v),
+            MetricValueRef::Int32(v) => MetricValue::Int32(v),
+            MetricValueRef::Int64(v) => MetricValue::Int64(v),
+            MetricValueRef::UInt8(v) => MetricValue::UInt8(v),
+            MetricValueRef::UInt16(v) => MetricValue::UInt16(v),
+            MetricValueRef::UInt32(v) => MetricValue::UInt32(v),
+            MetricValueRef::UInt64(v) => MetricValue::UInt64(v),
+            MetricValueRef::Float(v) => MetricValue::Float(v),
+            MetricValueRef::Double(v) => MetricValue::Double(v),
+            MetricValueRef::Boolean(v) => MetricValue::Boolean(v),
+            MetricValueRef::String(v) => MetricValue::String(v.to_string()),
+            MetricValueRef::DateTime(v) => MetricValue::DateTime(v),
+            MetricValueRef::Null => MetricValue::Null,
+        }
+    }
+
+    /// Returns the value as an `f64` if it is numeric or boolean.
+    ///
+    /// See [`MetricValue::as_f64`].
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetricValueRef::String(_) | MetricValueRef::Null => None,
+            other => other.to_value().as_f64(),
+        }
+    }
+
+    /// Returns the value as a string slice if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetricValueRef::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Borrowed view of a metric, pointing into a parsed payload.
+///
+/// Same fields as [`Metric`], without copying the name or string value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricRef<'a> {
+    /// Metric name (if present)
+    pub name: Option<&'a str>,
+    /// Metric alias (if present)
+    pub alias: Option<MetricAlias>,
+    /// Metric timestamp in milliseconds since Unix epoch (if present)
+    pub timestamp: Option<u64>,
+    /// Data type
+    pub datatype: DataType,
+    /// Metric value; `Null` if the sender marked the metric null or its datatype is not supported
+    pub value: MetricValueRef<'a>,
+}
+
+impl MetricRef<'_> {
+    /// Returns true if the sender marked the metric null (e.g. to signal quality loss).
+    ///
+    /// A metric whose datatype is not supported also has a `Null` value, but
+    /// its datatype is [`DataType::Unknown`] and it is not reported as null.
+    pub fn is_null(&self) -> bool {
+        self.value == MetricValueRef::Null && self.datatype != DataType::Unknown
+    }
+
+    /// Copies the view into an owned [`Metric`].
+    pub fn to_metric(&self) -> Metric {
+        Metric {
+            name: self.name.map(str::to_string),
+            alias: self.alias,
+            timestamp: self.timestamp,
+            datatype: self.datatype,
+            value: self.value.to_value(),
+        }
+    }
+}
+
 /// Metric information.
+///
+/// The name type defaults to `String`; [`InternedMetric`] shares names
+/// through a [`NameCache`](crate::intern::NameCache) instead.
 #[derive(Debug, Clone)]
-pub struct Metric {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metric<N = String> {
     /// Metric name (if present)
-    pub name: Option<String>,
+    pub name: Option<N>,
     /// Metric alias (if present)
     pub alias: Option<MetricAlias>,
     /// Metric timestamp in milliseconds since Unix epoch (if present)
     pub timestamp: Option<u64>,
     /// Data type
     pub datatype: DataType,
-    /// Metric value (or Null)
+    /// Metric value; `Null` if the sender marked the metric null or its datatype is not supported
     pub value: MetricValue,
 }
+
+/// An owned copy of a parsed payload.
+///
+/// Unlike [`Payload`](crate::Payload) it holds no C handle, so it can be
+/// stored, sent across threads or (with the `serde` feature) serialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayloadSnapshot {
+    /// Payload timestamp in milliseconds since Unix epoch (if present)
+    pub timestamp: Option<u64>,
+    /// Payload sequence number (if present)
+    pub seq: Option<u64>,
+    /// Payload UUID (if present)
+    pub uuid: Option<String>,
+    /// Decoded metrics
+    pub metrics: Vec<Metric>,
+}
+
+/// A parsed metric whose name is shared through a [`NameCache`](crate::intern::NameCache).
+pub type InternedMetric = Metric<std::sync::Arc<str>>;
+
+impl<N> Metric<N> {
+    /// Returns true if the sender marked the metric null (e.g. to signal quality loss).
+    ///
+    /// A metric whose datatype is not supported also has a `Null` value, but
+    /// its datatype is [`DataType::Unknown`] and it is not reported as null.
+    pub fn is_null(&self) -> bool {
+        self.value == MetricValue::Null && self.datatype != DataType::Unknown
+    }
+
+    /// Returns the metric timestamp as a [`SystemTime`](std::time::SystemTime), if present.
+    pub fn time(&self) -> Option<std::time::SystemTime> {
+        self.timestamp.map(crate::time::from_millis)
+    }
+}
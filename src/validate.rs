@@ -0,0 +1,152 @@
+//! Sparkplug payload rule checks.
+//!
+//! The C library serializes whatever it is given, so a birth with duplicate
+//! aliases or a data message with an unidentified metric is only noticed by
+//! the host application that rejects it. [`validate_birth`],
+//! [`validate_node_birth`] and [`validate_data`] run the same checks before
+//! publishing and report every violation at once.
+
+use crate::error::{Error, Result};
+use crate::payload::Payload;
+use crate::types::{MetricAlias, MetricValue};
+use std::collections::HashSet;
+
+/// Largest valid `seq` and `bdSeq` value.
+pub const MAX_SEQ: u64 = 255;
+
+/// A Sparkplug payload rule broken by a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// A birth metric has no name.
+    MissingName {
+        /// Metric index in the payload
+        index: usize,
+    },
+    /// A data metric has neither a name nor an alias.
+    MissingIdentifier {
+        /// Metric index in the payload
+        index: usize,
+    },
+    /// Two metrics share a name.
+    DuplicateName(String),
+    /// Two metrics share an alias.
+    DuplicateAlias(MetricAlias),
+    /// A data metric's alias was not declared in the birth.
+    UndeclaredAlias(MetricAlias),
+    /// A data metric's name was not declared in the birth.
+    UndeclaredName(String),
+    /// The payload sequence number is above [`MAX_SEQ`].
+    SeqOutOfRange(u64),
+    /// An NBIRTH has no `bdSeq` metric.
+    MissingBdSeq,
+    /// The `bdSeq` metric is not an unsigned integer in `0..=MAX_SEQ`.
+    InvalidBdSeq,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::MissingName { index } => write!(f, "birth metric {} has no name", index),
+            Violation::MissingIdentifier { index } => {
+                write!(f, "metric {} has neither name nor alias", index)
+            }
+            Violation::DuplicateName(name) => write!(f, "duplicate metric name '{}'", name),
+            Violation::DuplicateAlias(alias) => write!(f, "duplicate metric alias {}", alias),
+            Violation::UndeclaredAlias(alias) => {
+                write!(f, "metric alias {} is not declared in the birth", alias)
+            }
+            Violation::UndeclaredName(name) => {
+                write!(f, "metric '{}' is not declared in the birth", name)
+            }
+            Violation::SeqOutOfRange(seq) => write!(f, "seq {} is above {}", seq, MAX_SEQ),
+            Violation::MissingBdSeq => write!(f, "NBIRTH has no bdSeq metric"),
+            Violation::InvalidBdSeq => {
+                write!(f, "bdSeq must be an unsigned integer in 0..={}", MAX_SEQ)
+            }
+        }
+    }
+}
+
+/// Checks an NBIRTH or DBIRTH payload.
+///
+/// Every metric must carry a name, and names and aliases must be unique.
+///
+/// # Example
+///
+/// ```no_run
+/// use sparkplug_rs::validate::validate_birth;
+/// use sparkplug_rs::{Payload, PayloadBuilder};
+///
+/// let mut dbirth = PayloadBuilder::new()?;
+/// dbirth.add_double_with_alias("Temperature", 1, 20.5)?;
+/// let bytes = dbirth.serialize()?;
+/// validate_birth(&Payload::parse(&bytes)?)?;
+/// # Ok::<(), sparkplug_rs::Error>(())
+/// ```
+pub fn validate_birth(payload: &Payload) -> Result<()> {
+    finish(check(payload, true)?)
+}
+
+/// Checks an NBIRTH payload: [`validate_birth`] plus a valid `bdSeq` metric.
+pub fn validate_node_birth(payload: &Payload) -> Result<()> {
+    let mut violations = check(payload, true)?;
+    let mut bd_seq = None;
+    for metric in payload.metrics_ref() {
+        let metric = metric?;
+        if metric.name == Some("bdSeq") {
+            bd_seq = Some(metric.value.to_value());
+        }
+    }
+    match bd_seq {
+        None => violations.push(Violation::MissingBdSeq),
+        Some(MetricValue::UInt64(v)) if v <= MAX_SEQ => {}
+        Some(MetricValue::Int64(v)) if (0..=MAX_SEQ as i64).contains(&v) => {}
+        Some(_) => violations.push(Violation::InvalidBdSeq),
+    }
+    finish(violations)
+}
+
+/// Checks an NDATA or DDATA payload.
+///
+/// Every metric must carry a name or an alias, and names and aliases must
+/// be unique.
+pub fn validate_data(payload: &Payload) -> Result<()> {
+    finish(check(payload, false)?)
+}
+
+fn check(payload: &Payload, birth: bool) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    if let Some(seq) = payload.seq().filter(|&seq| seq > MAX_SEQ) {
+        violations.push(Violation::SeqOutOfRange(seq));
+    }
+
+    let mut names = HashSet::new();
+    let mut aliases = HashSet::new();
+    for (index, metric) in payload.metrics_ref().enumerate() {
+        let metric = metric?;
+        match (metric.name, metric.alias) {
+            (None, _) if birth => violations.push(Violation::MissingName { index }),
+            (None, None) => violations.push(Violation::MissingIdentifier { index }),
+            _ => {}
+        }
+        if let Some(name) = metric.name {
+            if !names.insert(name) {
+                violations.push(Violation::DuplicateName(name.to_string()));
+            }
+        }
+        if let Some(alias) = metric.alias {
+            if !aliases.insert(alias) {
+                violations.push(Violation::DuplicateAlias(alias));
+            }
+        }
+    }
+    Ok(violations)
+}
+
+fn finish(violations: Vec<Violation>) -> Result<()> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidPayload(violations))
+    }
+}
@@ -0,0 +1,209 @@
+//! Edits of serialized Sparkplug payloads at the protobuf wire level.
+//!
+//! The C API cannot set some fields, such as a metric's `is_null` flag, so
+//! they are patched into the bytes it serializes. Dry runs stamp sequence
+//! numbers the same way. Only the fields being edited are decoded; every
+//! other field is copied unchanged.
+
+use crate::error::{Error, Result};
+use crate::types::DataType;
+
+/// `Payload.timestamp`
+const PAYLOAD_TIMESTAMP: u32 = 1;
+/// `Payload.metrics`
+const PAYLOAD_METRICS: u32 = 2;
+/// `Payload.seq`
+const PAYLOAD_SEQ: u32 = 3;
+
+/// `Metric.name`
+const METRIC_NAME: u32 = 1;
+/// `Metric.datatype`
+const METRIC_DATATYPE: u32 = 4;
+/// `Metric.is_null`
+const METRIC_IS_NULL: u32 = 7;
+/// `Metric.long_value`
+const METRIC_LONG_VALUE: u32 = 11;
+/// Field numbers of the `Metric.value` oneof
+const METRIC_VALUES: std::ops::RangeInclusive<u32> = 10..=19;
+
+const VARINT: u8 = 0;
+const I64: u8 = 1;
+const LEN: u8 = 2;
+const I32: u8 = 5;
+
+/// One field of an encoded message.
+struct Field<'a> {
+    number: u32,
+    /// Contents of a length-delimited field
+    contents: &'a [u8],
+    /// The whole field, key included
+    encoded: &'a [u8],
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(Error::ParseFailed)?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::ParseFailed)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(Error::ParseFailed);
+    }
+    let (head, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(head)
+}
+
+fn fields(message: &[u8]) -> Result<Vec<Field<'_>>> {
+    let mut rest = message;
+    let mut fields = Vec::new();
+    while !rest.is_empty() {
+        let start = rest;
+        let key = read_varint(&mut rest)?;
+        let number = u32::try_from(key >> 3).map_err(|_| Error::ParseFailed)?;
+        let contents = match (key & 7) as u8 {
+            VARINT => {
+                read_varint(&mut rest)?;
+                &[][..]
+            }
+            I64 => take(&mut rest, 8)?,
+            LEN => {
+                let len = read_varint(&mut rest)?;
+                take(
+                    &mut rest,
+                    usize::try_from(len).map_err(|_| Error::ParseFailed)?,
+                )?
+            }
+            I32 => take(&mut rest, 4)?,
+            _ => return Err(Error::ParseFailed),
+        };
+        fields.push(Field {
+            number,
+            contents,
+            encoded: &start[..start.len() - rest.len()],
+        });
+    }
+    Ok(fields)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_varint_field(out: &mut Vec<u8>, number: u32, value: u64) {
+    write_varint(out, u64::from(number) << 3 | u64::from(VARINT));
+    write_varint(out, value);
+}
+
+fn write_len_field(out: &mut Vec<u8>, number: u32, contents: &[u8]) {
+    write_varint(out, u64::from(number) << 3 | u64::from(LEN));
+    write_varint(out, contents.len() as u64);
+    out.extend_from_slice(contents);
+}
+
+/// Copies the fields of an encoded metric except its datatype and value.
+fn strip_value(metric: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(metric.len() + 4);
+    for field in fields(metric)? {
+        let edited = field.number == METRIC_DATATYPE
+            || field.number == METRIC_IS_NULL
+            || METRIC_VALUES.contains(&field.number);
+        if !edited {
+            out.extend_from_slice(field.encoded);
+        }
+    }
+    Ok(out)
+}
+
+/// Marks metrics of a serialized payload as null.
+///
+/// `nulls` holds the index of each metric to mark, in payload order, and
+/// the datatype it declares. The metric's value is removed and its name,
+/// alias and other fields are kept.
+pub(crate) fn mark_null(payload: &[u8], nulls: &[(usize, DataType)]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(payload.len() + 4 * nulls.len());
+    let mut index = 0;
+    for field in fields(payload)? {
+        if field.number != PAYLOAD_METRICS {
+            out.extend_from_slice(field.encoded);
+            continue;
+        }
+        match nulls.iter().find(|(i, _)| *i == index) {
+            Some(&(_, datatype)) => {
+                let mut metric = strip_value(field.contents)?;
+                write_varint_field(&mut metric, METRIC_DATATYPE, datatype as u64);
+                write_varint_field(&mut metric, METRIC_IS_NULL, 1);
+                write_len_field(&mut out, PAYLOAD_METRICS, &metric);
+            }
+            None => out.extend_from_slice(field.encoded),
+        }
+        index += 1;
+    }
+    Ok(out)
+}
+
+/// Sets the sequence number of a serialized payload, the timestamp if it
+/// has none and, if `bd_seq` is given, the value of its `bdSeq` metric,
+/// which is appended if missing.
+///
+/// This is what the C publisher does to a payload before sending it; every
+/// other field is left as the caller encoded it.
+pub(crate) fn stamp(
+    payload: &[u8],
+    timestamp: u64,
+    seq: Option<u64>,
+    bd_seq: Option<u64>,
+) -> Result<Vec<u8>> {
+    let fields = fields(payload)?;
+    let mut out = Vec::with_capacity(payload.len() + 24);
+    if !fields.iter().any(|f| f.number == PAYLOAD_TIMESTAMP) {
+        write_varint_field(&mut out, PAYLOAD_TIMESTAMP, timestamp);
+    }
+    let mut has_bd_seq = false;
+    for field in &fields {
+        match (field.number, bd_seq) {
+            (PAYLOAD_SEQ, _) if seq.is_some() => {}
+            (PAYLOAD_METRICS, Some(bd_seq)) if named(field.contents, "bdSeq")? => {
+                has_bd_seq = true;
+                let mut metric = strip_value(field.contents)?;
+                write_bd_seq_value(&mut metric, bd_seq);
+                write_len_field(&mut out, PAYLOAD_METRICS, &metric);
+            }
+            _ => out.extend_from_slice(field.encoded),
+        }
+    }
+    if let (Some(bd_seq), false) = (bd_seq, has_bd_seq) {
+        let mut metric = Vec::new();
+        write_len_field(&mut metric, METRIC_NAME, b"bdSeq");
+        write_bd_seq_value(&mut metric, bd_seq);
+        write_len_field(&mut out, PAYLOAD_METRICS, &metric);
+    }
+    if let Some(seq) = seq {
+        write_varint_field(&mut out, PAYLOAD_SEQ, seq);
+    }
+    Ok(out)
+}
+
+/// Returns true if an encoded metric has the given name.
+fn named(metric: &[u8], name: &str) -> Result<bool> {
+    Ok(fields(metric)?
+        .iter()
+        .any(|f| f.number == METRIC_NAME && f.contents == name.as_bytes()))
+}
+
+fn write_bd_seq_value(metric: &mut Vec<u8>, bd_seq: u64) {
+    write_varint_field(metric, METRIC_DATATYPE, DataType::UInt64 as u64);
+    write_varint_field(metric, METRIC_LONG_VALUE, bd_seq);
+}
@@ -0,0 +1,90 @@
+//! Tests for metric alias assignment
+
+use sparkplug_rs::{AliasAllocator, Error, MetricAlias, Publisher, PublisherConfig};
+
+fn temp_file(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("sparkplug_rs_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_alias_is_stable_and_unique() {
+    let mut aliases = AliasAllocator::new();
+    let temperature = aliases.alias("Temperature");
+    let pressure = aliases.alias("Pressure");
+
+    assert_eq!(temperature, MetricAlias::new(1));
+    assert_eq!(pressure, MetricAlias::new(2));
+    assert_eq!(aliases.alias("Temperature"), temperature);
+    assert_eq!(aliases.name(pressure), Some("Pressure"));
+    assert_eq!(aliases.len(), 2);
+}
+
+#[test]
+fn test_insert_skips_reserved_aliases() {
+    let mut aliases = AliasAllocator::starting_at(10);
+    aliases.insert("Fixed", 11).unwrap();
+
+    assert_eq!(aliases.alias("A"), MetricAlias::new(10));
+    assert_eq!(aliases.alias("B"), MetricAlias::new(12));
+    assert!(matches!(
+        aliases.insert("Other", 11),
+        Err(Error::InvalidConfig(_))
+    ));
+    assert!(matches!(
+        aliases.insert("A", 20),
+        Err(Error::InvalidConfig(_))
+    ));
+    aliases.insert("A", 10).unwrap();
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = temp_file("aliases_roundtrip");
+    let mut aliases = AliasAllocator::new();
+    aliases.alias("Temperature");
+    aliases.alias("Device/Speed");
+    aliases.save(&path).unwrap();
+
+    let mut loaded = AliasAllocator::load(&path).unwrap();
+    assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        aliases.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(loaded.alias("New"), MetricAlias::new(3));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_load_rejects_malformed_lines() {
+    let path = temp_file("aliases_malformed");
+    std::fs::write(&path, "1\tTemperature\nnot an alias\n").unwrap();
+
+    assert!(matches!(AliasAllocator::load(&path), Err(Error::Io(_))));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_publisher_persists_aliases() {
+    let path = temp_file("aliases_publisher");
+    let config = PublisherConfig::builder()
+        .broker("tcp://localhost:1883")
+        .client_id("alias_test")
+        .group_id("Energy")
+        .edge_node_id("Gateway01")
+        .alias_file(&path)
+        .build()
+        .unwrap();
+
+    let mut publisher = Publisher::new(config.clone()).unwrap();
+    let speed = publisher.alias("Motor01/Speed").unwrap();
+    let temperature = publisher.alias("Temperature").unwrap();
+    drop(publisher);
+
+    let mut publisher = Publisher::new(config).unwrap();
+    assert_eq!(publisher.aliases().len(), 2);
+    assert_eq!(publisher.alias("Temperature").unwrap(), temperature);
+    assert_eq!(publisher.alias("Motor01/Speed").unwrap(), speed);
+    std::fs::remove_file(&path).unwrap();
+}
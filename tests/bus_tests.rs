@@ -0,0 +1,119 @@
+//! Tests for the decoded message event bus
+
+use sparkplug_rs::{EventBus, Message, MetricValue, PayloadBuilder};
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+fn data(value: f64) -> Message {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.set_seq(1);
+    builder.add_double("Temperature", value).unwrap();
+    Message {
+        topic: "spBv1.0/Energy/NDATA/Gateway01".to_string(),
+        payload_data: builder.serialize().unwrap(),
+    }
+}
+
+struct ThreadWaker(thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn test_every_consumer_receives_each_event() {
+    let bus = EventBus::new(16);
+    let ui = bus.subscribe("ui");
+    let historian = bus.subscribe("historian");
+
+    assert_eq!(bus.publish(&data(20.0)).unwrap(), 2);
+
+    for receiver in [&ui, &historian] {
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.topic.edge_node_id(), Some("Gateway01"));
+        assert_eq!(event.seq, Some(1));
+        assert_eq!(event.metrics[0].value, MetricValue::Double(20.0));
+        assert!(receiver.try_recv().is_none());
+    }
+}
+
+#[test]
+fn test_slow_consumer_lags_without_blocking_others() {
+    let bus = EventBus::new(2);
+    let slow = bus.subscribe("slow");
+    let fast = bus.subscribe("fast");
+
+    for value in [1.0, 2.0, 3.0] {
+        bus.publish(&data(value)).unwrap();
+        fast.try_recv().unwrap();
+    }
+
+    assert_eq!(slow.lagged(), 1);
+    assert_eq!(fast.lagged(), 0);
+    assert_eq!(
+        slow.try_recv().unwrap().metrics[0].value,
+        MetricValue::Double(2.0)
+    );
+
+    let stats = bus.stats();
+    assert_eq!(stats[0].name, "slow");
+    assert_eq!(stats[0].queued, 1);
+    assert_eq!(stats[0].lagged, 1);
+    assert_eq!(stats[1].queued, 0);
+}
+
+#[test]
+fn test_dropped_receiver_is_unregistered() {
+    let bus = EventBus::new(4);
+    let receiver = bus.subscribe("temporary");
+    assert_eq!(bus.receiver_count(), 1);
+
+    drop(receiver);
+    assert_eq!(bus.receiver_count(), 0);
+    assert_eq!(bus.publish(&data(1.0)).unwrap(), 0);
+}
+
+#[test]
+fn test_receiver_closes_when_bus_is_dropped() {
+    let bus = EventBus::new(4);
+    let receiver = bus.subscribe("consumer");
+    bus.publish(&data(1.0)).unwrap();
+    drop(bus);
+
+    assert!(receiver.recv().is_some());
+    assert!(receiver.recv().is_none());
+    assert!(receiver.recv_timeout(Duration::from_millis(10)).is_none());
+}
+
+#[test]
+fn test_recv_async() {
+    let bus = EventBus::new(4);
+    let receiver = bus.subscribe("async");
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        bus.publish(&data(5.0)).unwrap();
+    });
+
+    let event = block_on(receiver.recv_async()).unwrap();
+    assert_eq!(event.metrics[0].value, MetricValue::Double(5.0));
+    handle.join().unwrap();
+    assert!(block_on(receiver.recv_async()).is_none());
+}
@@ -0,0 +1,71 @@
+//! Tests for tag list code generation
+
+use sparkplug_rs::codegen::{generate_rust, parse_tag_csv, TagDefinition};
+use sparkplug_rs::{DataType, Error};
+
+const TAGS: &str = "\
+name,type,alias,units,writable
+DATA/Speed,Double,1,rpm,true
+\"CFG/Label, long\",string,,,
+Status/Since,DateTime,3,,no
+";
+
+#[test]
+fn test_parse_tag_csv() {
+    let tags = parse_tag_csv(TAGS).unwrap();
+
+    assert_eq!(tags.len(), 3);
+    assert_eq!(
+        tags[0],
+        TagDefinition {
+            name: "DATA/Speed".to_string(),
+            datatype: DataType::Double,
+            alias: Some(1),
+            units: Some("rpm".to_string()),
+            writable: true,
+        }
+    );
+    assert_eq!(tags[1].name, "CFG/Label, long");
+    assert_eq!(tags[1].datatype, DataType::String);
+    assert_eq!(tags[1].alias, None);
+    assert!(!tags[1].writable);
+    assert_eq!(tags[2].datatype, DataType::DateTime);
+}
+
+#[test]
+fn test_parse_tag_csv_errors() {
+    assert!(matches!(
+        parse_tag_csv("name,alias\nA,1\n"),
+        Err(Error::InvalidTagList(_))
+    ));
+    assert!(matches!(
+        parse_tag_csv("name,type\nA,Complex\n"),
+        Err(Error::InvalidTagList(_))
+    ));
+    assert!(matches!(
+        parse_tag_csv("name,type,alias\nA,Int32,x\n"),
+        Err(Error::InvalidTagList(_))
+    ));
+}
+
+#[test]
+fn test_generate_rust() {
+    let code = generate_rust(&parse_tag_csv(TAGS).unwrap(), "Pump").unwrap();
+
+    assert!(code.contains("pub const DATA_SPEED: &str = \"DATA/Speed\";"));
+    assert!(code.contains("pub const DATA_SPEED: u64 = 1;"));
+    assert!(code.contains("pub struct Pump {"));
+    assert!(code.contains("#[sparkplug(name = \"DATA/Speed\", alias = 1)]"));
+    assert!(code.contains("pub data_speed: f64,"));
+    assert!(code.contains("#[sparkplug(name = \"CFG/Label, long\", readonly)]"));
+    assert!(code.contains("// Status/Since (DateTime) has no typed field"));
+}
+
+#[test]
+fn test_generate_rust_rejects_duplicate_identifiers() {
+    let tags = parse_tag_csv("name,type\nA/B,Int32\nA_B,Int32\n").unwrap();
+    assert!(matches!(
+        generate_rust(&tags, "Tags"),
+        Err(Error::InvalidTagList(_))
+    ));
+}
@@ -0,0 +1,104 @@
+//! Tests for command confirmation waiting
+
+use sparkplug_rs::{
+    CommandWaiter, Confirmation, Error, Message, MetricKey, MetricValue, PayloadBuilder,
+};
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+fn message(topic: &str, builder: &PayloadBuilder) -> Message {
+    Message {
+        topic: topic.to_string(),
+        payload_data: builder.serialize().unwrap(),
+    }
+}
+
+fn setpoint_data(value: f64) -> Message {
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double("Setpoint", value).unwrap();
+    message("spBv1.0/Energy/NDATA/Gateway01", &data)
+}
+
+struct ThreadWaker(thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn test_wait_for_metric_value() {
+    let waiter = CommandWaiter::new();
+    let pending = waiter.expect_metric(
+        MetricKey::new("Energy", "Gateway01", None, "Setpoint"),
+        Some(MetricValue::Double(42.0)),
+        Duration::from_secs(5),
+    );
+
+    let observer = waiter.clone();
+    let handle = thread::spawn(move || {
+        observer.observe(&setpoint_data(10.0));
+        observer.observe(&setpoint_data(42.0));
+    });
+
+    match pending.wait().unwrap() {
+        Confirmation::Metric(sample) => assert_eq!(sample.value, MetricValue::Double(42.0)),
+        other => panic!("unexpected confirmation: {:?}", other),
+    }
+    handle.join().unwrap();
+    assert_eq!(waiter.pending_count(), 0);
+}
+
+#[test]
+fn test_wait_times_out() {
+    let waiter = CommandWaiter::new();
+    let pending = waiter.expect_metric(
+        MetricKey::new("Energy", "Gateway01", None, "Setpoint"),
+        Some(MetricValue::Double(42.0)),
+        Duration::from_millis(20),
+    );
+    waiter.observe(&setpoint_data(10.0));
+
+    assert!(matches!(pending.wait(), Err(Error::Timeout(_))));
+}
+
+#[test]
+fn test_await_birth() {
+    let waiter = CommandWaiter::new();
+    let pending = waiter.expect_birth("Energy", "Gateway01", Duration::from_secs(5));
+
+    let observer = waiter.clone();
+    let handle = thread::spawn(move || {
+        let mut birth = PayloadBuilder::new().unwrap();
+        birth.add_bool("Online", true).unwrap();
+        observer.observe(&message("spBv1.0/Energy/NBIRTH/Other", &birth));
+        observer.observe(&message("spBv1.0/Energy/NBIRTH/Gateway01", &birth));
+    });
+
+    assert_eq!(block_on(pending).unwrap(), Confirmation::Birth);
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_await_times_out() {
+    let waiter = CommandWaiter::new();
+    let pending = waiter.expect_birth("Energy", "Gateway01", Duration::from_millis(20));
+
+    assert!(matches!(block_on(pending), Err(Error::Timeout(_))));
+}
@@ -1,6 +1,6 @@
 //! Tests for Publisher and Subscriber configurations
 
-use sparkplug_rs::{PublisherConfig, SubscriberConfig};
+use sparkplug_rs::{DeviceBirthPolicy, Error, PublisherConfig, SubscriberConfig};
 
 #[test]
 fn test_publisher_config_creation() {
@@ -15,6 +15,7 @@ fn test_publisher_config_creation() {
     assert_eq!(config.client_id, "test_client");
     assert_eq!(config.group_id, "TestGroup");
     assert_eq!(config.edge_node_id, "TestNode");
+    assert!(!config.auto_reconnect);
 }
 
 #[test]
@@ -49,6 +50,7 @@ fn test_subscriber_config_creation() {
     assert_eq!(config.broker_url, "tcp://localhost:1883");
     assert_eq!(config.client_id, "sub_client");
     assert_eq!(config.group_id, "TestGroup");
+    assert!(!config.store_values);
 }
 
 #[test]
@@ -76,3 +78,40 @@ fn test_config_with_special_characters() {
     assert_eq!(config.group_id, "Group/SubGroup");
     assert_eq!(config.edge_node_id, "Node#1");
 }
+
+#[test]
+fn test_publisher_config_builder() {
+    let config = PublisherConfig::builder()
+        .broker("tcp://localhost:1883")
+        .client_id("client")
+        .group_id("Energy")
+        .edge_node_id("Gateway01")
+        .device_birth_policy(DeviceBirthPolicy::AutoBirth)
+        .strict(true)
+        .auto_reconnect(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.broker_url, "tcp://localhost:1883");
+    assert_eq!(config.client_id, "client");
+    assert_eq!(config.group_id, "Energy");
+    assert_eq!(config.edge_node_id, "Gateway01");
+    assert_eq!(config.device_birth_policy, DeviceBirthPolicy::AutoBirth);
+    assert!(config.strict);
+    assert!(config.auto_reconnect);
+    assert!(config.reconnect.is_none());
+}
+
+#[test]
+fn test_publisher_config_builder_requires_ids() {
+    let result = PublisherConfig::builder()
+        .broker("tcp://localhost:1883")
+        .client_id("client")
+        .group_id("Energy")
+        .build();
+
+    match result {
+        Err(Error::InvalidConfig(details)) => assert_eq!(details, "edge node ID is required"),
+        other => panic!("expected InvalidConfig, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,153 @@
+//! Tests for derived metric computation
+
+use sparkplug_rs::derived::DerivedValue;
+use sparkplug_rs::{DataType, Derivation, DerivedMetrics, Metric, MetricValue};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn values(results: &[DerivedValue]) -> Vec<(&str, f64)> {
+    results.iter().map(|v| (v.name.as_str(), v.value)).collect()
+}
+
+#[test]
+fn test_rate_of_change() {
+    let mut derived = DerivedMetrics::new();
+    derived.add(
+        "Energy/Rate",
+        Derivation::RateOfChange {
+            source: "Energy".to_string(),
+        },
+    );
+
+    assert!(derived.update("Energy", 1_000, 100.0).is_empty());
+    let results = derived.update("Energy", 3_000, 110.0);
+    assert_eq!(values(&results), vec![("Energy/Rate", 5.0)]);
+}
+
+#[test]
+fn test_rolling_window_drops_old_samples() {
+    let mut derived = DerivedMetrics::new();
+    let window = Duration::from_secs(10);
+    derived
+        .add(
+            "Avg",
+            Derivation::RollingAverage {
+                source: "P".to_string(),
+                window,
+            },
+        )
+        .add(
+            "Min",
+            Derivation::Min {
+                source: "P".to_string(),
+                window,
+            },
+        )
+        .add(
+            "Max",
+            Derivation::Max {
+                source: "P".to_string(),
+                window,
+            },
+        );
+
+    derived.update("P", 0, 100.0);
+    derived.update("P", 5_000, 10.0);
+    let results = derived.update("P", 12_000, 20.0);
+
+    // The sample at t=0 is outside the 10s window ending at t=12s.
+    assert_eq!(
+        values(&results),
+        vec![("Avg", 15.0), ("Min", 10.0), ("Max", 20.0)]
+    );
+}
+
+#[test]
+fn test_expression_waits_for_all_inputs() {
+    let mut derived = DerivedMetrics::new();
+    derived.add(
+        "Net",
+        Derivation::Expression {
+            inputs: vec!["PV".to_string(), "Load".to_string()],
+            function: Arc::new(|v| v[0] - v[1]),
+        },
+    );
+
+    assert!(derived.update("PV", 1_000, 50.0).is_empty());
+    let results = derived.update("Load", 1_000, 20.0);
+    assert_eq!(values(&results), vec![("Net", 30.0)]);
+}
+
+#[test]
+fn test_runtime_add_and_remove() {
+    let mut derived = DerivedMetrics::new();
+    derived.add(
+        "Rate",
+        Derivation::RateOfChange {
+            source: "X".to_string(),
+        },
+    );
+    assert_eq!(derived.names().collect::<Vec<_>>(), vec!["Rate"]);
+
+    assert!(derived.remove("Rate"));
+    assert!(!derived.remove("Rate"));
+    assert!(derived.update("X", 0, 1.0).is_empty());
+}
+
+#[test]
+fn test_remove_drops_unused_history() {
+    let mut derived = DerivedMetrics::new();
+    let rate = Derivation::RateOfChange {
+        source: "X".to_string(),
+    };
+    derived.add("Rate", rate.clone());
+    derived.update("X", 1_000, 1.0);
+    derived.update("X", 2_000, 2.0);
+
+    // Re-adding the definition starts from an empty history.
+    derived.remove("Rate");
+    derived.add("Rate", rate);
+    assert!(derived.update("X", 3_000, 10.0).is_empty());
+    let results = derived.update("X", 4_000, 11.0);
+    assert_eq!(values(&results), vec![("Rate", 1.0)]);
+}
+
+#[test]
+fn test_late_samples_are_ignored() {
+    let mut derived = DerivedMetrics::new();
+    derived.add(
+        "Rate",
+        Derivation::RateOfChange {
+            source: "X".to_string(),
+        },
+    );
+    derived.update("X", 1_000, 1.0);
+    derived.update("X", 3_000, 5.0);
+
+    assert!(derived.update("X", 2_000, 100.0).is_empty());
+    let results = derived.update("X", 4_000, 7.0);
+    assert_eq!(values(&results), vec![("Rate", 2.0)]);
+}
+
+#[test]
+fn test_update_from_metric() {
+    let mut derived = DerivedMetrics::new();
+    derived.add(
+        "Max",
+        Derivation::Max {
+            source: "Temp".to_string(),
+            window: Duration::from_secs(60),
+        },
+    );
+
+    let metric = Metric {
+        name: Some("Temp".to_string()),
+        alias: None,
+        timestamp: None,
+        datatype: DataType::Int32,
+        value: MetricValue::Int32(21),
+    };
+    let results = derived.update_metric(&metric, 1_000);
+    assert_eq!(results[0].timestamp, 1_000);
+    assert_eq!(results[0].value, 21.0);
+}
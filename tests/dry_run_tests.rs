@@ -0,0 +1,367 @@
+//! Tests for publishing to a sink instead of a broker
+
+use sparkplug_rs::{
+    DataType, Error, Message, MetricAlias, MetricValue, Payload, PayloadBuilder, Publisher,
+    PublisherConfig,
+};
+use std::sync::{Arc, Mutex};
+
+fn dry_publisher() -> (Publisher, Arc<Mutex<Vec<Message>>>) {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    let config = PublisherConfig::new("tcp://localhost:1883", "dry", "Energy", "Gateway01");
+    let publisher = Publisher::dry_run(
+        config,
+        Box::new(move |msg: Message| sink.lock().unwrap().push(msg)),
+    )
+    .unwrap();
+    (publisher, sent)
+}
+
+fn birth() -> Vec<u8> {
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_double_with_alias("Temperature", 1, 20.5).unwrap();
+    birth.serialize().unwrap()
+}
+
+fn data() -> Vec<u8> {
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 21.0);
+    data.serialize().unwrap()
+}
+
+fn bd_seq(payload: &Payload) -> Option<MetricValue> {
+    payload
+        .to_metrics()
+        .unwrap()
+        .into_iter()
+        .find(|metric| metric.name.as_deref() == Some("bdSeq"))
+        .map(|metric| metric.value)
+}
+
+#[test]
+fn test_dry_run_session() {
+    let (mut publisher, sent) = dry_publisher();
+    assert!(publisher.is_dry_run());
+
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth()).unwrap();
+    publisher.publish_data(&data()).unwrap();
+    publisher.publish_device_birth("Motor01", &birth()).unwrap();
+    publisher.publish_device_data("Motor01", &data()).unwrap();
+    publisher.disconnect().unwrap();
+
+    let sent = sent.lock().unwrap();
+    let topics: Vec<&str> = sent.iter().map(|msg| msg.topic.as_str()).collect();
+    assert_eq!(
+        topics,
+        [
+            "spBv1.0/Energy/NBIRTH/Gateway01",
+            "spBv1.0/Energy/NDATA/Gateway01",
+            "spBv1.0/Energy/DBIRTH/Gateway01/Motor01",
+            "spBv1.0/Energy/DDATA/Gateway01/Motor01",
+            "spBv1.0/Energy/NDEATH/Gateway01",
+        ]
+    );
+
+    let payloads: Vec<Payload> = sent
+        .iter()
+        .map(|msg| msg.parse_payload().unwrap())
+        .collect();
+    let seqs: Vec<Option<u64>> = payloads[..4].iter().map(Payload::seq).collect();
+    assert_eq!(seqs, [Some(0), Some(1), Some(2), Some(3)]);
+    assert!(payloads.iter().all(|payload| payload.timestamp().is_some()));
+    assert_eq!(bd_seq(&payloads[0]), Some(MetricValue::UInt64(0)));
+    assert_eq!(bd_seq(&payloads[4]), Some(MetricValue::UInt64(0)));
+}
+
+#[test]
+fn test_dry_run_bd_seq_advances() {
+    let (mut publisher, sent) = dry_publisher();
+
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth()).unwrap();
+    publisher.rebirth().unwrap();
+    assert_eq!(publisher.bd_seq(), 1);
+
+    publisher.disconnect().unwrap();
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth()).unwrap();
+    assert_eq!(publisher.bd_seq(), 2);
+
+    let births: Vec<Option<MetricValue>> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|msg| msg.topic.contains("/NBIRTH/"))
+        .map(|msg| bd_seq(&msg.parse_payload().unwrap()))
+        .collect();
+    assert_eq!(
+        births,
+        [
+            Some(MetricValue::UInt64(0)),
+            Some(MetricValue::UInt64(1)),
+            Some(MetricValue::UInt64(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_dry_run_rejects_invalid_birth() {
+    let (mut publisher, sent) = dry_publisher();
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_double_with_alias("Pressure", 1, 1.5)
+        .unwrap();
+
+    publisher.connect().unwrap();
+    assert!(matches!(
+        publisher.publish_birth(&birth.serialize().unwrap()),
+        Err(Error::InvalidPayload(_))
+    ));
+    assert!(sent.lock().unwrap().is_empty());
+    assert!(!publisher.is_birthed());
+}
+
+#[test]
+fn test_shutdown_ends_session() {
+    let (mut publisher, sent) = dry_publisher();
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth()).unwrap();
+
+    publisher
+        .shutdown(std::time::Duration::from_secs(1))
+        .unwrap();
+
+    assert!(publisher.is_shut_down());
+    let topics: Vec<String> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|msg| msg.topic.clone())
+        .collect();
+    assert_eq!(
+        topics,
+        [
+            "spBv1.0/Energy/NBIRTH/Gateway01",
+            "spBv1.0/Energy/NDEATH/Gateway01",
+        ]
+    );
+    assert!(matches!(
+        publisher.publish_data(&data()),
+        Err(Error::InvalidState { .. })
+    ));
+    assert!(matches!(
+        publisher.connect(),
+        Err(Error::InvalidState { .. })
+    ));
+}
+
+#[test]
+fn test_dry_run_reconnect_republishes_births() {
+    let (mut publisher, sent) = dry_publisher();
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth()).unwrap();
+    publisher.publish_device_birth("Motor01", &birth()).unwrap();
+    sent.lock().unwrap().clear();
+
+    publisher.reconnect().unwrap();
+
+    let sent = sent.lock().unwrap();
+    let topics: Vec<&str> = sent.iter().map(|msg| msg.topic.as_str()).collect();
+    assert_eq!(
+        topics,
+        [
+            "spBv1.0/Energy/NBIRTH/Gateway01",
+            "spBv1.0/Energy/DBIRTH/Gateway01/Motor01",
+        ]
+    );
+    assert_eq!(publisher.bd_seq(), 1);
+}
+
+#[test]
+fn test_publish_changed_handles_nan_and_large_integers() {
+    let mut config = PublisherConfig::new("tcp://localhost:1883", "dry", "Energy", "Gateway01");
+    config.deadband = 0.5;
+    let mut publisher = Publisher::dry_run(config, Box::new(|_| {})).unwrap();
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_int64_with_alias("Energy", 2, 0)
+        .unwrap();
+    publisher.connect().unwrap();
+    publisher
+        .publish_birth(&birth.serialize().unwrap())
+        .unwrap();
+
+    let nan = [(1u64, MetricValue::Double(f64::NAN))];
+    assert_eq!(publisher.publish_changed(&nan).unwrap(), 1);
+    assert_eq!(publisher.publish_changed(&nan).unwrap(), 0);
+    let recovered = [(1u64, MetricValue::Double(20.5))];
+    assert_eq!(publisher.publish_changed(&recovered).unwrap(), 1);
+
+    let big = 1i64 << 53;
+    let energy = |v: i64| [(2u64, MetricValue::Int64(v))];
+    assert_eq!(publisher.publish_changed(&energy(big)).unwrap(), 1);
+    assert_eq!(publisher.publish_changed(&energy(big + 1)).unwrap(), 1);
+    assert_eq!(publisher.publish_changed(&energy(big + 1)).unwrap(), 0);
+}
+
+fn backlog_record(out: &mut Vec<u8>, queued_at: u64, device_id: Option<&str>, payload: &[u8]) {
+    out.extend_from_slice(&queued_at.to_le_bytes());
+    match device_id {
+        None => out.push(0),
+        Some(device_id) => {
+            out.push(1);
+            out.extend_from_slice(&(device_id.len() as u32).to_le_bytes());
+            out.extend_from_slice(device_id.as_bytes());
+        }
+    }
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+}
+
+fn backlog_header(head: u64, end: u64) -> Vec<u8> {
+    let mut header = head.to_le_bytes().to_vec();
+    header.extend_from_slice(&end.to_le_bytes());
+    header
+}
+
+fn backlog_publisher(path: &std::path::Path) -> (Publisher, Arc<Mutex<Vec<Message>>>) {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    let config = PublisherConfig::builder()
+        .broker("tcp://localhost:1883")
+        .client_id("dry")
+        .group_id("Energy")
+        .edge_node_id("Gateway01")
+        .store_and_forward(10)
+        .backlog_file(path)
+        .backlog_max_age(std::time::Duration::from_secs(3600))
+        .build()
+        .unwrap();
+    let publisher = Publisher::dry_run(
+        config,
+        Box::new(move |msg: Message| sink.lock().unwrap().push(msg)),
+    )
+    .unwrap();
+    (publisher, sent)
+}
+
+#[test]
+fn test_backlog_file_is_sent_after_restart() {
+    let path = std::env::temp_dir().join(format!("sparkplug-backlog-{}", std::process::id()));
+    let mut records = Vec::new();
+    backlog_record(&mut records, 0, Some("Motor01"), &data());
+    backlog_record(
+        &mut records,
+        sparkplug_rs::time::now_millis(),
+        None,
+        &data(),
+    );
+    let mut file = backlog_header(16, 16 + records.len() as u64);
+    file.extend_from_slice(&records);
+    // An append cut short by a crash, before the end offset moved
+    file.extend_from_slice(&[1, 2, 3]);
+    std::fs::write(&path, &file).unwrap();
+
+    let (mut publisher, sent) = backlog_publisher(&path);
+    assert_eq!(publisher.backlog_len(), 2);
+
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth()).unwrap();
+    assert_eq!(publisher.flush_backlog().unwrap(), 1);
+    assert_eq!(publisher.backlog_len(), 0);
+    assert_eq!(publisher.backlog_dropped(), 1);
+    assert_eq!(
+        sent.lock().unwrap().last().unwrap().topic,
+        "spBv1.0/Energy/NDATA/Gateway01"
+    );
+
+    let remaining = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(remaining, backlog_header(16, 16));
+}
+
+#[test]
+fn test_backlog_file_survives_interrupted_compaction() {
+    let path = std::env::temp_dir().join(format!(
+        "sparkplug-backlog-compaction-{}",
+        std::process::id()
+    ));
+    let now = sparkplug_rs::time::now_millis();
+    let mut queued = Vec::new();
+    backlog_record(&mut queued, now, Some("Motor01"), &data());
+    // The queued record was copied over the sent ones and the header was
+    // rewritten, but the file was not truncated: sent records follow it.
+    let mut file = backlog_header(16, 16 + queued.len() as u64);
+    file.extend_from_slice(&queued);
+    let mut stale = Vec::new();
+    backlog_record(&mut stale, now, None, &data());
+    backlog_record(&mut stale, now, Some("Motor01"), &data());
+    file.extend_from_slice(&stale[5..]);
+    std::fs::write(&path, &file).unwrap();
+
+    let (publisher, _) = backlog_publisher(&path);
+    assert_eq!(publisher.backlog_len(), 1);
+    drop(publisher);
+
+    let remaining = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(remaining, file[..16 + queued.len()]);
+}
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn metric(name: Option<&str>, alias: u64, value: i8) -> Vec<u8> {
+    let mut metric = Vec::new();
+    if let Some(name) = name {
+        varint(&mut metric, 1 << 3 | 2);
+        varint(&mut metric, name.len() as u64);
+        metric.extend_from_slice(name.as_bytes());
+    }
+    for (field, value) in [(2, alias), (4, DataType::Int8 as u64), (10, value as u64)] {
+        varint(&mut metric, field << 3);
+        varint(&mut metric, value);
+    }
+    let mut payload = Vec::new();
+    varint(&mut payload, 2 << 3 | 2);
+    varint(&mut payload, metric.len() as u64);
+    payload.extend_from_slice(&metric);
+    payload
+}
+
+#[test]
+fn test_dry_run_accepts_payloads_the_builder_cannot_copy() {
+    let (mut publisher, sent) = dry_publisher();
+    publisher.connect().unwrap();
+    // Int8 metrics with an alias, which PayloadBuilder::add_metric rejects
+    publisher
+        .publish_birth(&metric(Some("Mode"), 2, 3))
+        .unwrap();
+    publisher.publish_data(&metric(None, 2, -4)).unwrap();
+
+    let sent = sent.lock().unwrap();
+    let birth = Payload::parse(&sent[0].payload_data).unwrap();
+    assert_eq!(birth.seq(), Some(0));
+    assert!(birth.timestamp().is_some());
+    assert_eq!(bd_seq(&birth), Some(MetricValue::UInt64(0)));
+    let mode = birth.metric_at(0).unwrap();
+    assert_eq!(mode.name.as_deref(), Some("Mode"));
+    assert_eq!(mode.alias, Some(MetricAlias::new(2)));
+    assert_eq!(mode.value, MetricValue::Int8(3));
+
+    let data = Payload::parse(&sent[1].payload_data).unwrap();
+    assert_eq!(data.seq(), Some(1));
+    assert_eq!(data.metric_count(), 1);
+    assert_eq!(data.metric_at(0).unwrap().value, MetricValue::Int8(-4));
+}
@@ -0,0 +1,91 @@
+//! Tests for CSV export
+
+use sparkplug_rs::{CsvWriter, DataType, Metric, MetricAlias, MetricValue, ParsedTopic};
+
+fn metric(name: Option<&str>, value: MetricValue, datatype: DataType) -> Metric {
+    Metric {
+        name: name.map(|n| n.to_string()),
+        alias: Some(MetricAlias::new(7)),
+        timestamp: None,
+        datatype,
+        value,
+    }
+}
+
+fn output(csv: CsvWriter<Vec<u8>>) -> String {
+    String::from_utf8(csv.into_inner()).unwrap()
+}
+
+#[test]
+fn test_csv_header() {
+    let mut csv = CsvWriter::new(Vec::new());
+    csv.write_header().unwrap();
+    assert_eq!(
+        output(csv),
+        "timestamp,group,node,device,metric,type,value\n"
+    );
+}
+
+#[test]
+fn test_csv_device_metric_row() {
+    let topic = ParsedTopic::parse("spBv1.0/Energy/DDATA/Gateway01/Meter01").unwrap();
+    let mut csv = CsvWriter::new(Vec::new());
+    csv.write_metric(
+        &topic,
+        &metric(Some("Power"), MetricValue::Double(1.5), DataType::Double),
+        Some(1000),
+    )
+    .unwrap();
+    assert_eq!(
+        output(csv),
+        "1000,Energy,Gateway01,Meter01,Power,Double,1.5\n"
+    );
+}
+
+#[test]
+fn test_csv_metric_timestamp_wins_over_fallback() {
+    let topic = ParsedTopic::parse("spBv1.0/Energy/NDATA/Gateway01").unwrap();
+    let mut m = metric(
+        Some("Active"),
+        MetricValue::Boolean(true),
+        DataType::Boolean,
+    );
+    m.timestamp = Some(42);
+
+    let mut csv = CsvWriter::new(Vec::new());
+    csv.write_metric(&topic, &m, Some(1000)).unwrap();
+    assert_eq!(output(csv), "42,Energy,Gateway01,,Active,Boolean,true\n");
+}
+
+#[test]
+fn test_csv_alias_only_and_null() {
+    let topic = ParsedTopic::parse("spBv1.0/Energy/NDATA/Gateway01").unwrap();
+    let mut csv = CsvWriter::new(Vec::new());
+    csv.write_metric(
+        &topic,
+        &metric(None, MetricValue::Null, DataType::Int32),
+        None,
+    )
+    .unwrap();
+    assert_eq!(output(csv), ",Energy,Gateway01,,7,Int32,\n");
+}
+
+#[test]
+fn test_csv_escapes_special_characters() {
+    let topic = ParsedTopic::parse("spBv1.0/Energy/NDATA/Gateway01").unwrap();
+    let mut csv = CsvWriter::new(Vec::new());
+    csv.write_metric(
+        &topic,
+        &metric(
+            Some("Status, text"),
+            MetricValue::String("say \"hi\"".to_string()),
+            DataType::String,
+        ),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        output(csv),
+        ",Energy,Gateway01,,\"Status, text\",String,\"say \"\"hi\"\"\"\n"
+    );
+}
@@ -0,0 +1,94 @@
+//! Tests for content-based message filtering
+
+use sparkplug_rs::filter::{accepts, MessageFilter};
+use sparkplug_rs::{Message, MessageType, PayloadBuilder};
+
+fn message(topic: &str, temperature: f64) -> Message {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.add_double("Temperature", temperature).unwrap();
+    Message {
+        topic: topic.to_string(),
+        payload_data: builder.serialize().unwrap(),
+    }
+}
+
+#[test]
+fn test_no_filters_accepts_everything() {
+    let msg = Message {
+        topic: "not a topic".to_string(),
+        payload_data: vec![0xff],
+    };
+    assert!(accepts(&[], &msg));
+}
+
+#[test]
+fn test_topic_filters() {
+    let ndata = message("spBv1.0/Energy/NDATA/Gateway01", 20.0);
+    let nbirth = message("spBv1.0/Energy/NBIRTH/Gateway02", 20.0);
+    let filters = [
+        MessageFilter::MessageTypes(vec![MessageType::NData]),
+        MessageFilter::EdgeNodes(vec!["Gateway01".to_string()]),
+    ];
+
+    assert!(accepts(&filters, &ndata));
+    assert!(!accepts(&filters, &nbirth));
+}
+
+#[test]
+fn test_topic_filters_skip_payload_decoding() {
+    let msg = Message {
+        topic: "spBv1.0/Energy/NDATA/Gateway01".to_string(),
+        payload_data: vec![0xff, 0xff],
+    };
+    assert!(accepts(
+        &[MessageFilter::MessageTypes(vec![MessageType::NData])],
+        &msg
+    ));
+    assert!(!accepts(
+        &[MessageFilter::HasMetric("Temperature".to_string())],
+        &msg
+    ));
+}
+
+#[test]
+fn test_metric_filters() {
+    let topic = "spBv1.0/Energy/NDATA/Gateway01";
+    let hot = message(topic, 90.0);
+    let cold = message(topic, 10.0);
+    let above = [MessageFilter::MetricAbove {
+        name: "Temperature".to_string(),
+        threshold: 80.0,
+    }];
+    let below = [MessageFilter::MetricBelow {
+        name: "Temperature".to_string(),
+        threshold: 80.0,
+    }];
+
+    assert!(accepts(
+        &[MessageFilter::HasMetric("Temperature".to_string())],
+        &hot
+    ));
+    assert!(!accepts(
+        &[MessageFilter::HasMetric("Pressure".to_string())],
+        &hot
+    ));
+    assert!(accepts(&above, &hot));
+    assert!(!accepts(&above, &cold));
+    assert!(accepts(&below, &cold));
+}
+
+#[test]
+fn test_custom_payload_predicate() {
+    let filters = [MessageFilter::Payload(Box::new(|topic, payload| {
+        topic.device_id() == Some("Meter") && payload.metric_count() == 1
+    }))];
+
+    assert!(accepts(
+        &filters,
+        &message("spBv1.0/Energy/DDATA/Gateway01/Meter", 1.0)
+    ));
+    assert!(!accepts(
+        &filters,
+        &message("spBv1.0/Energy/NDATA/Gateway01", 1.0)
+    ));
+}
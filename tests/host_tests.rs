@@ -0,0 +1,87 @@
+//! Tests for Host Application support
+
+use sparkplug_rs::{Error, HostApplication, HostApplicationConfig, Message, StatePayload};
+
+#[test]
+fn test_host_config_creation() {
+    let config = HostApplicationConfig::new(
+        "tcp://localhost:1883",
+        "scada",
+        "SCADA01",
+        ["Energy", "Water"],
+    );
+
+    assert_eq!(config.host_id, "SCADA01");
+    assert_eq!(config.groups, vec!["Energy", "Water"]);
+    assert!(config.reconnect.is_none());
+}
+
+#[test]
+fn test_host_requires_a_group() {
+    let config = HostApplicationConfig::new(
+        "tcp://localhost:1883",
+        "scada",
+        "SCADA01",
+        Vec::<String>::new(),
+    );
+    assert!(matches!(
+        HostApplication::new(config),
+        Err(Error::CreateFailed { .. })
+    ));
+}
+
+#[test]
+fn test_host_commands_are_scoped_to_groups() {
+    let config = HostApplicationConfig::new("tcp://localhost:1883", "scada", "SCADA01", ["Energy"]);
+    let mut host = HostApplication::new(config).unwrap();
+
+    assert_eq!(host.host_id(), "SCADA01");
+    assert_eq!(host.groups().collect::<Vec<_>>(), vec!["Energy"]);
+    assert_eq!(host.state_timestamp(), None);
+    assert!(!host.is_online());
+    assert!(matches!(
+        host.request_rebirth("Water", "Gateway01"),
+        Err(Error::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_state_payload_formats() {
+    let state = StatePayload::parse(br#"{"online":true,"timestamp":1700000000000}"#).unwrap();
+    assert_eq!(
+        state,
+        StatePayload {
+            online: true,
+            timestamp: Some(1700000000000),
+        }
+    );
+
+    let state = StatePayload::parse(br#" { "timestamp" : 42, "online" : false } "#).unwrap();
+    assert!(!state.online);
+    assert_eq!(state.timestamp, Some(42));
+
+    assert!(StatePayload::parse(b"ONLINE").unwrap().online);
+    assert_eq!(StatePayload::parse(b"OFFLINE").unwrap().timestamp, None);
+
+    let message = Message {
+        topic: "spBv1.0/STATE/SCADA01".to_string(),
+        payload_data: br#"{"online":false,"timestamp":7}"#.to_vec(),
+    };
+    assert!(!message.parse_state().unwrap().online);
+}
+
+#[test]
+fn test_state_payload_rejects_other_content() {
+    for data in [
+        &b"online"[..],
+        br#"{"timestamp":1}"#,
+        br#"{"online":"yes","timestamp":1}"#,
+        br#"{"online":true,"timestamp":-1}"#,
+        b"",
+    ] {
+        assert!(matches!(
+            StatePayload::parse(data),
+            Err(Error::InvalidStatePayload(_))
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+//! Tests for metric name interning
+
+use sparkplug_rs::intern::NameCache;
+use sparkplug_rs::{MetricValue, Payload, PayloadBuilder};
+use std::sync::Arc;
+
+fn payload(value: i32) -> Payload {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder
+        .add_int32("Motor/Speed", value)
+        .unwrap()
+        .add_bool("Motor/Running", true)
+        .unwrap();
+    Payload::parse(&builder.serialize().unwrap()).unwrap()
+}
+
+#[test]
+fn test_intern_returns_shared_name() {
+    let mut cache = NameCache::new();
+    let a = cache.intern("Motor/Speed");
+    let b = cache.intern("Motor/Speed");
+
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_metrics_interned_share_names_across_payloads() {
+    let mut cache = NameCache::new();
+    let first: Vec<_> = payload(1)
+        .metrics_interned(&mut cache)
+        .map(|m| m.unwrap())
+        .collect();
+    let second: Vec<_> = payload(2)
+        .metrics_interned(&mut cache)
+        .map(|m| m.unwrap())
+        .collect();
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(first[0].name.as_deref(), Some("Motor/Speed"));
+    assert_eq!(second[0].value, MetricValue::Int32(2));
+    assert!(Arc::ptr_eq(
+        first[0].name.as_ref().unwrap(),
+        second[0].name.as_ref().unwrap()
+    ));
+}
+
+#[test]
+fn test_shrink_drops_unused_names() {
+    let mut cache = NameCache::new();
+    let kept = cache.intern("Kept");
+    cache.intern("Dropped");
+
+    cache.shrink();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.intern("Kept"), kept);
+}
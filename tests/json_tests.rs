@@ -0,0 +1,94 @@
+//! Tests for Sparkplug JSON conversion in both directions
+
+#![cfg(feature = "json")]
+
+use serde_json::json;
+use sparkplug_rs::{Error, Payload, PayloadBuilder};
+
+#[test]
+fn test_payload_to_json() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.set_timestamp(1_700_000_000_000).set_seq(3);
+    builder
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_string("Serial", "P-101")
+        .unwrap();
+    builder.add_bool_by_alias(2, true);
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+
+    assert_eq!(
+        payload.to_json_value().unwrap(),
+        json!({
+            "timestamp": 1_700_000_000_000u64,
+            "seq": 3,
+            "metrics": [
+                { "name": "Temperature", "alias": 1, "dataType": "Double", "value": 20.5 },
+                { "name": "Serial", "dataType": "String", "value": "P-101" },
+                { "alias": 2, "dataType": "Boolean", "value": true }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_metric_to_json_string() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.add_int32("Count", -4).unwrap();
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    let metric = payload.metric_at(0).unwrap();
+
+    let parsed: serde_json::Value = serde_json::from_str(&metric.to_json()).unwrap();
+    assert_eq!(
+        parsed,
+        json!({ "name": "Count", "dataType": "Int32", "value": -4 })
+    );
+}
+
+#[test]
+fn test_payload_from_json_round_trip() {
+    let json = json!({
+        "timestamp": 1_700_000_000_000u64,
+        "seq": 3,
+        "metrics": [
+            { "name": "Temperature", "alias": 1, "dataType": "Double", "value": 20.5 },
+            { "name": "Mode", "dataType": "Int8", "value": -2 },
+            { "name": "Count", "value": 7 },
+            { "alias": 2, "dataType": "Boolean", "value": true }
+        ]
+    });
+
+    let builder = PayloadBuilder::from_json(&json.to_string()).unwrap();
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+
+    assert_eq!(
+        payload.to_json_value().unwrap(),
+        json!({
+            "timestamp": 1_700_000_000_000u64,
+            "seq": 3,
+            "metrics": [
+                { "name": "Temperature", "alias": 1, "dataType": "Double", "value": 20.5 },
+                { "name": "Mode", "dataType": "Int8", "value": -2 },
+                { "name": "Count", "dataType": "Int64", "value": 7 },
+                { "alias": 2, "dataType": "Boolean", "value": true }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_payload_from_invalid_json() {
+    for input in [
+        "not json",
+        "[]",
+        r#"{"metrics": {}}"#,
+        r#"{"metrics": [{"name": "X", "dataType": "Int8", "value": 1000}]}"#,
+        r#"{"metrics": [{"name": "X", "dataType": "Bogus", "value": 1}]}"#,
+    ] {
+        assert!(
+            matches!(PayloadBuilder::from_json(input), Err(Error::InvalidJson(_))),
+            "{}",
+            input
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Tests for latency and clock-skew measurement
+
+use sparkplug_rs::latency::SkewWarning;
+use sparkplug_rs::time::from_millis;
+use sparkplug_rs::{LatencyTracker, Message, PayloadBuilder};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn data(topic: &str, timestamp: Option<u64>) -> Message {
+    let mut builder = PayloadBuilder::new().unwrap();
+    if let Some(ts) = timestamp {
+        builder.set_timestamp(ts);
+    }
+    builder.add_int32("Value", 1).unwrap();
+    Message {
+        topic: topic.to_string(),
+        payload_data: builder.serialize().unwrap(),
+    }
+}
+
+#[test]
+fn test_latency_statistics() {
+    let mut tracker = LatencyTracker::new();
+    let topic = "spBv1.0/Energy/NDATA/Gateway01";
+
+    let d1 = tracker
+        .record_at(&data(topic, Some(10_000)), from_millis(10_050))
+        .unwrap();
+    let d2 = tracker
+        .record_at(&data(topic, Some(20_000)), from_millis(19_950))
+        .unwrap();
+
+    assert_eq!(d1, Some(50));
+    assert_eq!(d2, Some(-50));
+    let stats = tracker.get("Energy", "Gateway01").unwrap();
+    assert_eq!(stats.count, 2);
+    assert_eq!(stats.last_ms, -50);
+    assert_eq!(stats.min_ms, -50);
+    assert_eq!(stats.max_ms, 50);
+    assert_eq!(stats.mean_ms, 0.0);
+}
+
+#[test]
+fn test_device_messages_count_for_node() {
+    let mut tracker = LatencyTracker::new();
+    tracker
+        .record_at(
+            &data("spBv1.0/Energy/DDATA/Gateway01/Meter", Some(1_000)),
+            from_millis(1_010),
+        )
+        .unwrap();
+
+    assert_eq!(tracker.get("Energy", "Gateway01").unwrap().count, 1);
+}
+
+#[test]
+fn test_untimestamped_messages_are_ignored() {
+    let mut tracker = LatencyTracker::new();
+    let delta = tracker
+        .record_at(
+            &data("spBv1.0/Energy/NDATA/Gateway01", None),
+            from_millis(1_000),
+        )
+        .unwrap();
+
+    assert_eq!(delta, None);
+    assert!(tracker.get("Energy", "Gateway01").is_none());
+}
+
+#[test]
+fn test_skew_warning() {
+    let warnings: Arc<Mutex<Vec<SkewWarning>>> = Arc::new(Mutex::new(Vec::new()));
+    let warnings_clone = warnings.clone();
+
+    let mut tracker = LatencyTracker::new();
+    tracker.on_skew(
+        Duration::from_secs(1),
+        Box::new(move |w| warnings_clone.lock().unwrap().push(w.clone())),
+    );
+    let topic = "spBv1.0/Energy/NDATA/Gateway01";
+    tracker
+        .record_at(&data(topic, Some(10_000)), from_millis(10_500))
+        .unwrap();
+    tracker
+        .record_at(&data(topic, Some(20_000)), from_millis(17_000))
+        .unwrap();
+
+    let warnings = warnings.lock().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].delta_ms, -3_000);
+    assert_eq!(warnings[0].edge_node_id, "Gateway01");
+}
@@ -0,0 +1,200 @@
+//! Tests for routing a dry-run publisher to a subscriber in memory
+
+#![cfg(feature = "test-util")]
+
+use sparkplug_rs::{
+    Message, MessageType, MetricValue, PayloadBuilder, Publisher, PublisherConfig, Quality,
+    Subscriber, SubscriberConfig,
+};
+use std::sync::{Arc, Mutex};
+
+fn recording_host(store_values: bool) -> (Subscriber, Arc<Mutex<Vec<String>>>) {
+    let topics = Arc::new(Mutex::new(Vec::new()));
+    let sink = topics.clone();
+    let mut config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    config.store_values = store_values;
+    let subscriber = Subscriber::new(
+        config,
+        Box::new(move |msg: Message| sink.lock().unwrap().push(msg.topic)),
+    )
+    .unwrap();
+    (subscriber, topics)
+}
+
+#[test]
+fn test_published_messages_reach_subscriber() {
+    let (host, topics) = recording_host(true);
+    let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    let mut edge = Publisher::dry_run(config, host.sink()).unwrap();
+
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_double_with_alias("Temperature", 1, 20.5).unwrap();
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 21.0);
+
+    edge.connect().unwrap();
+    edge.publish_birth(&birth.serialize().unwrap()).unwrap();
+    edge.publish_data(&data.serialize().unwrap()).unwrap();
+
+    let sample = host
+        .value("Energy", "Gateway01", None, "Temperature")
+        .unwrap();
+    assert_eq!(sample.value, MetricValue::Double(21.0));
+
+    edge.disconnect().unwrap();
+    assert_eq!(
+        *topics.lock().unwrap(),
+        [
+            "spBv1.0/Energy/NBIRTH/Gateway01",
+            "spBv1.0/Energy/NDATA/Gateway01",
+            "spBv1.0/Energy/NDEATH/Gateway01",
+        ]
+    );
+    let sample = host
+        .value("Energy", "Gateway01", None, "Temperature")
+        .unwrap();
+    assert_eq!(sample.quality, Quality::Stale);
+}
+
+#[test]
+fn test_commands_reach_command_callback() {
+    let (mut edge, topics) = recording_host(false);
+    let commands = Arc::new(Mutex::new(Vec::new()));
+    let sink = commands.clone();
+    edge.set_command_callback(Box::new(move |msg: Message| {
+        sink.lock().unwrap().push(msg.topic)
+    }))
+    .unwrap();
+
+    let config = PublisherConfig::new("tcp://localhost:1883", "scada", "Energy", "Scada");
+    let mut host = Publisher::dry_run(config, edge.sink()).unwrap();
+    let mut rebirth = PayloadBuilder::new().unwrap();
+    rebirth.add_bool("Node Control/Rebirth", true).unwrap();
+
+    host.connect().unwrap();
+    host.publish_node_command("Gateway01", &rebirth.serialize().unwrap())
+        .unwrap();
+
+    let expected = ["spBv1.0/Energy/NCMD/Gateway01"];
+    assert_eq!(*commands.lock().unwrap(), expected);
+    assert_eq!(*topics.lock().unwrap(), expected);
+}
+
+#[test]
+fn test_command_callback_can_publish_to_its_subscriber() {
+    let (mut node, topics) = recording_host(false);
+    let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    let edge = Arc::new(Mutex::new(Publisher::dry_run(config, node.sink()).unwrap()));
+    node.set_command_callback(Publisher::rebirth_on_command(edge.clone()))
+        .unwrap();
+
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_double_with_alias("Temperature", 1, 20.5).unwrap();
+    {
+        let mut edge = edge.lock().unwrap();
+        edge.connect().unwrap();
+        edge.publish_birth(&birth.serialize().unwrap()).unwrap();
+    }
+
+    let config = PublisherConfig::new("tcp://localhost:1883", "scada", "Energy", "Scada");
+    let mut host = Publisher::dry_run(config, node.sink()).unwrap();
+    let mut rebirth = PayloadBuilder::new().unwrap();
+    rebirth.add_bool("Node Control/Rebirth", true).unwrap();
+    host.connect().unwrap();
+    host.publish_node_command("Gateway01", &rebirth.serialize().unwrap())
+        .unwrap();
+
+    // The NBIRTH published from the command callback is delivered once the
+    // callback has returned.
+    assert_eq!(
+        *topics.lock().unwrap(),
+        [
+            "spBv1.0/Energy/NBIRTH/Gateway01",
+            "spBv1.0/Energy/NCMD/Gateway01",
+            "spBv1.0/Energy/NBIRTH/Gateway01",
+        ]
+    );
+}
+
+#[test]
+fn test_aliases_are_resolved_per_group_until_death() {
+    let (host, _) = recording_host(false);
+    let mut edges = Vec::new();
+    for (group, name) in [("Energy", "Temperature"), ("Water", "Flow")] {
+        let config = PublisherConfig::new("tcp://localhost:1883", "edge", group, "Gateway01");
+        let mut edge = Publisher::dry_run(config, host.sink()).unwrap();
+        let mut birth = PayloadBuilder::new().unwrap();
+        birth.add_double_with_alias(name, 1, 20.5).unwrap();
+        edge.connect().unwrap();
+        edge.publish_birth(&birth.serialize().unwrap()).unwrap();
+        edges.push(edge);
+    }
+    let mut dbirth = PayloadBuilder::new().unwrap();
+    dbirth.add_bool_with_alias("Running", 2, true).unwrap();
+    edges[0]
+        .publish_device_birth("Pump01", &dbirth.serialize().unwrap())
+        .unwrap();
+
+    let resolve = |group, device, alias: u64| host.resolve_alias(group, "Gateway01", device, alias);
+    assert_eq!(resolve("Energy", None, 1).as_deref(), Some("Temperature"));
+    assert_eq!(resolve("Water", None, 1).as_deref(), Some("Flow"));
+    assert_eq!(
+        resolve("Energy", Some("Pump01"), 2).as_deref(),
+        Some("Running")
+    );
+
+    edges[0].publish_device_death("Pump01").unwrap();
+    assert_eq!(resolve("Energy", Some("Pump01"), 2), None);
+    assert_eq!(resolve("Energy", None, 1).as_deref(), Some("Temperature"));
+
+    edges[0]
+        .publish_device_birth("Pump01", &dbirth.serialize().unwrap())
+        .unwrap();
+    edges[0].disconnect().unwrap();
+    assert_eq!(resolve("Energy", None, 1), None);
+    assert_eq!(resolve("Energy", Some("Pump01"), 2), None);
+    assert_eq!(resolve("Water", None, 1).as_deref(), Some("Flow"));
+}
+
+#[test]
+fn test_parsed_callback_shares_payload_with_store_and_aliases() {
+    let parsed = Arc::new(Mutex::new(Vec::new()));
+    let sink = parsed.clone();
+    let mut config = SubscriberConfig::new("tcp://localhost:1883", "host", "Energy");
+    config.store_values = true;
+    let host = Subscriber::with_parsed_callback(
+        config,
+        Box::new(move |topic, payload| {
+            let value = payload.metric_at(0).unwrap().value;
+            sink.lock().unwrap().push((topic.message_type(), value));
+        }),
+    )
+    .unwrap();
+    let config = PublisherConfig::new("tcp://localhost:1883", "edge", "Energy", "Gateway01");
+    let mut edge = Publisher::dry_run(config, host.sink()).unwrap();
+
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_double_with_alias("Temperature", 1, 20.5).unwrap();
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 21.0);
+    edge.connect().unwrap();
+    edge.publish_birth(&birth.serialize().unwrap()).unwrap();
+    edge.publish_data(&data.serialize().unwrap()).unwrap();
+
+    assert_eq!(
+        *parsed.lock().unwrap(),
+        [
+            (Some(MessageType::NBirth), MetricValue::Double(20.5)),
+            (Some(MessageType::NData), MetricValue::Double(21.0)),
+        ]
+    );
+    let sample = host
+        .value("Energy", "Gateway01", None, "Temperature")
+        .unwrap();
+    assert_eq!(sample.value, MetricValue::Double(21.0));
+    assert_eq!(
+        host.resolve_alias("Energy", "Gateway01", None, 1u64)
+            .as_deref(),
+        Some("Temperature")
+    );
+}
@@ -1,6 +1,6 @@
 //! Tests for PayloadBuilder and Payload parsing
 
-use sparkplug_rs::{Error, PayloadBuilder};
+use sparkplug_rs::{DataType, Error, Metric, MetricAlias, MetricValue, PayloadBuilder};
 
 #[test]
 fn test_payload_builder_creation() {
@@ -128,6 +128,19 @@ fn test_payload_round_trip() {
     assert_eq!(payload.metric_count(), 4, "Should have 4 metrics");
 }
 
+#[test]
+fn test_parsed_metrics_are_not_null() {
+    use sparkplug_rs::Payload;
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.add_int32_with_alias("metric1", 1, 0).unwrap();
+
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    let metric = payload.metric_at(0).unwrap();
+    assert!(!metric.is_null(), "Zero value must not be reported as null");
+    assert_eq!(metric.value, MetricValue::Int32(0));
+}
+
 #[test]
 fn test_payload_parse_invalid_data() {
     use sparkplug_rs::Payload;
@@ -246,3 +259,451 @@ fn test_unicode_strings() {
     let bytes = builder.serialize();
     assert!(bytes.is_ok(), "Should handle Unicode strings");
 }
+
+#[test]
+fn test_payload_to_builder_round_trip() {
+    use sparkplug_rs::Payload;
+
+    let mut original = PayloadBuilder::new().unwrap();
+    original.set_timestamp(1_700_000_000_000).set_seq(7);
+    original
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_string("Serial", "P-101")
+        .unwrap()
+        .add_int8("Mode", -3)
+        .unwrap();
+    original.add_bool_by_alias(2, true);
+    let payload = Payload::parse(&original.serialize().unwrap()).unwrap();
+
+    let (builder, skipped) = payload.to_builder().unwrap();
+    assert!(skipped.is_empty());
+    let copy = Payload::parse(&builder.serialize().unwrap()).unwrap();
+
+    assert_eq!(copy.timestamp(), Some(1_700_000_000_000));
+    assert_eq!(copy.seq(), Some(7));
+    assert_eq!(copy.metric_count(), 4);
+    let metrics: Vec<_> = copy.metrics().map(|m| m.unwrap()).collect();
+    assert_eq!(metrics[0].name.as_deref(), Some("Temperature"));
+    assert_eq!(metrics[0].alias, Some(MetricAlias::new(1)));
+    assert_eq!(metrics[0].value, MetricValue::Double(20.5));
+    assert_eq!(metrics[1].value, MetricValue::String("P-101".to_string()));
+    assert_eq!(metrics[2].value, MetricValue::Int8(-3));
+    assert_eq!(metrics[3].name, None);
+    assert_eq!(metrics[3].alias, Some(MetricAlias::new(2)));
+}
+
+#[test]
+fn test_add_metric_rejects_unsupported_combinations() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    let mut metric = Metric {
+        name: None,
+        alias: Some(MetricAlias::new(5)),
+        timestamp: None,
+        datatype: DataType::String,
+        value: MetricValue::String("x".to_string()),
+    };
+    assert!(matches!(
+        builder.add_metric(&metric),
+        Err(Error::Unsupported(_))
+    ));
+
+    metric.value = MetricValue::Null;
+    metric.datatype = DataType::Unknown;
+    metric.name = Some("Gone".to_string());
+    assert!(matches!(
+        builder.add_metric(&metric),
+        Err(Error::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_borrowed_metric_views() {
+    use sparkplug_rs::{MetricValueRef, Payload};
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder
+        .add_string("Serial", "P-101")
+        .unwrap()
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap();
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+
+    let views: Vec<_> = payload.metrics_ref().map(|m| m.unwrap()).collect();
+    assert_eq!(views.len(), 2);
+    assert_eq!(views[0].name, Some("Serial"));
+    assert_eq!(views[0].value, MetricValueRef::String("P-101"));
+    assert_eq!(views[0].value.as_str(), Some("P-101"));
+    assert_eq!(views[1].value.as_f64(), Some(20.5));
+
+    let owned: Vec<_> = payload.metrics().map(|m| m.unwrap()).collect();
+    assert_eq!(views[1].to_metric().value, owned[1].value);
+    assert_eq!(views[0].value.to_value(), owned[0].value);
+}
+
+#[test]
+fn test_serialize_payload_larger_than_64k() {
+    use sparkplug_rs::Payload;
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    let prefix = "Plant/Area/Line/Cell/".repeat(10);
+    for i in 0..400 {
+        builder
+            .add_double_with_alias(&format!("{}{}", prefix, i), i, i as f64)
+            .unwrap();
+    }
+
+    let bytes = builder.serialize().unwrap();
+    assert!(bytes.len() > 65536);
+    assert_eq!(Payload::parse(&bytes).unwrap().metric_count(), 400);
+}
+
+#[test]
+fn test_serialize_long_string_value() {
+    use sparkplug_rs::{MetricValue, Payload};
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    let text = "x".repeat(1 << 20);
+    builder.add_string("Log", &text).unwrap();
+
+    let bytes = builder.serialize().unwrap();
+    let payload = Payload::parse(&bytes).unwrap();
+    let metric = payload.metric_at(0).unwrap();
+    assert_eq!(metric.value, MetricValue::String(text));
+}
+
+#[test]
+fn test_serialize_into_reuses_buffer() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.add_int32("Value", 1).unwrap();
+
+    let mut buffer = Vec::new();
+    let len = builder.serialize_into(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), len);
+    assert_eq!(buffer, builder.serialize().unwrap());
+
+    let capacity = buffer.capacity();
+    builder.serialize_into(&mut buffer).unwrap();
+    assert_eq!(buffer.capacity(), capacity);
+}
+
+#[test]
+fn test_serialized_size_matches_serialize() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_string("Serial", "P-101")
+        .unwrap();
+
+    assert_eq!(
+        builder.serialized_size().unwrap(),
+        builder.serialize().unwrap().len()
+    );
+}
+
+#[test]
+fn test_merge_later_metrics_win() {
+    use sparkplug_rs::Payload;
+
+    let mut first = PayloadBuilder::new().unwrap();
+    first
+        .set_seq(1)
+        .add_double_with_alias("Temperature", 1, 20.0)
+        .unwrap()
+        .add_int32("Count", 1)
+        .unwrap();
+    let first = Payload::parse(&first.serialize().unwrap()).unwrap();
+
+    let mut second = PayloadBuilder::new().unwrap();
+    second
+        .set_seq(2)
+        .add_double_by_alias(1, 21.5)
+        .add_bool("Running", true)
+        .unwrap();
+    let second = Payload::parse(&second.serialize().unwrap()).unwrap();
+
+    let (merged, _) = first.merge(&second).unwrap();
+    let payload = Payload::parse(&merged.serialize().unwrap()).unwrap();
+    assert_eq!(payload.seq(), Some(2));
+    assert_eq!(payload.metric_count(), 3);
+
+    let temperature = payload.metric_at(0).unwrap();
+    assert_eq!(temperature.name.as_deref(), Some("Temperature"));
+    assert_eq!(temperature.alias, Some(MetricAlias::new(1)));
+    assert_eq!(temperature.value, MetricValue::Double(21.5));
+    assert_eq!(payload.metric_at(1).unwrap().value, MetricValue::Int32(1));
+    assert_eq!(
+        payload.metric_at(2).unwrap().value,
+        MetricValue::Boolean(true)
+    );
+}
+
+/// Encodes a protobuf field, the way another Sparkplug stack would.
+fn field(buf: &mut Vec<u8>, number: u64, value: &[u8]) {
+    varint(buf, number << 3 | 2);
+    varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn varint_field(buf: &mut Vec<u8>, number: u64, value: u64) {
+    varint(buf, number << 3);
+    varint(buf, value);
+}
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// A payload holding a double, a null boolean and an aliased int8 metric,
+/// none of which but the first the C builder can produce.
+fn foreign_payload() -> Vec<u8> {
+    let mut temperature = Vec::new();
+    field(&mut temperature, 1, b"Temperature");
+    varint_field(&mut temperature, 4, DataType::Double as u64);
+    varint(&mut temperature, 13 << 3 | 1);
+    temperature.extend_from_slice(&20.5f64.to_le_bytes());
+
+    let mut fault = Vec::new();
+    field(&mut fault, 1, b"Fault");
+    varint_field(&mut fault, 4, DataType::Boolean as u64);
+    varint_field(&mut fault, 7, 1);
+
+    let mut mode = Vec::new();
+    field(&mut mode, 1, b"Mode");
+    varint_field(&mut mode, 2, 5);
+    varint_field(&mut mode, 4, DataType::Int8 as u64);
+    varint_field(&mut mode, 10, 3);
+
+    let mut payload = Vec::new();
+    varint_field(&mut payload, 1, 1_700_000_000_000);
+    for metric in [temperature, fault, mode] {
+        field(&mut payload, 2, &metric);
+    }
+    varint_field(&mut payload, 3, 4);
+    payload
+}
+
+#[test]
+fn test_to_builder_skips_unsupported_metrics() {
+    use sparkplug_rs::Payload;
+
+    let payload = Payload::parse(&foreign_payload()).unwrap();
+    assert_eq!(payload.metric_count(), 3);
+
+    let (builder, skipped) = payload.to_builder().unwrap();
+    let names: Vec<_> = skipped.iter().map(|m| m.name.as_deref()).collect();
+    assert_eq!(names, [Some("Mode")]);
+    assert_eq!(skipped[0].value, MetricValue::Int8(3));
+
+    let copy = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    assert_eq!(copy.timestamp(), Some(1_700_000_000_000));
+    assert_eq!(copy.seq(), Some(4));
+    assert_eq!(copy.metric_count(), 2);
+    assert_eq!(copy.metric_at(0).unwrap().value, MetricValue::Double(20.5));
+    let fault = copy.metric_at(1).unwrap();
+    assert!(fault.is_null());
+    assert_eq!(fault.datatype, DataType::Boolean);
+
+    let mut ndata = PayloadBuilder::new().unwrap();
+    assert_eq!(ndata.extend_from(&payload).unwrap().len(), 1);
+    // The metrics that could be merged are kept
+    let merged = Payload::parse(&ndata.serialize().unwrap()).unwrap();
+    assert_eq!(merged.metric_count(), 2);
+}
+
+#[test]
+fn test_parsed_null_metric_keeps_datatype() {
+    use sparkplug_rs::Payload;
+
+    let payload = Payload::parse(&foreign_payload()).unwrap();
+    let fault = payload.metric_at(1).unwrap();
+    assert_eq!(fault.name.as_deref(), Some("Fault"));
+    assert!(fault.is_null());
+    assert_eq!(fault.datatype, DataType::Boolean);
+    assert_eq!(fault.value, MetricValue::Null);
+    assert!(payload.metric_ref_at(1).unwrap().is_null());
+
+    let temperature = payload.metric_at(0).unwrap();
+    assert!(!temperature.is_null());
+}
+
+#[test]
+fn test_null_metrics_round_trip() {
+    use sparkplug_rs::{DataPayloadBuilder, Payload};
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_null_with_alias("Pressure", 2, DataType::Float)
+        .unwrap()
+        .add_null("Status", DataType::String)
+        .unwrap()
+        .add_null_by_alias(3, DataType::DateTime);
+
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    assert_eq!(payload.metric_count(), 4);
+    assert!(!payload.metric_at(0).unwrap().is_null());
+    let expected = [
+        (Some("Pressure"), Some(2), DataType::Float),
+        (Some("Status"), None, DataType::String),
+        (None, Some(3), DataType::DateTime),
+    ];
+    for (index, (name, alias, datatype)) in expected.into_iter().enumerate() {
+        let metric = payload.metric_at(index + 1).unwrap();
+        assert_eq!(metric.name.as_deref(), name);
+        assert_eq!(metric.alias, alias.map(MetricAlias::new));
+        assert_eq!(metric.datatype, datatype);
+        assert_eq!(metric.value, MetricValue::Null);
+        assert!(metric.is_null());
+    }
+
+    // A parsed null metric is copied as a null.
+    let mut copy = PayloadBuilder::new().unwrap();
+    copy.add_metric(&payload.metric_at(1).unwrap()).unwrap();
+    let copy = Payload::parse(&copy.serialize().unwrap()).unwrap();
+    assert!(copy.metric_at(0).unwrap().is_null());
+    assert_eq!(copy.metric_at(0).unwrap().datatype, DataType::Float);
+
+    let mut data = DataPayloadBuilder::new().unwrap();
+    data.add_double(1, 21.0).add_null(2, DataType::Float);
+    let data = Payload::parse(&data.serialize().unwrap()).unwrap();
+    assert!(data.metric_at(1).unwrap().is_null());
+    assert_eq!(data.metric_at(1).unwrap().alias, Some(MetricAlias::new(2)));
+}
+
+#[test]
+fn test_extend_from_coalesces_samples() {
+    use sparkplug_rs::Payload;
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    for value in 1..=3 {
+        let mut sample = PayloadBuilder::new().unwrap();
+        sample.add_int32("Count", value).unwrap();
+        let sample = Payload::parse(&sample.serialize().unwrap()).unwrap();
+        assert!(builder.extend_from(&sample).unwrap().is_empty());
+    }
+
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    assert_eq!(payload.metric_count(), 1);
+    assert_eq!(payload.metric_at(0).unwrap().value, MetricValue::Int32(3));
+}
+
+#[test]
+fn test_metrics_resolved_fills_alias_names() {
+    use sparkplug_rs::Payload;
+
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_string("Serial", "P-101")
+        .unwrap();
+    let birth = Payload::parse(&birth.serialize().unwrap()).unwrap();
+    let aliases = birth.alias_map().unwrap();
+    assert_eq!(aliases.len(), 1);
+
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 21.0).add_int32_by_alias(9, 3);
+    let data = Payload::parse(&data.serialize().unwrap()).unwrap();
+
+    let metrics: Vec<Metric> = data
+        .metrics_resolved(&aliases)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(metrics[0].name.as_deref(), Some("Temperature"));
+    assert_eq!(metrics[0].value, MetricValue::Double(21.0));
+    assert_eq!(metrics[1].name, None);
+}
+
+#[test]
+fn test_to_metrics_decodes_all() {
+    use sparkplug_rs::Payload;
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    for i in 0..500 {
+        builder.add_int32(&format!("Tag{}", i), i).unwrap();
+    }
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+
+    let metrics = payload.to_metrics().unwrap();
+    assert_eq!(metrics.len(), 500);
+    assert_eq!(metrics[499].name.as_deref(), Some("Tag499"));
+    assert_eq!(metrics[499].value, MetricValue::Int32(499));
+}
+
+#[test]
+fn test_data_payload_builder_is_alias_only() {
+    use sparkplug_rs::{DataPayloadBuilder, Payload};
+
+    let mut data = DataPayloadBuilder::new().unwrap();
+    data.set_timestamp(1_700_000_000_000)
+        .add_double(1, 21.0)
+        .add_bool(MetricAlias::new(2), true);
+    let payload = Payload::parse(&data.serialize().unwrap()).unwrap();
+
+    assert_eq!(payload.timestamp(), Some(1_700_000_000_000));
+    for metric in payload.metrics() {
+        let metric = metric.unwrap();
+        assert_eq!(metric.name, None);
+        assert!(metric.alias.is_some());
+    }
+    assert_eq!(
+        payload.metric_at(0).unwrap().value,
+        MetricValue::Double(21.0)
+    );
+}
+
+#[test]
+fn test_birth_payload_builder_node() {
+    use sparkplug_rs::{BirthPayloadBuilder, Payload};
+
+    let mut birth = BirthPayloadBuilder::node(3).unwrap();
+    birth
+        .add_double("Temperature", 1, 20.5)
+        .unwrap()
+        .add_bool("Active", 2, true)
+        .unwrap();
+    let payload = Payload::parse(&birth.serialize().unwrap()).unwrap();
+
+    let names: Vec<String> = payload
+        .metrics()
+        .map(|m| m.unwrap().name.unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        ["bdSeq", "Node Control/Rebirth", "Temperature", "Active"]
+    );
+    assert_eq!(
+        payload.metric_at(2).unwrap().alias,
+        Some(MetricAlias::new(1))
+    );
+}
+
+#[test]
+fn test_birth_payload_builder_rejects_violations() {
+    use sparkplug_rs::validate::Violation;
+    use sparkplug_rs::BirthPayloadBuilder;
+
+    let mut birth = BirthPayloadBuilder::device().unwrap();
+    birth.add_int32("Count", 1, 0).unwrap();
+    assert!(matches!(
+        birth.add_int32("Other", 1, 0),
+        Err(Error::InvalidPayload(v)) if v == [Violation::DuplicateAlias(MetricAlias::new(1))]
+    ));
+    assert!(matches!(
+        birth.add_int32("Count", 2, 0),
+        Err(Error::InvalidPayload(v)) if v == [Violation::DuplicateName("Count".to_string())]
+    ));
+
+    let birth = BirthPayloadBuilder::node(300).unwrap();
+    assert!(matches!(
+        birth.serialize(),
+        Err(Error::InvalidPayload(v)) if v == [Violation::InvalidBdSeq]
+    ));
+}
@@ -0,0 +1,252 @@
+//! Tests for Publisher-side checks that run before anything is sent
+
+use sparkplug_rs::{
+    DataType, DeviceBirthPolicy, Error, Message, Metric, MetricValue, PayloadBuilder, Publisher,
+    PublisherConfig, PublisherHandle,
+};
+use std::sync::{Arc, Mutex};
+
+fn config() -> PublisherConfig {
+    PublisherConfig::new("tcp://localhost:1883", "test_client", "Group", "Node")
+}
+
+fn rejecting_config() -> PublisherConfig {
+    let mut config = config();
+    config.device_birth_policy = DeviceBirthPolicy::Reject;
+    config
+}
+
+#[test]
+fn test_default_device_birth_policy_is_unchecked() {
+    assert_eq!(config().device_birth_policy, DeviceBirthPolicy::Unchecked);
+}
+
+#[test]
+fn test_device_data_without_birth_is_rejected() {
+    let mut publisher = Publisher::new(rejecting_config()).unwrap();
+
+    assert!(!publisher.is_device_birthed("Motor01"));
+    assert!(matches!(
+        publisher.publish_device_data("Motor01", &[]),
+        Err(Error::DeviceNotBirthed { device_id }) if device_id == "Motor01"
+    ));
+}
+
+#[test]
+fn test_auto_birth_without_cached_birth_is_rejected() {
+    let mut config = config();
+    config.device_birth_policy = DeviceBirthPolicy::AutoBirth;
+    let mut publisher = Publisher::new(config).unwrap();
+
+    assert!(matches!(
+        publisher.publish_device_data("Motor01", &[]),
+        Err(Error::DeviceNotBirthed { .. })
+    ));
+}
+
+#[test]
+fn test_strict_mode_is_off_by_default() {
+    assert!(!config().strict);
+}
+
+#[test]
+fn test_strict_rejects_operations_before_connect() {
+    let mut config = config();
+    config.strict = true;
+    let mut publisher = Publisher::new(config).unwrap();
+
+    assert!(!publisher.is_connected());
+    assert!(!publisher.is_birthed());
+    assert!(matches!(
+        publisher.publish_data(&[]),
+        Err(Error::InvalidState {
+            operation: "publish_data",
+            ..
+        })
+    ));
+    assert!(matches!(
+        publisher.rebirth(),
+        Err(Error::InvalidState {
+            operation: "rebirth",
+            ..
+        })
+    ));
+    assert!(matches!(
+        publisher.publish_device_data("Motor01", &[]),
+        Err(Error::InvalidState { .. })
+    ));
+}
+
+#[test]
+fn test_raw_handle_is_exposed() {
+    let publisher = Publisher::new(config()).unwrap();
+    assert!(!publisher.as_raw_ptr().is_null());
+}
+
+#[test]
+fn test_backlog_starts_empty() {
+    let mut config = config();
+    config.store_and_forward = Some(100);
+    let mut publisher = Publisher::new(config).unwrap();
+
+    assert_eq!(publisher.backlog_len(), 0);
+    assert_eq!(publisher.backlog_dropped(), 0);
+    assert_eq!(publisher.backlog_rejected(), 0);
+    assert_eq!(publisher.flush_backlog().unwrap(), 0);
+}
+
+#[test]
+fn test_device_handle_uses_publisher_checks() {
+    let mut publisher = Publisher::new(rejecting_config()).unwrap();
+    let mut motor = publisher.device("Motor01");
+
+    assert_eq!(motor.id(), "Motor01");
+    assert!(!motor.is_birthed());
+    assert_eq!(motor.birth(), None);
+    assert!(matches!(
+        motor.publish_data(&[]),
+        Err(Error::DeviceNotBirthed { device_id }) if device_id == "Motor01"
+    ));
+}
+
+#[test]
+fn test_handle_command_ignores_other_messages() {
+    let mut publisher = Publisher::new(config()).unwrap();
+    let command = |topic: &str, rebirth: bool| {
+        let mut payload = PayloadBuilder::new().unwrap();
+        payload.add_node_control_rebirth(rebirth).unwrap();
+        Message {
+            topic: topic.to_string(),
+            payload_data: payload.serialize().unwrap(),
+        }
+    };
+
+    let other_node = command("spBv1.0/Group/NCMD/OtherNode", true);
+    let other_group = command("spBv1.0/OtherGroup/NCMD/Node", true);
+    let not_requested = command("spBv1.0/Group/NCMD/Node", false);
+    let data = command("spBv1.0/Group/NDATA/Node", true);
+    for message in [other_node, other_group, not_requested, data] {
+        assert!(!publisher.handle_command(&message).unwrap());
+    }
+}
+
+#[test]
+fn test_publish_metrics_rejects_unsupported_metrics() {
+    let mut publisher = Publisher::new(config()).unwrap();
+    let null = Metric {
+        name: Some("Temperature".to_string()),
+        alias: None,
+        timestamp: None,
+        datatype: DataType::Unknown,
+        value: MetricValue::Null,
+    };
+
+    assert!(matches!(
+        publisher.publish_data_metrics(std::slice::from_ref(&null)),
+        Err(Error::Unsupported(_))
+    ));
+    assert!(matches!(
+        publisher.publish_device_birth_metrics("Motor01", &[null]),
+        Err(Error::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_batch_checks_every_device_first() {
+    let mut publisher = Publisher::new(rejecting_config()).unwrap();
+    let batch = [("Motor01", vec![0u8]), ("Pump01", vec![0u8])];
+
+    assert!(matches!(
+        publisher.publish_device_data_batch(&batch),
+        Err(Error::DeviceNotBirthed { device_id }) if device_id == "Motor01"
+    ));
+}
+
+#[test]
+fn test_device_death_does_not_require_birth() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    let mut publisher = Publisher::dry_run(
+        config(),
+        Box::new(move |msg: Message| sink.lock().unwrap().push(msg.topic)),
+    )
+    .unwrap();
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_double_with_alias("Temperature", 1, 20.5).unwrap();
+    publisher.connect().unwrap();
+    publisher
+        .publish_birth(&birth.serialize().unwrap())
+        .unwrap();
+
+    // Only DDATA is checked against the device birth policy.
+    publisher.publish_device_death("Motor01").unwrap();
+    assert_eq!(
+        sent.lock().unwrap().last().unwrap(),
+        "spBv1.0/Group/DDEATH/Node/Motor01"
+    );
+    assert_eq!(publisher.birthed_devices().count(), 0);
+}
+
+#[test]
+fn test_publish_changed_checks_types_before_publishing() {
+    let mut publisher = Publisher::new(config()).unwrap();
+
+    assert_eq!(publisher.publish_changed::<u64>(&[]).unwrap(), 0);
+    assert!(matches!(
+        publisher.publish_changed(&[(1u64, MetricValue::String("idle".to_string()))]),
+        Err(Error::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_publisher_handle_clones_share_state() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<PublisherHandle>();
+
+    let handle = Publisher::new(config()).unwrap().into_handle();
+    let clone = handle.clone();
+    clone
+        .lock()
+        .register_metric("Temperature", 1, MetricValue::Double(20.5))
+        .unwrap();
+
+    assert_eq!(handle.lock().registry().len(), 1);
+}
+
+#[test]
+fn test_unchecked_rejects_only_dead_devices() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    let mut publisher = Publisher::dry_run(
+        config(),
+        Box::new(move |msg: Message| sink.lock().unwrap().push(msg.topic)),
+    )
+    .unwrap();
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_double_with_alias("Temperature", 1, 20.5).unwrap();
+    let birth = birth.serialize().unwrap();
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 21.0);
+    let data = data.serialize().unwrap();
+
+    publisher.connect().unwrap();
+    publisher.publish_birth(&birth).unwrap();
+    publisher.publish_device_birth("Motor01", &birth).unwrap();
+    publisher.rebirth().unwrap();
+
+    // Not birthed since the rebirth, but not dead either.
+    assert!(!publisher.is_device_birthed("Motor01"));
+    publisher.publish_device_data("Motor01", &data).unwrap();
+    assert_eq!(
+        sent.lock().unwrap().last().unwrap(),
+        "spBv1.0/Group/DDATA/Node/Motor01"
+    );
+
+    publisher.publish_device_death("Motor01").unwrap();
+    assert!(matches!(
+        publisher.publish_device_data("Motor01", &data),
+        Err(Error::DeviceNotBirthed { .. })
+    ));
+    publisher.publish_device_birth("Motor01", &birth).unwrap();
+    publisher.publish_device_data("Motor01", &data).unwrap();
+}
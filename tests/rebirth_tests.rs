@@ -0,0 +1,109 @@
+//! Tests for automatic rebirth requests
+
+use sparkplug_rs::{
+    AutoRebirth, Message, PayloadBuilder, RebirthReason, ReconnectPolicy, SequenceStatus,
+};
+use std::time::{Duration, Instant};
+
+fn message(message_type: &str, seq: u64) -> Message {
+    let mut payload = PayloadBuilder::new().unwrap();
+    payload.set_seq(seq);
+    let device = if message_type.starts_with('D') {
+        "/BESS"
+    } else {
+        ""
+    };
+    Message {
+        topic: format!("spBv1.0/Energy/{}/Gateway01{}", message_type, device),
+        payload_data: payload.serialize().unwrap(),
+    }
+}
+
+fn backoff(max_attempts: Option<u32>) -> ReconnectPolicy {
+    ReconnectPolicy {
+        initial_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(8),
+        multiplier: 2.0,
+        jitter: 0.0,
+        max_attempts,
+    }
+}
+
+#[test]
+fn test_data_from_unknown_node_requests_rebirth_with_backoff() {
+    let mut rebirth = AutoRebirth::new(backoff(None));
+    let start = Instant::now();
+
+    let request = rebirth.observe_at(&message("NDATA", 5), start).unwrap();
+    assert_eq!(request.group_id, "Energy");
+    assert_eq!(request.edge_node_id, "Gateway01");
+    assert_eq!(request.reason, RebirthReason::UnknownNode);
+    assert_eq!(request.attempt, 1);
+
+    // The second request waits 1s, the third 2s after that.
+    let ms = Duration::from_millis;
+    assert!(rebirth
+        .observe_at(&message("NDATA", 6), start + ms(500))
+        .is_none());
+    let request = rebirth.observe_at(&message("NDATA", 7), start + ms(1000));
+    assert_eq!(request.unwrap().attempt, 2);
+    assert!(rebirth
+        .observe_at(&message("NDATA", 8), start + ms(2500))
+        .is_none());
+    let request = rebirth.observe_at(&message("NDATA", 9), start + ms(3000));
+    assert_eq!(request.unwrap().attempt, 3);
+    assert_eq!(rebirth.requests("Energy", "Gateway01"), 3);
+}
+
+#[test]
+fn test_birth_resets_backoff_and_commands_are_ignored() {
+    let mut rebirth = AutoRebirth::new(backoff(None));
+    let now = Instant::now();
+
+    assert!(rebirth.observe_at(&message("NCMD", 0), now).is_none());
+    assert!(rebirth.observe_at(&message("DDATA", 3), now).is_some());
+    assert!(!rebirth.is_birthed("Energy", "Gateway01"));
+
+    assert!(rebirth.observe_at(&message("NBIRTH", 0), now).is_none());
+    assert!(rebirth.is_birthed("Energy", "Gateway01"));
+    assert_eq!(rebirth.requests("Energy", "Gateway01"), 0);
+    assert!(rebirth.observe_at(&message("NDATA", 1), now).is_none());
+    assert!(rebirth.observe_at(&message("NDATA", 2), now).is_none());
+}
+
+#[test]
+fn test_sequence_gap_requests_rebirth() {
+    let mut rebirth = AutoRebirth::new(backoff(None));
+    let now = Instant::now();
+
+    rebirth.observe_at(&message("NBIRTH", 0), now);
+    rebirth.observe_at(&message("NDATA", 1), now);
+
+    let request = rebirth.observe_at(&message("NDATA", 4), now).unwrap();
+    assert_eq!(
+        request.reason,
+        RebirthReason::SequenceError(SequenceStatus::Gap {
+            expected: 2,
+            received: 4,
+        })
+    );
+    assert!(!rebirth.is_birthed("Energy", "Gateway01"));
+}
+
+#[test]
+fn test_attempt_limit_until_death() {
+    let mut rebirth = AutoRebirth::new(backoff(Some(2)));
+    let start = Instant::now();
+    let later = start + Duration::from_secs(60);
+
+    assert!(rebirth.observe_at(&message("NDATA", 0), start).is_some());
+    assert!(rebirth.observe_at(&message("NDATA", 1), later).is_some());
+    assert!(rebirth
+        .observe_at(&message("NDATA", 2), later + Duration::from_secs(60))
+        .is_none());
+
+    // A death resets the node: the next data message is answered again.
+    rebirth.observe_at(&message("NDEATH", 0), later);
+    let request = rebirth.observe_at(&message("NDATA", 3), later).unwrap();
+    assert_eq!(request.attempt, 1);
+}
@@ -0,0 +1,96 @@
+//! Tests for the reconnect backoff policy
+
+use sparkplug_rs::{Error, PublisherConfig, ReconnectPolicy, SubscriberConfig};
+use std::time::Duration;
+
+fn policy() -> ReconnectPolicy {
+    ReconnectPolicy {
+        initial_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(1000),
+        multiplier: 2.0,
+        jitter: 0.0,
+        max_attempts: Some(3),
+    }
+}
+
+#[test]
+fn test_exponential_delay_is_capped() {
+    let policy = policy();
+    let delays: Vec<_> = (0..6).map(|n| policy.delay(n).as_millis()).collect();
+    assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+}
+
+#[test]
+fn test_delay_after_many_retries_is_capped() {
+    let zero = ReconnectPolicy {
+        initial_delay: Duration::ZERO,
+        ..policy()
+    };
+    let slow = ReconnectPolicy {
+        jitter: 0.2,
+        ..policy()
+    };
+    for retry in [1023, 1024, 2000, u32::MAX] {
+        assert!(zero.base_delay(retry) <= zero.max_delay);
+        assert!(zero.delay(retry) <= zero.max_delay);
+        assert_eq!(slow.base_delay(retry), slow.max_delay);
+    }
+}
+
+#[test]
+fn test_jitter_stays_within_bounds() {
+    let policy = ReconnectPolicy {
+        jitter: 0.2,
+        ..policy()
+    };
+    for retry in 0..20 {
+        let base = policy.base_delay(retry).as_secs_f64();
+        let delay = policy.delay(retry).as_secs_f64();
+        assert!(delay >= base * 0.8 - 1e-9 && delay <= base * 1.2 + 1e-9);
+    }
+}
+
+#[test]
+fn test_retry_stops_after_max_attempts() {
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(1),
+        ..policy()
+    };
+    let mut attempts = 0;
+    let result: Result<(), Error> = policy.retry(|| {
+        attempts += 1;
+        Err(Error::ConnectionFailed("refused".to_string()))
+    });
+
+    assert_eq!(attempts, 3);
+    assert!(matches!(result, Err(Error::ConnectionFailed(_))));
+}
+
+#[test]
+fn test_retry_returns_first_success() {
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_millis(1),
+        max_attempts: None,
+        ..policy()
+    };
+    let mut attempts = 0;
+    let result = policy.retry(|| {
+        attempts += 1;
+        if attempts < 3 {
+            Err(Error::ConnectionFailed("refused".to_string()))
+        } else {
+            Ok(attempts)
+        }
+    });
+
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn test_configs_default_to_single_attempt() {
+    let publisher = PublisherConfig::new("tcp://localhost:1883", "p", "G", "N");
+    let subscriber = SubscriberConfig::new("tcp://localhost:1883", "s", "G");
+    assert!(publisher.reconnect.is_none());
+    assert!(subscriber.reconnect.is_none());
+}
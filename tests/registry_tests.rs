@@ -0,0 +1,78 @@
+//! Tests for declared node metrics
+
+use sparkplug_rs::validate::Violation;
+use sparkplug_rs::{Error, MetricAlias, MetricRegistry, MetricValue, Payload, PayloadBuilder};
+
+fn registry() -> MetricRegistry {
+    let mut registry = MetricRegistry::new();
+    registry
+        .register("Temperature", 1, MetricValue::Double(20.5))
+        .unwrap();
+    registry
+        .register("Active", 2, MetricValue::Boolean(true))
+        .unwrap();
+    registry
+}
+
+fn parse(builder: &PayloadBuilder) -> Payload {
+    Payload::parse(&builder.serialize().unwrap()).unwrap()
+}
+
+#[test]
+fn test_register_rejects_duplicates() {
+    let mut registry = registry();
+    assert!(matches!(
+        registry.register("Temperature", 3, MetricValue::Int32(0)),
+        Err(Error::InvalidPayload(v)) if v == [Violation::DuplicateName("Temperature".to_string())]
+    ));
+    assert!(matches!(
+        registry.register("Pressure", 1, MetricValue::Int32(0)),
+        Err(Error::InvalidPayload(v)) if v == [Violation::DuplicateAlias(MetricAlias::new(1))]
+    ));
+    assert_eq!(registry.len(), 2);
+}
+
+#[test]
+fn test_birth_payload_from_registry() {
+    let birth = parse(&registry().birth_payload(4).unwrap());
+    let metrics = birth.to_metrics().unwrap();
+
+    assert_eq!(metrics.len(), 4);
+    assert_eq!(metrics[0].name.as_deref(), Some("bdSeq"));
+    assert_eq!(metrics[0].value, MetricValue::UInt64(4));
+    assert_eq!(metrics[2].name.as_deref(), Some("Temperature"));
+    assert_eq!(metrics[2].alias, Some(MetricAlias::new(1)));
+    assert_eq!(metrics[3].value, MetricValue::Boolean(true));
+}
+
+#[test]
+fn test_check_data_tracks_latest_values() {
+    let mut registry = registry();
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 22.0);
+    registry.check_data(&parse(&data)).unwrap();
+
+    assert_eq!(registry.metrics()[0].value, MetricValue::Double(22.0));
+    let birth = parse(&registry.birth_payload(0).unwrap());
+    assert_eq!(birth.metric_at(2).unwrap().value, MetricValue::Double(22.0));
+}
+
+#[test]
+fn test_check_data_rejects_undeclared_metrics() {
+    let mut registry = registry();
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 22.0)
+        .add_int32_by_alias(9, 1)
+        .add_int32("Pressure", 3)
+        .unwrap();
+
+    assert!(matches!(
+        registry.check_data(&parse(&data)),
+        Err(Error::InvalidPayload(v)) if v == [
+            Violation::UndeclaredAlias(MetricAlias::new(9)),
+            Violation::UndeclaredName("Pressure".to_string()),
+        ]
+    ));
+    // Rejected payloads do not update values.
+    assert_eq!(registry.metrics()[0].value, MetricValue::Double(20.5));
+}
@@ -0,0 +1,129 @@
+//! Tests for sequence number tracking
+
+use sparkplug_rs::{MessageType, SequenceStatus, SequenceTracker};
+
+#[test]
+fn test_in_order_sequence() {
+    let mut tracker = SequenceTracker::new();
+    assert_eq!(tracker.check(MessageType::NBirth, 0), SequenceStatus::Reset);
+    assert_eq!(
+        tracker.check(MessageType::DBirth, 1),
+        SequenceStatus::InOrder
+    );
+    assert_eq!(
+        tracker.check(MessageType::NData, 2),
+        SequenceStatus::InOrder
+    );
+    assert_eq!(
+        tracker.check(MessageType::DData, 3),
+        SequenceStatus::InOrder
+    );
+    assert_eq!(tracker.last(), Some(3));
+    assert_eq!(tracker.expected(), Some(4));
+}
+
+#[test]
+fn test_wrap_from_255_to_0() {
+    let mut tracker = SequenceTracker::new();
+    tracker.check(MessageType::NBirth, 0);
+    for seq in 1..=255 {
+        assert_eq!(
+            tracker.check(MessageType::NData, seq),
+            SequenceStatus::InOrder
+        );
+    }
+    assert_eq!(
+        tracker.check(MessageType::NData, 0),
+        SequenceStatus::InOrder
+    );
+    assert_eq!(
+        tracker.check(MessageType::NData, 1),
+        SequenceStatus::InOrder
+    );
+}
+
+#[test]
+fn test_gap_after_255() {
+    let mut tracker = SequenceTracker::new();
+    tracker.check(MessageType::NBirth, 0);
+    for seq in 1..=255 {
+        tracker.check(MessageType::NData, seq);
+    }
+    assert_eq!(
+        tracker.check(MessageType::NData, 1),
+        SequenceStatus::Gap {
+            expected: 0,
+            received: 1
+        }
+    );
+}
+
+#[test]
+fn test_gap_resynchronizes() {
+    let mut tracker = SequenceTracker::new();
+    tracker.check(MessageType::NBirth, 0);
+    assert_eq!(
+        tracker.check(MessageType::NData, 5),
+        SequenceStatus::Gap {
+            expected: 1,
+            received: 5
+        }
+    );
+    assert_eq!(
+        tracker.check(MessageType::NData, 6),
+        SequenceStatus::InOrder
+    );
+}
+
+#[test]
+fn test_rebirth_resets_sequence() {
+    let mut tracker = SequenceTracker::new();
+    tracker.check(MessageType::NBirth, 0);
+    tracker.check(MessageType::NData, 1);
+    tracker.check(MessageType::NData, 2);
+
+    assert_eq!(tracker.check(MessageType::NBirth, 0), SequenceStatus::Reset);
+    assert_eq!(
+        tracker.check(MessageType::NData, 1),
+        SequenceStatus::InOrder
+    );
+}
+
+#[test]
+fn test_invalid_birth_and_duplicates() {
+    let mut tracker = SequenceTracker::new();
+    assert_eq!(
+        tracker.check(MessageType::NBirth, 7),
+        SequenceStatus::InvalidBirth { received: 7 }
+    );
+    assert_eq!(
+        tracker.check(MessageType::NData, 8),
+        SequenceStatus::InOrder
+    );
+    assert_eq!(
+        tracker.check(MessageType::NData, 8),
+        SequenceStatus::Duplicate
+    );
+    assert!(!SequenceStatus::Duplicate.is_ok());
+}
+
+#[test]
+fn test_untracked_and_out_of_range() {
+    let mut tracker = SequenceTracker::new();
+    assert_eq!(
+        tracker.check(MessageType::NData, 42),
+        SequenceStatus::Untracked
+    );
+    assert_eq!(
+        tracker.check(MessageType::NData, 256),
+        SequenceStatus::OutOfRange(256)
+    );
+    assert_eq!(
+        tracker.check(MessageType::NDeath, 0),
+        SequenceStatus::InOrder
+    );
+    assert_eq!(tracker.last(), Some(42));
+
+    tracker.reset();
+    assert_eq!(tracker.last(), None);
+}
@@ -0,0 +1,48 @@
+//! Tests for serde support of metrics and payload snapshots
+
+#![cfg(feature = "serde")]
+
+use sparkplug_rs::{DataType, Metric, MetricAlias, MetricValue, Payload, PayloadBuilder};
+
+#[test]
+fn test_metric_serde_round_trip() {
+    let metric = Metric {
+        name: Some("Temperature".to_string()),
+        alias: Some(MetricAlias::new(1)),
+        timestamp: Some(1_700_000_000_000),
+        datatype: DataType::Double,
+        value: MetricValue::Double(20.5),
+    };
+
+    let json = serde_json::to_string(&metric).unwrap();
+    let decoded: Metric = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.name, metric.name);
+    assert_eq!(decoded.alias, metric.alias);
+    assert_eq!(decoded.timestamp, metric.timestamp);
+    assert_eq!(decoded.datatype, DataType::Double);
+    assert_eq!(decoded.value, MetricValue::Double(20.5));
+}
+
+#[test]
+fn test_alias_serializes_as_number() {
+    assert_eq!(serde_json::to_string(&MetricAlias::new(7)).unwrap(), "7");
+}
+
+#[test]
+fn test_payload_snapshot() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.set_timestamp(1_700_000_000_000).set_seq(4);
+    builder.add_int32("Count", 3).unwrap();
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+
+    let snapshot = payload.snapshot().unwrap();
+    assert_eq!(snapshot.timestamp, Some(1_700_000_000_000));
+    assert_eq!(snapshot.seq, Some(4));
+    assert_eq!(snapshot.metrics.len(), 1);
+    assert_eq!(snapshot.metrics[0].value, MetricValue::Int32(3));
+
+    let value = serde_json::to_value(&snapshot).unwrap();
+    assert_eq!(value["seq"], 4);
+    assert_eq!(value["metrics"][0]["name"], "Count");
+    assert_eq!(value["metrics"][0]["value"]["Int32"], 3);
+}
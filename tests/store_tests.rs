@@ -0,0 +1,301 @@
+//! Tests for the host-side MetricStore and alarm detection
+
+use sparkplug_rs::alarm::{is_alarm_tag, Alarm, AlarmSeverity, AlarmTransition};
+use sparkplug_rs::{Message, MetricStore, MetricValue, PayloadBuilder, Quality};
+use std::sync::{Arc, Mutex};
+
+fn message(topic: &str, builder: &PayloadBuilder) -> Message {
+    Message {
+        topic: topic.to_string(),
+        payload_data: builder.serialize().unwrap(),
+    }
+}
+
+#[test]
+fn test_store_resolves_aliases_from_birth() {
+    let mut store = MetricStore::new();
+
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth
+        .add_double_with_alias("DATA/BESS_SOC_ACT", 200, 50.0)
+        .unwrap();
+    store
+        .ingest(&message("spBv1.0/VPP/DBIRTH/BAL01/BESS", &birth))
+        .unwrap();
+
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(200, 51.5);
+    let updated = store
+        .ingest(&message("spBv1.0/VPP/DDATA/BAL01/BESS", &data))
+        .unwrap();
+
+    assert_eq!(updated.len(), 1);
+    assert_eq!(
+        store.resolve_alias("VPP", "BAL01", Some("BESS"), 200),
+        Some("DATA/BESS_SOC_ACT")
+    );
+    let sample = store
+        .get("VPP", "BAL01", Some("BESS"), "DATA/BESS_SOC_ACT")
+        .unwrap();
+    assert_eq!(sample.value, MetricValue::Double(51.5));
+}
+
+#[test]
+fn test_store_history_ring_buffer() {
+    let mut store = MetricStore::with_history(3);
+
+    for value in 1..=5 {
+        let mut data = PayloadBuilder::new().unwrap();
+        data.set_timestamp(value as u64 * 1_000)
+            .add_int32("Level", value)
+            .unwrap();
+        store
+            .ingest(&message("spBv1.0/VPP/NDATA/BAL01", &data))
+            .unwrap();
+    }
+
+    let history = store.history("VPP", "BAL01", None, "Level").unwrap();
+    let values: Vec<_> = history.iter().map(|s| s.value.clone()).collect();
+    assert_eq!(
+        values,
+        vec![
+            MetricValue::Int32(3),
+            MetricValue::Int32(4),
+            MetricValue::Int32(5)
+        ]
+    );
+    assert_eq!(history.back().unwrap().timestamp, Some(5_000));
+
+    store.set_history_depth(1);
+    assert_eq!(
+        store.history("VPP", "BAL01", None, "Level").unwrap().len(),
+        1
+    );
+    store.set_history_depth(0);
+    assert!(store.history("VPP", "BAL01", None, "Level").is_none());
+}
+
+#[test]
+fn test_store_skips_unknown_alias() {
+    let mut store = MetricStore::new();
+
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double_by_alias(1, 1.0);
+    let updated = store
+        .ingest(&message("spBv1.0/VPP/NDATA/BAL01", &data))
+        .unwrap();
+
+    assert!(updated.is_empty());
+    assert!(store.is_empty());
+}
+
+#[test]
+fn test_store_ignores_state_messages() {
+    let mut store = MetricStore::new();
+    let msg = Message {
+        topic: "STATE/Host01".to_string(),
+        payload_data: b"ONLINE".to_vec(),
+    };
+    assert!(store.ingest(&msg).unwrap().is_empty());
+}
+
+#[test]
+fn test_alarm_transitions() {
+    let mut store = MetricStore::new();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    store.on_alarm(Box::new(move |event| {
+        events_clone.lock().unwrap().push(event.clone());
+    }));
+
+    let alarm = Alarm::new("HighTemp", AlarmSeverity::High).with_setpoint(85.0);
+
+    let mut birth = PayloadBuilder::new().unwrap();
+    alarm.add_to_birth(&mut birth, false).unwrap();
+    store
+        .ingest(&message("spBv1.0/Plant/NBIRTH/Node1", &birth))
+        .unwrap();
+    assert!(events.lock().unwrap().is_empty());
+
+    for active in [true, true, false] {
+        let mut data = PayloadBuilder::new().unwrap();
+        alarm.add_to_data(&mut data, active).unwrap();
+        store
+            .ingest(&message("spBv1.0/Plant/NDATA/Node1", &data))
+            .unwrap();
+    }
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].transition, AlarmTransition::Raised);
+    assert_eq!(events[0].key.name, "Alarms/HighTemp");
+    assert_eq!(events[0].severity, Some(AlarmSeverity::High));
+    assert_eq!(events[0].setpoint, Some(85.0));
+    assert_eq!(events[1].transition, AlarmTransition::Cleared);
+}
+
+#[test]
+fn test_is_alarm_tag() {
+    assert!(is_alarm_tag("Alarms/HighTemp"));
+    assert!(is_alarm_tag("Alarms/Line1/HighTemp"));
+    assert!(is_alarm_tag("Alarms/HighSeverity"));
+    assert!(!is_alarm_tag("Alarms/HighTemp/Severity"));
+    assert!(!is_alarm_tag("Alarms/HighTemp/Setpoint"));
+    assert!(!is_alarm_tag("Alarms/"));
+    assert!(!is_alarm_tag("Temperature"));
+}
+
+#[test]
+fn test_alarm_severity_codes() {
+    for severity in [
+        AlarmSeverity::Low,
+        AlarmSeverity::Medium,
+        AlarmSeverity::High,
+        AlarmSeverity::Critical,
+    ] {
+        assert_eq!(AlarmSeverity::from_code(severity.code()), Some(severity));
+    }
+    assert_eq!(AlarmSeverity::from_code(42), None);
+}
+
+#[test]
+fn test_wildcard_watch_across_nodes_and_devices() {
+    let mut store = MetricStore::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+    let id = store.watch(
+        "VPP_R2/*/DATA/BESS_P_ACT",
+        Box::new(move |key, sample| {
+            seen_clone
+                .lock()
+                .unwrap()
+                .push((key.to_string(), sample.value.clone()));
+        }),
+    );
+
+    for (topic, power) in [
+        ("spBv1.0/VPP_R2/NDATA/BAL01", 10.0),
+        ("spBv1.0/VPP_R2/DDATA/BAL02/BESS", 20.0),
+        ("spBv1.0/VPP4S_R2/NDATA/CBHS01", 30.0),
+    ] {
+        let mut data = PayloadBuilder::new().unwrap();
+        data.add_double("DATA/BESS_P_ACT", power)
+            .unwrap()
+            .add_double("DATA/BESS_SOC_ACT", 50.0)
+            .unwrap();
+        store.ingest(&message(topic, &data)).unwrap();
+    }
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            (
+                "VPP_R2/BAL01/DATA/BESS_P_ACT".to_string(),
+                MetricValue::Double(10.0)
+            ),
+            (
+                "VPP_R2/BAL02/BESS/DATA/BESS_P_ACT".to_string(),
+                MetricValue::Double(20.0)
+            ),
+        ]
+    );
+
+    assert!(store.unwatch(id));
+    assert!(!store.unwatch(id));
+}
+
+#[test]
+fn test_single_character_watch() {
+    let mut store = MetricStore::new();
+    let count = Arc::new(Mutex::new(0));
+    let count_clone = count.clone();
+    store.watch(
+        "Plant/Node?/Temp",
+        Box::new(move |_, _| *count_clone.lock().unwrap() += 1),
+    );
+
+    for topic in [
+        "spBv1.0/Plant/NDATA/Node1",
+        "spBv1.0/Plant/NDATA/Node2",
+        "spBv1.0/Plant/NDATA/Node10",
+    ] {
+        let mut data = PayloadBuilder::new().unwrap();
+        data.add_double("Temp", 1.0).unwrap();
+        store.ingest(&message(topic, &data)).unwrap();
+    }
+
+    assert_eq!(*count.lock().unwrap(), 2);
+}
+
+#[test]
+fn test_watch_metric_channel() {
+    let mut store = MetricStore::new();
+    let rx = store.watch_metric("Plant", "Node1", None, "Temp");
+
+    for (node, temp) in [("Node1", 20.0), ("Node2", 99.0), ("Node1", 21.0)] {
+        let mut data = PayloadBuilder::new().unwrap();
+        data.set_timestamp(1_000);
+        data.add_double("Temp", temp).unwrap();
+        store
+            .ingest(&message(&format!("spBv1.0/Plant/NDATA/{}", node), &data))
+            .unwrap();
+    }
+
+    let samples: Vec<_> = rx.try_iter().collect();
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0].value, MetricValue::Double(20.0));
+    assert_eq!(samples[1].value, MetricValue::Double(21.0));
+    assert_eq!(samples[1].timestamp, Some(1_000));
+
+    drop(rx);
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double("Temp", 22.0).unwrap();
+    store
+        .ingest(&message("spBv1.0/Plant/NDATA/Node1", &data))
+        .unwrap();
+}
+
+#[test]
+fn test_death_marks_metrics_stale() {
+    let mut store = MetricStore::new();
+    for topic in [
+        "spBv1.0/Plant/NDATA/Node1",
+        "spBv1.0/Plant/DDATA/Node1/Pump",
+        "spBv1.0/Plant/DDATA/Node1/Fan",
+        "spBv1.0/Plant/NDATA/Node2",
+    ] {
+        let mut data = PayloadBuilder::new().unwrap();
+        data.add_double("Temp", 20.0).unwrap();
+        store.ingest(&message(topic, &data)).unwrap();
+    }
+    let quality = |store: &MetricStore, node: &str, device: Option<&str>| {
+        store.get("Plant", node, device, "Temp").unwrap().quality
+    };
+
+    let death = PayloadBuilder::new().unwrap();
+    let stale = store
+        .ingest(&message("spBv1.0/Plant/DDEATH/Node1/Pump", &death))
+        .unwrap();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(quality(&store, "Node1", Some("Pump")), Quality::Stale);
+    assert_eq!(quality(&store, "Node1", Some("Fan")), Quality::Good);
+
+    let stale = store
+        .ingest(&message("spBv1.0/Plant/NDEATH/Node1", &death))
+        .unwrap();
+    assert_eq!(stale.len(), 2);
+    assert_eq!(quality(&store, "Node1", None), Quality::Stale);
+    assert_eq!(quality(&store, "Node1", Some("Fan")), Quality::Stale);
+    assert_eq!(quality(&store, "Node2", None), Quality::Good);
+    assert_eq!(
+        store.get("Plant", "Node1", None, "Temp").unwrap().value,
+        MetricValue::Double(20.0)
+    );
+
+    let mut data = PayloadBuilder::new().unwrap();
+    data.add_double("Temp", 21.0).unwrap();
+    store
+        .ingest(&message("spBv1.0/Plant/NDATA/Node1", &data))
+        .unwrap();
+    assert_eq!(quality(&store, "Node1", None), Quality::Good);
+}
@@ -0,0 +1,19 @@
+//! Tests for async message streams
+#![cfg(feature = "async")]
+
+use futures_util::{FutureExt, StreamExt};
+use sparkplug_rs::{Message, Subscriber, SubscriberConfig};
+
+#[test]
+fn test_stream_ends_when_subscriber_is_dropped() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "stream_test", "Energy");
+    let mut subscriber = Subscriber::new(config, Box::new(|_: Message| {})).unwrap();
+    let mut first = subscriber.messages();
+    let mut second = subscriber.messages();
+
+    assert!(first.next().now_or_never().is_none());
+    drop(subscriber);
+
+    assert!(matches!(first.next().now_or_never(), Some(None)));
+    assert!(matches!(second.next().now_or_never(), Some(None)));
+}
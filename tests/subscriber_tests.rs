@@ -0,0 +1,134 @@
+//! Tests for Subscriber message delivery that do not need a broker
+
+use sparkplug_rs::{Message, MessageHandler, PausePolicy, Subscriber, SubscriberConfig};
+
+#[test]
+fn test_channel_disconnects_when_subscriber_is_dropped() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "channel_test", "Energy");
+    let (subscriber, messages) = Subscriber::with_channel(config).unwrap();
+
+    assert!(messages.try_recv().is_err());
+    drop(subscriber);
+
+    assert!(messages.recv().is_err());
+}
+
+#[test]
+fn test_typed_handlers_register_alongside_callback() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "handler_test", "Energy");
+    let mut subscriber = Subscriber::new(config, Box::new(|_| {})).unwrap();
+
+    subscriber.on_nbirth(Box::new(|topic, payload| {
+        let _ = (topic.edge_node_id(), payload.metric_count());
+    }));
+    subscriber.on_ndata(Box::new(|_, _| {}));
+    subscriber.on_dbirth(Box::new(|_, _| {}));
+    subscriber.on_ddata(Box::new(|_, _| {}));
+    subscriber.on_death(Box::new(|_, _| {}));
+    subscriber.on_state(Box::new(|topic, state| {
+        let _ = (topic.host_id(), state.online);
+    }));
+}
+
+#[test]
+fn test_resolve_alias_is_empty_before_any_birth() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "alias_test", "Energy");
+    let subscriber = Subscriber::new(config, Box::new(|_| {})).unwrap();
+
+    assert_eq!(
+        subscriber.resolve_alias("Energy", "Gateway01", None, 1u64),
+        None
+    );
+    assert_eq!(
+        subscriber.resolve_alias("Energy", "Gateway01", Some("BESS"), 1u64),
+        None
+    );
+}
+
+#[test]
+fn test_parsed_callback_subscriber_creation() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "parsed_test", "Energy");
+    let subscriber = Subscriber::with_parsed_callback(
+        config,
+        Box::new(|topic, payload| {
+            let _ = (topic.message_type(), payload.metric_count());
+        }),
+    );
+
+    assert!(subscriber.is_ok());
+}
+
+#[test]
+fn test_error_callback_can_be_set_and_cleared() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "error_test", "Energy");
+    let mut subscriber = Subscriber::new(config, Box::new(|_| {})).unwrap();
+
+    subscriber.on_error(Box::new(|msg, err| {
+        let _ = (msg.topic.len(), err.to_string());
+    }));
+    subscriber.clear_on_error();
+}
+
+#[test]
+fn test_pause_and_resume() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "pause_test", "Energy");
+    let mut subscriber = Subscriber::new(config, Box::new(|_| {})).unwrap();
+    assert!(!subscriber.is_paused());
+
+    subscriber.pause(PausePolicy::Drop);
+    subscriber.pause(PausePolicy::Buffer { capacity: 100 });
+    assert!(subscriber.is_paused());
+
+    assert_eq!(subscriber.resume(), 0);
+    assert!(!subscriber.is_paused());
+    assert_eq!(subscriber.resume(), 0);
+}
+
+struct Recorder {
+    messages: usize,
+}
+
+impl MessageHandler for Recorder {
+    fn on_message(&mut self, _message: Message) {
+        self.messages += 1;
+    }
+}
+
+#[test]
+fn test_subscriber_with_handler() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "handler_trait_test", "Energy");
+    let subscriber = Subscriber::with_handler(config, Recorder { messages: 0 });
+
+    assert!(subscriber.is_ok());
+}
+
+#[test]
+fn test_replace_and_clear_message_callback() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "swap_test", "Energy");
+    let (mut subscriber, messages) = Subscriber::with_channel(config).unwrap();
+
+    // Replacing the callback drops the channel's sender.
+    subscriber.set_message_callback(Box::new(|_| {}));
+    assert!(messages.recv().is_err());
+
+    subscriber.clear_message_callback();
+}
+
+#[test]
+fn test_value_store_is_optional() {
+    let config = SubscriberConfig::new("tcp://localhost:1883", "lkv_off", "Energy");
+    let subscriber = Subscriber::new(config, Box::new(|_| {})).unwrap();
+    assert!(subscriber.value_store().is_none());
+    assert!(subscriber
+        .value("Energy", "Gateway01", None, "Temperature")
+        .is_none());
+
+    let mut config = SubscriberConfig::new("tcp://localhost:1883", "lkv_on", "Energy");
+    config.store_values = true;
+    let subscriber = Subscriber::new(config, Box::new(|_| {})).unwrap();
+    let store = subscriber.value_store().unwrap();
+    assert!(store.lock().unwrap().is_empty());
+    assert!(subscriber
+        .value("Energy", "Gateway01", None, "Temperature")
+        .is_none());
+}
@@ -0,0 +1,71 @@
+//! Tests for timestamp conversion helpers and DateTime values
+
+use sparkplug_rs::time::{from_millis, now_millis, to_millis};
+use sparkplug_rs::{MetricValue, Payload, PayloadBuilder};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_millis_round_trip() {
+    let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+    assert_eq!(to_millis(time), 1_700_000_000_123);
+    assert_eq!(from_millis(1_700_000_000_123), time);
+}
+
+#[test]
+fn test_pre_epoch_maps_to_zero() {
+    assert_eq!(to_millis(UNIX_EPOCH - Duration::from_secs(1)), 0);
+}
+
+#[test]
+fn test_now_millis_is_after_2020() {
+    assert!(now_millis() > 1_577_836_800_000);
+}
+
+#[test]
+fn test_payload_time() {
+    let time = from_millis(1_700_000_000_000);
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.set_time(time).add_int32("Value", 1).unwrap();
+
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    assert_eq!(payload.timestamp(), Some(1_700_000_000_000));
+    assert_eq!(payload.time(), Some(time));
+}
+
+#[test]
+fn test_datetime_value_accessors() {
+    let value = MetricValue::DateTime(1_700_000_000_000);
+    assert_eq!(value.as_datetime_millis(), Some(1_700_000_000_000));
+    assert_eq!(value.as_system_time(), Some(from_millis(1_700_000_000_000)));
+    assert_eq!(MetricValue::UInt64(1).as_datetime_millis(), None);
+}
+
+#[test]
+fn test_add_datetime_publishes_millis() {
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder
+        .add_datetime("LastService", from_millis(1_700_000_000_000))
+        .unwrap();
+
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    let metric = payload.metric_at(0).unwrap();
+    assert_eq!(metric.value, MetricValue::UInt64(1_700_000_000_000));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_datetime_chrono_interop() {
+    let time = chrono::DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+    assert_eq!(
+        MetricValue::DateTime(1_700_000_000_123).as_chrono(),
+        Some(time)
+    );
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.add_datetime("LastService", time).unwrap();
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    assert_eq!(
+        payload.metric_at(0).unwrap().value,
+        MetricValue::UInt64(1_700_000_000_123)
+    );
+}
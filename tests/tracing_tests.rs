@@ -0,0 +1,93 @@
+//! Tests for the fields recorded on publisher tracing spans
+#![cfg(feature = "tracing")]
+
+use sparkplug_rs::{Message, PayloadBuilder, Publisher, PublisherConfig};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+type Fields = HashMap<String, String>;
+
+/// Collects the fields of the last span with each name.
+#[derive(Clone, Default)]
+struct SpanFields(Arc<Mutex<HashMap<&'static str, Fields>>>);
+
+impl SpanFields {
+    fn get(&self, span: &str) -> Fields {
+        self.0.lock().unwrap()[span].clone()
+    }
+}
+
+struct Collect<'a>(&'a mut Fields);
+
+impl Visit for Collect<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: tracing::Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanFields {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+        let mut spans = self.0.lock().unwrap();
+        let fields = spans.entry(attrs.metadata().name()).or_default();
+        fields.clear();
+        attrs.record(&mut Collect(fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let name = ctx.span(id).unwrap().name();
+        values.record(&mut Collect(self.0.lock().unwrap().get_mut(name).unwrap()));
+    }
+}
+
+fn payload() -> Vec<u8> {
+    let mut payload = PayloadBuilder::new().unwrap();
+    payload.add_double("Temperature", 20.5).unwrap();
+    payload.serialize().unwrap()
+}
+
+#[test]
+fn test_publish_spans_record_ids_and_sequence_numbers() {
+    let spans = SpanFields::default();
+    let subscriber = tracing_subscriber::registry().with(spans.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+        let config = PublisherConfig::new("tcp://localhost:1883", "dry", "Energy", "Gateway01");
+        let mut publisher = Publisher::dry_run(config, Box::new(|_: Message| {})).unwrap();
+        publisher.connect().unwrap();
+        publisher.publish_birth(&payload()).unwrap();
+        publisher
+            .publish_device_birth("Motor01", &payload())
+            .unwrap();
+        publisher
+            .publish_device_data("Motor01", &payload())
+            .unwrap();
+        publisher
+            .publish_node_command("Gateway02", &payload())
+            .unwrap();
+        publisher
+            .publish_device_command("Gateway02", "Pump01", &payload())
+            .unwrap();
+        publisher.publish_state_birth("SCADA01", 1_000).unwrap();
+    });
+
+    let data = spans.get("publish_device_data");
+    assert_eq!(data["device_id"], "Motor01");
+    assert_eq!(data["seq"], "2");
+    assert_eq!(data["bd_seq"], "0");
+    assert_eq!(spans.get("publish_birth")["seq"], "0");
+
+    let command = spans.get("publish_device_command");
+    assert_eq!(command["target_edge_node_id"], "Gateway02");
+    assert_eq!(command["target_device_id"], "Pump01");
+
+    let state = spans.get("publish_state_birth");
+    assert_eq!(state["host_id"], "SCADA01");
+    assert_eq!(state["timestamp"], "1000");
+}
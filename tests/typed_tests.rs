@@ -0,0 +1,78 @@
+//! Tests for #[derive(SparkplugMetrics)]
+
+#![cfg(feature = "derive")]
+
+use sparkplug_rs::{MetricAlias, MetricValue, Payload, PayloadBuilder, SparkplugMetrics};
+
+#[derive(SparkplugMetrics, Clone, PartialEq)]
+struct Pump {
+    #[sparkplug(name = "DATA/Speed", alias = 1)]
+    speed: f64,
+    #[sparkplug(alias = 2, readonly)]
+    running: bool,
+    #[sparkplug(name = "CFG/Label")]
+    label: String,
+    #[sparkplug(skip)]
+    #[allow(dead_code)]
+    polls: u32,
+}
+
+fn pump() -> Pump {
+    Pump {
+        speed: 1200.0,
+        running: true,
+        label: "P-101".to_string(),
+        polls: 0,
+    }
+}
+
+fn parse(builder: &PayloadBuilder) -> Payload {
+    Payload::parse(&builder.serialize().unwrap()).unwrap()
+}
+
+#[test]
+fn test_birth_contains_all_metrics() {
+    let payload = parse(&pump().to_birth_payload().unwrap());
+    let metrics: Vec<_> = payload.metrics().map(|m| m.unwrap()).collect();
+
+    assert_eq!(metrics.len(), 3);
+    assert_eq!(metrics[0].name.as_deref(), Some("DATA/Speed"));
+    assert_eq!(metrics[0].alias, Some(MetricAlias::new(1)));
+    assert_eq!(metrics[1].name.as_deref(), Some("running"));
+    assert_eq!(metrics[2].value, MetricValue::String("P-101".to_string()));
+}
+
+#[test]
+fn test_data_contains_changed_fields_only() {
+    let before = pump();
+    assert!(before.to_data_payload(&before).unwrap().is_none());
+
+    let mut after = before.clone();
+    after.speed = 900.0;
+    let payload = parse(&after.to_data_payload(&before).unwrap().unwrap());
+    let metrics: Vec<_> = payload.metrics().map(|m| m.unwrap()).collect();
+
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].name, None);
+    assert_eq!(metrics[0].alias, Some(MetricAlias::new(1)));
+    assert_eq!(metrics[0].value, MetricValue::Double(900.0));
+}
+
+#[test]
+fn test_apply_command_skips_readonly_fields() {
+    let mut cmd = PayloadBuilder::new().unwrap();
+    cmd.add_double("DATA/Speed", 600.0)
+        .unwrap()
+        .add_bool("running", false)
+        .unwrap()
+        .add_string("CFG/Label", "P-102")
+        .unwrap();
+
+    let mut pump = pump();
+    let applied = pump.apply_command(&parse(&cmd)).unwrap();
+
+    assert_eq!(applied, 2);
+    assert_eq!(pump.speed, 600.0);
+    assert!(pump.running);
+    assert_eq!(pump.label, "P-102");
+}
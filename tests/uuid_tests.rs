@@ -0,0 +1,31 @@
+//! Tests for uuid crate interop
+
+#![cfg(feature = "uuid")]
+
+use sparkplug_rs::{Error, MetricValue, Payload, PayloadBuilder};
+use uuid::Uuid;
+
+#[test]
+fn test_add_uuid_round_trip() {
+    let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+    let mut builder = PayloadBuilder::new().unwrap();
+    builder.add_uuid("Device/Id", id).unwrap();
+    let payload = Payload::parse(&builder.serialize().unwrap()).unwrap();
+    let metric = payload.metric_at(0).unwrap();
+
+    assert_eq!(
+        metric.value,
+        MetricValue::String("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+    );
+    assert_eq!(metric.value.as_uuid().unwrap(), Some(id));
+}
+
+#[test]
+fn test_as_uuid_validation() {
+    assert_eq!(MetricValue::Int32(1).as_uuid().unwrap(), None);
+    assert!(matches!(
+        MetricValue::String("not-a-uuid".to_string()).as_uuid(),
+        Err(Error::InvalidUuid(_))
+    ));
+}
@@ -0,0 +1,86 @@
+//! Tests for Sparkplug payload rule checks
+
+use sparkplug_rs::validate::{validate_birth, validate_data, validate_node_birth, Violation};
+use sparkplug_rs::{Error, MetricAlias, Payload, PayloadBuilder};
+
+fn parse(builder: &PayloadBuilder) -> Payload {
+    Payload::parse(&builder.serialize().unwrap()).unwrap()
+}
+
+fn violations(result: sparkplug_rs::Result<()>) -> Vec<Violation> {
+    match result {
+        Err(Error::InvalidPayload(violations)) => violations,
+        other => panic!("expected InvalidPayload, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_valid_node_birth() {
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth
+        .add_bd_seq(3)
+        .unwrap()
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_bool_with_alias("Active", 2, true)
+        .unwrap();
+    let birth = parse(&birth);
+
+    assert!(validate_birth(&birth).is_ok());
+    assert!(validate_node_birth(&birth).is_ok());
+}
+
+#[test]
+fn test_birth_violations() {
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth
+        .add_double_with_alias("Temperature", 1, 20.5)
+        .unwrap()
+        .add_double_with_alias("Temperature", 1, 21.0)
+        .unwrap()
+        .add_int32_by_alias(2, 7);
+
+    assert_eq!(
+        violations(validate_node_birth(&parse(&birth))),
+        vec![
+            Violation::DuplicateName("Temperature".to_string()),
+            Violation::DuplicateAlias(MetricAlias::new(1)),
+            Violation::MissingName { index: 2 },
+            Violation::MissingBdSeq,
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_bd_seq() {
+    let mut birth = PayloadBuilder::new().unwrap();
+    birth.add_bd_seq(256).unwrap();
+    assert_eq!(
+        violations(validate_node_birth(&parse(&birth))),
+        vec![Violation::InvalidBdSeq]
+    );
+}
+
+#[test]
+fn test_data_allows_alias_only_metrics() {
+    let mut data = PayloadBuilder::new().unwrap();
+    data.set_seq(12)
+        .add_double_by_alias(1, 20.5)
+        .add_int32("Count", 1)
+        .unwrap();
+    assert!(validate_data(&parse(&data)).is_ok());
+}
+
+#[test]
+fn test_data_violations() {
+    let mut data = PayloadBuilder::new().unwrap();
+    data.set_seq(300)
+        .add_double_by_alias(1, 20.5)
+        .add_double_by_alias(1, 21.0);
+
+    let err = validate_data(&parse(&data)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid payload: seq 300 is above 255; duplicate metric alias 1"
+    );
+}